@@ -0,0 +1,76 @@
+//! Measures the per query cost of parsing with a new parser for every query (how
+//! `CassandraAST::new` worked before `CqlParser`), with a reused `CqlParser`, and with the thread
+//! local default parser that `CassandraAST::new` now uses.  The last columns parse with the
+//! reused parser into the borrowed AST and into statement summaries.
+//!
+//! Run with `cargo bench`.
+use cql3_parser::cassandra_ast::CassandraAST;
+use cql3_parser::cql_parser::CqlParser;
+use std::time::{Duration, Instant};
+
+const QUERIES: [(&str, &str); 3] = [
+    ("short", "SELECT * FROM foo"),
+    (
+        "select",
+        "SELECT DISTINCT a, b AS c FROM ks.tbl WHERE a = 5 AND b IN (1, 2) ORDER BY b DESC LIMIT 10",
+    ),
+    (
+        "insert",
+        "INSERT INTO ks.tbl (a, b, c) VALUES (1, 'two', {'k':'v'}) IF NOT EXISTS USING TTL 86400",
+    ),
+];
+
+const WARM_UP: Duration = Duration::from_millis(500);
+const MEASUREMENT: Duration = Duration::from_secs(2);
+
+/// run `f` repeatedly and return the mean time of a call.
+fn measure(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    while start.elapsed() < WARM_UP {
+        f();
+    }
+    let mut iterations = 0;
+    let start = Instant::now();
+    while start.elapsed() < MEASUREMENT {
+        f();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    // `cargo test --benches` passes `--bench` only when benchmarks should run.
+    if !std::env::args().any(|arg| arg.eq("--bench")) {
+        return;
+    }
+    let creation = measure(|| {
+        CqlParser::new().unwrap();
+    });
+    println!("creating a parser: {:?}", creation);
+    println!(
+        "{:<8} {:>14} {:>14} {:>14} {:>14} {:>14}",
+        "query", "new parser", "reused", "default", "borrowed", "classify"
+    );
+    for (name, query) in QUERIES {
+        let new_parser = measure(|| {
+            CqlParser::new().unwrap().parse(query).unwrap();
+        });
+        let mut parser = CqlParser::new().unwrap();
+        let reused = measure(|| {
+            parser.parse(query).unwrap();
+        });
+        let default = measure(|| {
+            CassandraAST::new(query);
+        });
+        let borrowed = measure(|| {
+            parser.parse_borrowed(query).unwrap();
+        });
+        let classify = measure(|| {
+            parser.classify(query).unwrap();
+        });
+        println!(
+            "{:<8} {:>14?} {:>14?} {:>14?} {:>14?} {:>14?}",
+            name, new_parser, reused, default, borrowed, classify
+        );
+    }
+}
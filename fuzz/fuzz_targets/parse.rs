@@ -0,0 +1,19 @@
+#![no_main]
+
+use cql3_parser::cassandra_ast::CassandraAST;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing any text, and displaying the statements that were parsed, must not panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(ast) = CassandraAST::try_new(text) {
+            for statement in &ast.statements {
+                let _ = statement.statement.to_string();
+            }
+        }
+        let ast = CassandraAST::new(text);
+        for statement in &ast.statements {
+            let _ = statement.statement.to_string();
+        }
+    }
+});
@@ -1,4 +1,4 @@
-use crate::common::{DataType, FQName};
+use crate::common::{move_spans, DataType, FQName};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -14,6 +14,11 @@ pub struct Aggregate {
     pub init_cond: InitCondition,
 }
 
+move_spans!(Aggregate {
+    name, data_type, sfunc, stype, finalfunc;
+    or_replace, not_exists, init_cond
+});
+
 impl Display for Aggregate {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,4 +1,4 @@
-use crate::common::{DataType, Identifier};
+use crate::common::{move_spans, DataType, Identifier};
 use std::fmt::{Display, Formatter};
 
 /// data to alter a column type.
@@ -10,6 +10,8 @@ pub struct AlterColumnType {
     pub data_type: DataType,
 }
 
+move_spans!(AlterColumnType { data_type; name });
+
 impl Display for AlterColumnType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "ALTER {} TYPE {}", self.name, self.data_type)
@@ -1,4 +1,4 @@
-use crate::common::{FQName, WithItem};
+use crate::common::{move_spans, FQName, WithItem};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -11,6 +11,8 @@ pub struct AlterMaterializedView {
     pub with_clause: Vec<WithItem>,
 }
 
+move_spans!(AlterMaterializedView { name, with_clause });
+
 impl Display for AlterMaterializedView {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
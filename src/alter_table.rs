@@ -1,4 +1,6 @@
-use crate::common::{ColumnDefinition, FQName, Identifier, WithItem};
+use crate::common::{
+    move_spans, ColumnDefinition, FQName, FunctionCall, Identifier, MoveSpans, WithItem,
+};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -7,10 +9,14 @@ use std::fmt::{Display, Formatter};
 pub struct AlterTable {
     /// the name of the table.
     pub name: FQName,
+    /// only alter the table if it exists (Cassandra 4.1).
+    pub if_exists: bool,
     /// the table alteration operation.
     pub operation: AlterTableOperation,
 }
 
+move_spans!(AlterTable { name, operation; if_exists });
+
 /// table alteration operations
 #[derive(PartialEq, Debug, Clone)]
 pub enum AlterTableOperation {
@@ -24,6 +30,24 @@ pub enum AlterTableOperation {
     Rename((Identifier, Identifier)),
     /// add with element options.
     With(Vec<WithItem>),
+    /// mask a column `(column, function)` (Cassandra 5.0).
+    Mask((Identifier, FunctionCall)),
+    /// remove the mask from a column (Cassandra 5.0).
+    DropMask(Identifier),
+}
+
+impl MoveSpans for AlterTableOperation {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            AlterTableOperation::Add(columns) => columns.move_spans(offset),
+            AlterTableOperation::With(with_clause) => with_clause.move_spans(offset),
+            AlterTableOperation::Mask((_, mask)) => mask.move_spans(offset),
+            AlterTableOperation::DropColumns(_)
+            | AlterTableOperation::DropCompactStorage
+            | AlterTableOperation::Rename(_)
+            | AlterTableOperation::DropMask(_) => {}
+        }
+    }
 }
 
 impl Display for AlterTableOperation {
@@ -46,6 +70,10 @@ impl Display for AlterTableOperation {
                 "WITH {}",
                 with_element.iter().map(|x| x.to_string()).join(" AND ")
             ),
+            AlterTableOperation::Mask((column, function)) => {
+                write!(f, "ALTER {} MASKED WITH {}", column, function)
+            }
+            AlterTableOperation::DropMask(column) => write!(f, "ALTER {} DROP MASKED", column),
         }
     }
 }
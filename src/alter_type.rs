@@ -1,5 +1,5 @@
 use crate::alter_column::AlterColumnType;
-use crate::common::{ColumnDefinition, FQName, Identifier};
+use crate::common::{move_spans, ColumnDefinition, FQName, Identifier, MoveSpans};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -8,13 +8,23 @@ use std::fmt::{Display, Formatter};
 pub struct AlterType {
     /// the name of the type to alter
     pub name: FQName,
+    /// only alter the type if it exists (Cassandra 4.1).
+    pub if_exists: bool,
     /// the operation to perform on the type.
     pub operation: AlterTypeOperation,
 }
 
+move_spans!(AlterType { name, operation; if_exists });
+
 impl Display for AlterType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ALTER TYPE {} {}", self.name, self.operation)
+        write!(
+            f,
+            "ALTER TYPE {}{} {}",
+            if self.if_exists { "IF EXISTS " } else { "" },
+            self.name,
+            self.operation
+        )
     }
 }
 
@@ -29,6 +39,16 @@ pub enum AlterTypeOperation {
     Rename(Vec<(Identifier, Identifier)>),
 }
 
+impl MoveSpans for AlterTypeOperation {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            AlterTypeOperation::AlterColumnType(column) => column.move_spans(offset),
+            AlterTypeOperation::Add(columns) => columns.move_spans(offset),
+            AlterTypeOperation::Rename(_) => {}
+        }
+    }
+}
+
 impl Display for AlterTypeOperation {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1,3 +1,4 @@
+use crate::common::Bindable;
 use std::fmt::{Display, Formatter};
 
 #[derive(PartialEq, Debug, Clone)]
@@ -12,7 +13,7 @@ pub enum BatchType {
 pub struct BeginBatch {
     pub ty: BatchType,
     /// the optional timestamp for the `BEGIN BATCH` command
-    pub timestamp: Option<u64>,
+    pub timestamp: Option<Bindable<u64>>,
 }
 
 impl Display for BeginBatch {
@@ -23,7 +24,7 @@ impl Display for BeginBatch {
             BatchType::Unlogged => "UNLOGGED ",
         };
 
-        if let Some(timestamp) = self.timestamp {
+        if let Some(timestamp) = &self.timestamp {
             write!(f, "BEGIN {}BATCH USING TIMESTAMP {} ", modifiers, timestamp)
         } else {
             write!(f, "BEGIN {}BATCH ", modifiers)
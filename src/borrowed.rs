@@ -0,0 +1,1334 @@
+//! A variant of the AST that borrows its text from the query string.
+//!
+//! The types in this module mirror the owned types of the crate but hold `Cow<'a, str>` slices of
+//! the query rather than `String`s, so inspecting a query does not allocate for every identifier
+//! and constant.  `SELECT`, `INSERT`, `UPDATE`, `DELETE`, `USE` and `TRUNCATE` statements have a
+//! borrowed form, other statements are parsed into their owned form.  Every type has an
+//! `into_owned()` method that produces the equivalent owned type.
+use crate::begin_batch::BeginBatch;
+use crate::cassandra_ast::{self, BorrowedParser, Recovered};
+use crate::cassandra_statement;
+use crate::comment::Comment;
+use crate::common::{
+    self, eq_without_spans, Bindable, CqlDuration, IdentifierRef, RelationOperator, Span,
+    TtlTimestamp,
+};
+use crate::cql_parser::CqlParser;
+use crate::delete;
+use crate::dialect::Dialect;
+use crate::insert;
+use crate::parse_error::ParseError;
+use crate::select::{self, ArithmeticOperator};
+use crate::update;
+use std::borrow::Cow;
+use tree_sitter::{Node, Tree};
+
+/// A borrowed [common::Identifier].
+/// Quoted identifiers that contain escaped quotes (`""`) are unescaped into an owned string.
+#[derive(Debug, Clone)]
+pub enum Identifier<'a> {
+    /// This variant is case sensitive
+    /// "fOo""bAr""" is stored as fOo"bAr"
+    Quoted(Cow<'a, str>),
+    /// This variant is case insensitive
+    /// fOo_bAr is stored as fOo_bAr
+    Unquoted(Cow<'a, str>),
+}
+
+impl<'a> Identifier<'a> {
+    /// parses strings as returned by the parser into Quoted or Unquoted Identifiers.
+    /// See [common::Identifier::parse].
+    pub fn parse(text: &'a str) -> Identifier<'a> {
+        if text.starts_with('"') {
+            let mut chars = text.chars();
+            chars.next();
+            chars.next_back();
+            let txt = chars.as_str();
+            Identifier::Quoted(if txt.contains("\"\"") {
+                Cow::Owned(txt.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(txt)
+            })
+        } else {
+            Identifier::Unquoted(Cow::Borrowed(text))
+        }
+    }
+
+    /// the equivalent owned identifier.
+    pub fn into_owned(self) -> common::Identifier {
+        match self {
+            Identifier::Quoted(txt) => common::Identifier::Quoted(txt.into_owned()),
+            Identifier::Unquoted(txt) => common::Identifier::Unquoted(txt.into_owned()),
+        }
+    }
+
+    fn as_ref(&self) -> IdentifierRef<'_> {
+        match self {
+            Identifier::Quoted(txt) => IdentifierRef::Quoted(txt),
+            Identifier::Unquoted(txt) => IdentifierRef::Unquoted(txt),
+        }
+    }
+}
+
+impl PartialEq for Identifier<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl PartialEq<common::Identifier> for Identifier<'_> {
+    fn eq(&self, other: &common::Identifier) -> bool {
+        &self.as_ref() == other
+    }
+}
+
+/// A borrowed [common::FQName].
+#[derive(Debug, Clone)]
+pub struct FQName<'a> {
+    pub keyspace: Option<Identifier<'a>>,
+    pub name: Identifier<'a>,
+    /// the location of the name in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(FQName<'a> { keyspace, name; span });
+
+impl FQName<'_> {
+    /// the equivalent owned name.
+    pub fn into_owned(self) -> common::FQName {
+        common::FQName {
+            keyspace: self.keyspace.map(Identifier::into_owned),
+            name: self.name.into_owned(),
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [common::FunctionCall].
+#[derive(PartialEq, Debug, Clone)]
+pub struct FunctionCall<'a> {
+    /// the name of the function.
+    pub name: FQName<'a>,
+    /// the arguments of the call.  The `*` of `count(*)` is held as a constant.
+    pub args: Vec<Operand<'a>>,
+}
+
+impl FunctionCall<'_> {
+    /// the equivalent owned function call.
+    pub fn into_owned(self) -> common::FunctionCall {
+        common::FunctionCall {
+            name: self.name.into_owned(),
+            args: self.args.into_iter().map(Operand::into_owned).collect(),
+        }
+    }
+}
+
+/// A borrowed [common::Literal].
+#[derive(PartialEq, Debug, Clone)]
+pub enum Literal<'a> {
+    /// a string quoted by `'` or `$$`
+    String(Cow<'a, str>),
+    /// an integer
+    Integer(Cow<'a, str>),
+    /// a floating point number
+    Float(Cow<'a, str>),
+    /// `TRUE` or `FALSE`
+    Boolean(Cow<'a, str>),
+    /// a UUID
+    Uuid(Cow<'a, str>),
+    /// a blob written in hexadecimal
+    Hex(Cow<'a, str>),
+    /// a duration
+    Duration(Cow<'a, str>),
+    /// `NaN` or `-NaN`
+    NaN(Cow<'a, str>),
+    /// `Infinity` or `-Infinity`
+    Infinity(Cow<'a, str>),
+}
+
+impl<'a> Literal<'a> {
+    /// classifies the text of a constant.  Returns `None` if the text is not a literal.
+    /// See [common::Literal::parse].
+    pub fn parse(text: &'a str) -> Option<Literal<'a>> {
+        let unsigned = text.strip_prefix('-').unwrap_or(text);
+        let digits = |x: &str| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit());
+        let hex = |x: &str| !x.is_empty() && x.chars().all(|c| c.is_ascii_hexdigit());
+        let is_uuid = || {
+            let groups: Vec<&str> = text.split('-').collect();
+            groups.len() == 5
+                && groups
+                    .iter()
+                    .zip([8, 4, 4, 4, 12])
+                    .all(|(group, len)| group.len() == len && hex(group))
+        };
+        let is_float = || {
+            let (mantissa, exponent) = match unsigned.find(|c| c == 'e' || c == 'E') {
+                Some(pos) => (&unsigned[..pos], Some(&unsigned[pos + 1..])),
+                None => (unsigned, None),
+            };
+            let (whole, fraction) = match mantissa.find('.') {
+                Some(pos) => (&mantissa[..pos], &mantissa[pos + 1..]),
+                None => (mantissa, ""),
+            };
+            digits(whole)
+                && fraction.chars().all(|c| c.is_ascii_digit())
+                && exponent.map_or(true, |x| {
+                    digits(x.trim_start_matches(|c| c == '+' || c == '-'))
+                })
+        };
+        let text = Cow::Borrowed(text);
+        Some(if text.starts_with('\'') || text.starts_with("$$") {
+            Literal::String(text)
+        } else if text.eq_ignore_ascii_case("TRUE") || text.eq_ignore_ascii_case("FALSE") {
+            Literal::Boolean(text)
+        } else if unsigned.eq_ignore_ascii_case("NaN") {
+            Literal::NaN(text)
+        } else if unsigned.eq_ignore_ascii_case("Infinity") {
+            Literal::Infinity(text)
+        } else if text
+            .get(..2)
+            .map_or(false, |x| x.eq_ignore_ascii_case("0x"))
+            && text.get(2..).map_or(false, hex)
+        {
+            Literal::Hex(text)
+        } else if is_uuid() {
+            Literal::Uuid(text)
+        } else if digits(unsigned) {
+            Literal::Integer(text)
+        } else if is_float() {
+            Literal::Float(text)
+        } else if common::CqlDuration::parse(text.as_ref()).is_some() {
+            Literal::Duration(text)
+        } else {
+            return None;
+        })
+    }
+
+    /// the equivalent owned literal.
+    pub fn into_owned(self) -> common::Literal {
+        match self {
+            Literal::String(txt) => common::Literal::String(txt.into_owned()),
+            Literal::Integer(txt) => common::Literal::Integer(txt.into_owned()),
+            Literal::Float(txt) => common::Literal::Float(txt.into_owned()),
+            Literal::Boolean(txt) => common::Literal::Boolean(txt.into_owned()),
+            Literal::Uuid(txt) => common::Literal::Uuid(txt.into_owned()),
+            Literal::Hex(txt) => common::Literal::Hex(txt.into_owned()),
+            Literal::Duration(txt) => common::Literal::Duration(txt.into_owned()),
+            Literal::NaN(txt) => common::Literal::NaN(txt.into_owned()),
+            Literal::Infinity(txt) => common::Literal::Infinity(txt.into_owned()),
+        }
+    }
+}
+
+/// a `key : value` entry of a map.
+pub type MapEntry<'a> = (Operand<'a>, Operand<'a>);
+
+/// A borrowed [common::Operand].
+#[derive(PartialEq, Debug, Clone)]
+pub enum Operand<'a> {
+    /// A constant that is not a literal, such as the `*` of `count(*)`
+    Const(Cow<'a, str>),
+    /// A literal value
+    Literal(Literal<'a>),
+    /// a map of entries.
+    Map(Vec<MapEntry<'a>>),
+    /// a set of values.
+    Set(Vec<Operand<'a>>),
+    /// a list of values.
+    List(Vec<Operand<'a>>),
+    /// a user defined type literal.
+    Udt(Vec<(Identifier<'a>, Operand<'a>)>),
+    /// a tuple of values.
+    Tuple(Vec<Operand<'a>>),
+    /// A column name
+    Column(Identifier<'a>),
+    /// A function call e.g. foo(bar)
+    Func(FunctionCall<'a>),
+    /// A parameter.  The string will either be '?' or ':name'
+    Param(Cow<'a, str>),
+    /// the `NULL` value.
+    Null,
+    /// an arithmetic operation e.g. `now() - 1d`
+    Arithmetic(Box<Operand<'a>>, ArithmeticOperator, Box<Operand<'a>>),
+}
+
+impl Operand<'_> {
+    /// the equivalent owned operand.
+    pub fn into_owned(self) -> common::Operand {
+        match self {
+            Operand::Const(txt) => common::Operand::Const(txt.into_owned()),
+            Operand::Literal(literal) => common::Operand::Literal(literal.into_owned()),
+            Operand::Map(entries) => common::Operand::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect(),
+            ),
+            Operand::Udt(fields) => common::Operand::Udt(
+                fields
+                    .into_iter()
+                    .map(|(field, value)| (field.into_owned(), value.into_owned()))
+                    .collect(),
+            ),
+            Operand::Set(values) => {
+                common::Operand::Set(values.into_iter().map(Operand::into_owned).collect())
+            }
+            Operand::List(values) => {
+                common::Operand::List(values.into_iter().map(Operand::into_owned).collect())
+            }
+            Operand::Tuple(values) => {
+                common::Operand::Tuple(values.into_iter().map(Operand::into_owned).collect())
+            }
+            Operand::Column(name) => common::Operand::Column(name.into_owned()),
+            Operand::Func(function) => common::Operand::Func(function.into_owned()),
+            Operand::Param(txt) => common::Operand::Param(txt.into_owned()),
+            Operand::Null => common::Operand::Null,
+            Operand::Arithmetic(left, operator, right) => common::Operand::Arithmetic(
+                Box::new(left.into_owned()),
+                operator,
+                Box::new(right.into_owned()),
+            ),
+        }
+    }
+}
+
+/// A borrowed [common::RelationElement].
+#[derive(Debug, Clone)]
+pub struct RelationElement<'a> {
+    /// the column, column list or token on the left side
+    pub obj: RelationTarget<'a>,
+    /// the relational operator
+    pub oper: RelationOperator,
+    /// the value, func, argument list, tuple list or tuple
+    pub value: Operand<'a>,
+    /// the location of the relation in the query text.
+    pub span: Option<Span>,
+    /// the location of the column, column list or token on the left side.
+    pub obj_span: Option<Span>,
+    /// the location of the value.
+    pub value_span: Option<Span>,
+}
+
+eq_without_spans!(RelationElement<'a> { obj, oper, value; span, obj_span, value_span });
+
+impl RelationElement<'_> {
+    /// the equivalent owned relation.
+    pub fn into_owned(self) -> common::RelationElement {
+        common::RelationElement {
+            obj: self.obj.into_owned(),
+            oper: self.oper,
+            value: self.value.into_owned(),
+            span: self.span,
+            obj_span: self.obj_span,
+            value_span: self.value_span,
+        }
+    }
+}
+
+/// A borrowed [common::RelationTarget].
+#[derive(PartialEq, Debug, Clone)]
+pub enum RelationTarget<'a> {
+    /// a column.
+    Column(Identifier<'a>),
+    /// the columns of a multi-column relation.
+    Columns(Vec<Identifier<'a>>),
+    /// the token of the partition key columns.
+    Token(Vec<Identifier<'a>>),
+    /// any other left side.
+    Other(Operand<'a>),
+}
+
+impl<'a> RelationTarget<'a> {
+    /// the target for the operand on the left side of a relation.  A tuple of columns is a
+    /// multi-column relation and a call of `token` on columns is a token relation.
+    pub fn from_operand(operand: Operand<'a>) -> RelationTarget<'a> {
+        let columns = |operands: &[Operand<'a>]| {
+            operands
+                .iter()
+                .map(|x| match x {
+                    Operand::Column(column) => Some(column.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        let is_token = |function: &FunctionCall<'_>| match &function.name {
+            FQName {
+                keyspace: None,
+                name: Identifier::Unquoted(name),
+                ..
+            } => name.eq_ignore_ascii_case("token"),
+            _ => false,
+        };
+        match operand {
+            Operand::Column(column) => RelationTarget::Column(column),
+            Operand::Tuple(ref items) => match columns(items) {
+                Some(columns) => RelationTarget::Columns(columns),
+                None => RelationTarget::Other(operand),
+            },
+            Operand::Func(ref function) if is_token(function) => match columns(&function.args) {
+                Some(columns) => RelationTarget::Token(columns),
+                None => RelationTarget::Other(operand),
+            },
+            _ => RelationTarget::Other(operand),
+        }
+    }
+
+    /// the equivalent owned target.
+    pub fn into_owned(self) -> common::RelationTarget {
+        let into_owned = |columns: Vec<Identifier<'_>>| {
+            columns.into_iter().map(Identifier::into_owned).collect()
+        };
+        match self {
+            RelationTarget::Column(column) => common::RelationTarget::Column(column.into_owned()),
+            RelationTarget::Columns(columns) => {
+                common::RelationTarget::Columns(into_owned(columns))
+            }
+            RelationTarget::Token(columns) => common::RelationTarget::Token(into_owned(columns)),
+            RelationTarget::Other(operand) => common::RelationTarget::Other(operand.into_owned()),
+        }
+    }
+}
+
+/// convert a list of borrowed relations into owned relations.
+fn into_relations(relations: Vec<RelationElement<'_>>) -> Vec<common::RelationElement> {
+    relations
+        .into_iter()
+        .map(RelationElement::into_owned)
+        .collect()
+}
+
+/// A borrowed [common::WhereExpression].
+#[derive(PartialEq, Debug, Clone)]
+pub enum WhereExpression<'a> {
+    /// a single relation.
+    Relation(RelationElement<'a>),
+    /// the expressions must all be true.
+    And(Vec<WhereExpression<'a>>),
+    /// one of the expressions must be true.
+    Or(Vec<WhereExpression<'a>>),
+    /// the expression must be false.
+    Not(Box<WhereExpression<'a>>),
+}
+
+impl<'a> WhereExpression<'a> {
+    /// the expression that requires all of the relations, `None` if there are no relations.
+    pub fn from_relations(mut relations: Vec<RelationElement<'a>>) -> Option<WhereExpression<'a>> {
+        match relations.len() {
+            0 => None,
+            1 => Some(WhereExpression::Relation(relations.remove(0))),
+            _ => Some(WhereExpression::And(
+                relations
+                    .into_iter()
+                    .map(WhereExpression::Relation)
+                    .collect(),
+            )),
+        }
+    }
+
+    /// the equivalent owned expression.
+    pub fn into_owned(self) -> common::WhereExpression {
+        let into_owned = |items: Vec<WhereExpression<'_>>| {
+            items.into_iter().map(WhereExpression::into_owned).collect()
+        };
+        match self {
+            WhereExpression::Relation(relation) => {
+                common::WhereExpression::Relation(relation.into_owned())
+            }
+            WhereExpression::And(items) => common::WhereExpression::And(into_owned(items)),
+            WhereExpression::Or(items) => common::WhereExpression::Or(into_owned(items)),
+            WhereExpression::Not(item) => common::WhereExpression::Not(Box::new(item.into_owned())),
+        }
+    }
+}
+
+/// A borrowed [common::OrderClause].
+#[derive(Debug, Clone)]
+pub struct OrderClause<'a> {
+    /// the column to order by.
+    pub name: Identifier<'a>,
+    /// if `true` then the order is descending,
+    pub desc: bool,
+    /// if set the rows are ordered by the similarity to this vector.
+    pub ann_of: Option<Operand<'a>>,
+    /// the location of the ordering in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub name_span: Option<Span>,
+}
+
+eq_without_spans!(OrderClause<'a> { name, desc, ann_of; span, name_span });
+
+impl OrderClause<'_> {
+    /// the equivalent owned order clause.
+    pub fn into_owned(self) -> common::OrderClause {
+        common::OrderClause {
+            name: self.name.into_owned(),
+            desc: self.desc,
+            ann_of: self.ann_of.map(Operand::into_owned),
+            span: self.span,
+            name_span: self.name_span,
+        }
+    }
+}
+
+/// A borrowed [select::Select].
+#[derive(Debug, Clone)]
+pub struct Select<'a> {
+    /// if true DISTINCT results
+    pub distinct: bool,
+    /// if true JSON reslts
+    pub json: bool,
+    /// The table name.
+    pub table_name: FQName<'a>,
+    /// the list of elements to select.
+    pub columns: Vec<SelectElement<'a>>,
+    /// the condition of the where clause, `None` if there is no `WHERE` clause.
+    pub where_clause: Option<WhereExpression<'a>>,
+    /// the columns to group the results by.
+    pub group_by: Vec<Identifier<'a>>,
+    /// the ordering, empty if there is no `ORDER BY` clause
+    pub order: Vec<OrderClause<'a>>,
+    /// the number of items to return from each partition
+    pub per_partition_limit: Option<Bindable<i32>>,
+    /// the number of items to return
+    pub limit: Option<Bindable<i32>>,
+    /// if true ALLOW FILTERING is displayed
+    pub filtering: bool,
+    /// if true BYPASS CACHE is displayed, a ScyllaDB extension.
+    pub bypass_cache: bool,
+    /// the timeout of the query, `USING TIMEOUT` is a ScyllaDB extension.
+    pub timeout: Option<Bindable<CqlDuration>>,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(Select<'a> {
+    distinct, json, table_name, columns, where_clause, group_by, order, per_partition_limit, limit,
+    filtering, bypass_cache, timeout;
+    span
+});
+
+impl Select<'_> {
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> select::Select {
+        select::Select {
+            distinct: self.distinct,
+            json: self.json,
+            table_name: self.table_name.into_owned(),
+            columns: self
+                .columns
+                .into_iter()
+                .map(SelectElement::into_owned)
+                .collect(),
+            where_clause: self.where_clause.map(WhereExpression::into_owned),
+            group_by: self
+                .group_by
+                .into_iter()
+                .map(Identifier::into_owned)
+                .collect(),
+            order: self
+                .order
+                .into_iter()
+                .map(OrderClause::into_owned)
+                .collect(),
+            per_partition_limit: self.per_partition_limit,
+            limit: self.limit,
+            filtering: self.filtering,
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [select::SelectElement].
+#[derive(PartialEq, Debug, Clone)]
+pub enum SelectElement<'a> {
+    /// All of the columns
+    Star,
+    /// a named column.  May have an alias specified.
+    Column(Named<'a>),
+    /// a function call.  May have an alias specified.
+    Function(NamedFunction<'a>),
+    /// any other selector.  May have an alias specified.
+    Selector(NamedSelector<'a>),
+}
+
+impl SelectElement<'_> {
+    /// the equivalent owned element.
+    pub fn into_owned(self) -> select::SelectElement {
+        match self {
+            SelectElement::Star => select::SelectElement::Star,
+            SelectElement::Column(named) => select::SelectElement::Column(named.into_owned()),
+            SelectElement::Function(named) => select::SelectElement::Function(named.into_owned()),
+            SelectElement::Selector(named) => select::SelectElement::Selector(named.into_owned()),
+        }
+    }
+}
+
+/// A borrowed [select::Named].
+#[derive(Debug, Clone)]
+pub struct Named<'a> {
+    pub name: Identifier<'a>,
+    pub alias: Option<Identifier<'a>>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub name_span: Option<Span>,
+}
+
+eq_without_spans!(Named<'a> { name, alias; span, name_span });
+
+impl Named<'_> {
+    /// the equivalent owned name.
+    pub fn into_owned(self) -> select::Named {
+        select::Named {
+            name: self.name.into_owned(),
+            alias: self.alias.map(Identifier::into_owned),
+            span: self.span,
+            name_span: self.name_span,
+        }
+    }
+}
+
+/// A borrowed [select::NamedFunction].
+#[derive(Debug, Clone)]
+pub struct NamedFunction<'a> {
+    pub function: FunctionCall<'a>,
+    pub alias: Option<Identifier<'a>>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(NamedFunction<'a> { function, alias; span });
+
+impl NamedFunction<'_> {
+    /// the equivalent owned function.
+    pub fn into_owned(self) -> select::NamedFunction {
+        select::NamedFunction {
+            function: self.function.into_owned(),
+            alias: self.alias.map(Identifier::into_owned),
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [select::Selector].
+#[derive(PartialEq, Debug, Clone)]
+pub enum Selector<'a> {
+    /// a column.
+    Column(Identifier<'a>),
+    /// a literal, bind marker or collection term.
+    Term(Operand<'a>),
+    /// a function call on selectors.
+    Function(FQName<'a>, Vec<Selector<'a>>),
+    /// `CAST(selector AS type)`
+    Cast(Box<Selector<'a>>, common::DataType),
+    /// `WRITETIME(selector)`
+    WriteTime(Box<Selector<'a>>),
+    /// `MAXWRITETIME(selector)`
+    MaxWriteTime(Box<Selector<'a>>),
+    /// `TTL(selector)`
+    Ttl(Box<Selector<'a>>),
+    /// `selector[key]`
+    Element(Box<Selector<'a>>, Box<Selector<'a>>),
+    /// `selector[start..end]`
+    Slice(
+        Box<Selector<'a>>,
+        Option<Box<Selector<'a>>>,
+        Option<Box<Selector<'a>>>,
+    ),
+    /// `selector.field`
+    Field(Box<Selector<'a>>, Identifier<'a>),
+    /// an arithmetic operation.
+    Arithmetic(Box<Selector<'a>>, ArithmeticOperator, Box<Selector<'a>>),
+    /// `-selector`
+    Negate(Box<Selector<'a>>),
+}
+
+impl Selector<'_> {
+    /// the equivalent owned selector.
+    pub fn into_owned(self) -> select::Selector {
+        let boxed = |selector: Box<Selector>| Box::new(selector.into_owned());
+        match self {
+            Selector::Column(name) => select::Selector::Column(name.into_owned()),
+            Selector::Term(term) => select::Selector::Term(term.into_owned()),
+            Selector::Function(name, args) => select::Selector::Function(
+                name.into_owned(),
+                args.into_iter().map(Selector::into_owned).collect(),
+            ),
+            Selector::Cast(selector, data_type) => {
+                select::Selector::Cast(boxed(selector), data_type)
+            }
+            Selector::WriteTime(selector) => select::Selector::WriteTime(boxed(selector)),
+            Selector::MaxWriteTime(selector) => select::Selector::MaxWriteTime(boxed(selector)),
+            Selector::Ttl(selector) => select::Selector::Ttl(boxed(selector)),
+            Selector::Element(selector, key) => {
+                select::Selector::Element(boxed(selector), boxed(key))
+            }
+            Selector::Slice(selector, start, end) => {
+                select::Selector::Slice(boxed(selector), start.map(boxed), end.map(boxed))
+            }
+            Selector::Field(selector, field) => {
+                select::Selector::Field(boxed(selector), field.into_owned())
+            }
+            Selector::Arithmetic(left, operator, right) => {
+                select::Selector::Arithmetic(boxed(left), operator, boxed(right))
+            }
+            Selector::Negate(selector) => select::Selector::Negate(boxed(selector)),
+        }
+    }
+}
+
+/// A borrowed [select::NamedSelector].
+#[derive(Debug, Clone)]
+pub struct NamedSelector<'a> {
+    pub selector: Selector<'a>,
+    pub alias: Option<Identifier<'a>>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(NamedSelector<'a> { selector, alias; span });
+
+impl NamedSelector<'_> {
+    /// the equivalent owned selector.
+    pub fn into_owned(self) -> select::NamedSelector {
+        select::NamedSelector {
+            selector: self.selector.into_owned(),
+            alias: self.alias.map(Identifier::into_owned),
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [insert::Insert].
+#[derive(Debug, Clone)]
+pub struct Insert<'a> {
+    /// if set the statement starts with `BEGIN BATCH`.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
+    pub begin_batch: Option<BeginBatch>,
+    /// the table name
+    pub table_name: FQName<'a>,
+    /// an the list of of column names to insert into.
+    pub columns: Vec<Identifier<'a>>,
+    /// the `VALUES` to insert
+    pub values: InsertValues<'a>,
+    /// if set the timestamp for `USING TTL`
+    pub using_ttl: Option<TtlTimestamp>,
+    /// if true then `IF NOT EXISTS` is added to the statement
+    pub if_not_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(Insert<'a> {
+    begin_batch, table_name, columns, values, using_ttl, if_not_exists;
+    span
+});
+
+impl Insert<'_> {
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> insert::Insert {
+        insert::Insert {
+            begin_batch: self.begin_batch,
+            table_name: self.table_name.into_owned(),
+            columns: self
+                .columns
+                .into_iter()
+                .map(Identifier::into_owned)
+                .collect(),
+            values: self.values.into_owned(),
+            using_ttl: self.using_ttl,
+            if_not_exists: self.if_not_exists,
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [insert::InsertValues].
+#[derive(PartialEq, Debug, Clone)]
+pub enum InsertValues<'a> {
+    /// this is the standard list of values.
+    Values(Vec<Operand<'a>>),
+    /// this option allows JSON string to define the values.
+    Json(Cow<'a, str>),
+}
+
+impl InsertValues<'_> {
+    /// the equivalent owned values.
+    pub fn into_owned(self) -> insert::InsertValues {
+        match self {
+            InsertValues::Values(values) => {
+                insert::InsertValues::Values(values.into_iter().map(Operand::into_owned).collect())
+            }
+            InsertValues::Json(txt) => insert::InsertValues::Json(txt.into_owned()),
+        }
+    }
+}
+
+/// A borrowed [update::Update].
+#[derive(Debug, Clone)]
+pub struct Update<'a> {
+    /// if present then statement starts with BEGIN BATCH.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
+    pub begin_batch: Option<BeginBatch>,
+    /// the table name to update
+    pub table_name: FQName<'a>,
+    /// if present then the TTL Timestamp for the update
+    pub using_ttl: Option<TtlTimestamp>,
+    /// the column assignments for the update.
+    pub assignments: Vec<AssignmentElement<'a>>,
+    /// the where clause
+    pub where_clause: Vec<RelationElement<'a>>,
+    /// if present a list of key,values for the `IF` clause
+    pub if_clause: Vec<RelationElement<'a>>,
+    /// if true and `if_clause` is NONE then  `IF EXISTS` is added to the statement
+    pub if_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(Update<'a> {
+    begin_batch, table_name, using_ttl, assignments, where_clause, if_clause, if_exists;
+    span
+});
+
+impl Update<'_> {
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> update::Update {
+        update::Update {
+            begin_batch: self.begin_batch,
+            table_name: self.table_name.into_owned(),
+            using_ttl: self.using_ttl,
+            assignments: self
+                .assignments
+                .into_iter()
+                .map(AssignmentElement::into_owned)
+                .collect(),
+            where_clause: into_relations(self.where_clause),
+            if_clause: into_relations(self.if_clause),
+            if_exists: self.if_exists,
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [update::AssignmentElement].
+#[derive(Debug, Clone)]
+pub struct AssignmentElement<'a> {
+    /// the column to set the value for.
+    pub name: IndexedColumn<'a>,
+    /// the column value
+    pub value: Operand<'a>,
+    /// an optional +/- value
+    pub operator: Option<AssignmentOperator<'a>>,
+    /// the location of the assignment in the query text.
+    pub span: Option<Span>,
+    /// the location of the value.
+    pub value_span: Option<Span>,
+}
+
+eq_without_spans!(AssignmentElement<'a> { name, value, operator; span, value_span });
+
+impl AssignmentElement<'_> {
+    /// the equivalent owned assignment.
+    pub fn into_owned(self) -> update::AssignmentElement {
+        update::AssignmentElement {
+            name: self.name.into_owned(),
+            value: self.value.into_owned(),
+            operator: self.operator.map(AssignmentOperator::into_owned),
+            span: self.span,
+            value_span: self.value_span,
+        }
+    }
+}
+
+/// A borrowed [update::AssignmentOperator].
+#[derive(PartialEq, Debug, Clone)]
+pub enum AssignmentOperator<'a> {
+    Plus(Operand<'a>),
+    Minus(Operand<'a>),
+}
+
+impl AssignmentOperator<'_> {
+    /// the equivalent owned operator.
+    pub fn into_owned(self) -> update::AssignmentOperator {
+        match self {
+            AssignmentOperator::Plus(op) => update::AssignmentOperator::Plus(op.into_owned()),
+            AssignmentOperator::Minus(op) => update::AssignmentOperator::Minus(op.into_owned()),
+        }
+    }
+}
+
+/// A borrowed [delete::Delete].
+#[derive(Debug, Clone)]
+pub struct Delete<'a> {
+    /// if set the statement starts with `BEGIN BATCH`.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
+    pub begin_batch: Option<BeginBatch>,
+    /// an optional list of columns to delete
+    pub columns: Vec<IndexedColumn<'a>>,
+    /// the table to delete from
+    pub table_name: FQName<'a>,
+    /// an optional timestamp to use for the deletion.
+    pub timestamp: Option<Bindable<u64>>,
+    /// the were clause for the delete.
+    pub where_clause: Vec<RelationElement<'a>>,
+    /// if present a list of key,values for the `IF` clause
+    pub if_clause: Vec<RelationElement<'a>>,
+    /// if true and if_clause is NONE then `IF EXISTS` is added
+    pub if_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(Delete<'a> {
+    begin_batch, columns, table_name, timestamp, where_clause, if_clause, if_exists;
+    span
+});
+
+impl Delete<'_> {
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> delete::Delete {
+        delete::Delete {
+            begin_batch: self.begin_batch,
+            columns: self
+                .columns
+                .into_iter()
+                .map(IndexedColumn::into_owned)
+                .collect(),
+            table_name: self.table_name.into_owned(),
+            timestamp: self.timestamp,
+            where_clause: into_relations(self.where_clause),
+            if_clause: into_relations(self.if_clause),
+            if_exists: self.if_exists,
+            span: self.span,
+        }
+    }
+}
+
+/// A borrowed [delete::IndexedColumn].
+#[derive(Debug, Clone)]
+pub struct IndexedColumn<'a> {
+    /// the column name
+    pub column: Identifier<'a>,
+    /// the optional index in to the column
+    pub idx: Option<Cow<'a, str>>,
+    /// the location of the column in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub column_span: Option<Span>,
+}
+
+eq_without_spans!(IndexedColumn<'a> { column, idx; span, column_span });
+
+impl IndexedColumn<'_> {
+    /// the equivalent owned column.
+    pub fn into_owned(self) -> delete::IndexedColumn {
+        delete::IndexedColumn {
+            column: self.column.into_owned(),
+            idx: self.idx.map(Cow::into_owned),
+            span: self.span,
+            column_span: self.column_span,
+        }
+    }
+}
+
+/// A borrowed [cassandra_statement::CassandraStatement].
+#[derive(PartialEq, Debug, Clone)]
+pub enum CassandraStatement<'a> {
+    Delete(Delete<'a>),
+    Insert(Insert<'a>),
+    Select(Select<'a>),
+    Truncate(FQName<'a>),
+    Update(Update<'a>),
+    Use(Identifier<'a>),
+    /// a statement that does not have a borrowed form.
+    Owned(cassandra_statement::CassandraStatement),
+    Unknown(&'a str),
+}
+
+impl<'a> CassandraStatement<'a> {
+    /// extract the cassandra statement from an AST node.
+    /// Returns an error if the node does not have the shape the parser expects.
+    /// See [cassandra_statement::CassandraStatement::try_from_node].
+    pub fn try_from_node(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CassandraStatement<'a>, ParseError> {
+        Ok(match node.kind() {
+            "delete_statement" => CassandraStatement::Delete(
+                BorrowedParser::parse_delete_statement(node, source, recovered)?,
+            ),
+            "insert_statement" => {
+                CassandraStatement::Insert(BorrowedParser::parse_insert(node, source, recovered)?)
+            }
+            "select_statement" => {
+                CassandraStatement::Select(BorrowedParser::parse_select(node, source, recovered)?)
+            }
+            "truncate" => {
+                CassandraStatement::Truncate(BorrowedParser::parse_truncate(node, source)?)
+            }
+            "update" => {
+                CassandraStatement::Update(BorrowedParser::parse_update(node, source, recovered)?)
+            }
+            "use" => CassandraStatement::Use(BorrowedParser::parse_use(node, source)?),
+            "ERROR" => match BorrowedParser::parse_unparsed_write(node, source, recovered) {
+                Some(statement) => statement,
+                None => CassandraStatement::from_owned(node, source, recovered)?,
+            },
+            _ => CassandraStatement::from_owned(node, source, recovered)?,
+        })
+    }
+
+    /// extract a statement that does not have a borrowed form.
+    fn from_owned(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CassandraStatement<'a>, ParseError> {
+        Ok(
+            match cassandra_statement::CassandraStatement::try_from_node(node, source, recovered)? {
+                cassandra_statement::CassandraStatement::Unknown(_) => {
+                    CassandraStatement::Unknown(source)
+                }
+                statement => CassandraStatement::Owned(statement),
+            },
+        )
+    }
+
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> cassandra_statement::CassandraStatement {
+        match self {
+            CassandraStatement::Delete(delete) => {
+                cassandra_statement::CassandraStatement::Delete(delete.into_owned())
+            }
+            CassandraStatement::Insert(insert) => {
+                cassandra_statement::CassandraStatement::Insert(insert.into_owned())
+            }
+            CassandraStatement::Select(select) => {
+                cassandra_statement::CassandraStatement::Select(select.into_owned())
+            }
+            CassandraStatement::Truncate(name) => {
+                cassandra_statement::CassandraStatement::Truncate(name.into_owned())
+            }
+            CassandraStatement::Update(update) => {
+                cassandra_statement::CassandraStatement::Update(update.into_owned())
+            }
+            CassandraStatement::Use(name) => {
+                cassandra_statement::CassandraStatement::Use(name.into_owned())
+            }
+            CassandraStatement::Owned(statement) => statement,
+            CassandraStatement::Unknown(txt) => {
+                cassandra_statement::CassandraStatement::Unknown(txt.to_string())
+            }
+        }
+    }
+}
+
+/// A borrowed [cassandra_ast::ParsedStatement].
+#[derive(PartialEq, Debug)]
+pub struct ParsedStatement<'a> {
+    /// true if the statement had an error in parsing.
+    pub has_error: bool,
+    /// the parsed statement.
+    pub statement: CassandraStatement<'a>,
+    /// the beginning byte of the text for the parsed statement within
+    /// the original statement.
+    start_byte: usize,
+    /// the ending byte of the text for the parsed statement within
+    /// the original statement.
+    end_byte: usize,
+}
+
+impl<'a> ParsedStatement<'a> {
+    /// create the parsed statement for a statement node.
+    /// See [cassandra_ast::ParsedStatement::try_new].
+    pub fn try_new(node: Node, source: &'a str) -> Result<ParsedStatement<'a>, ParseError> {
+        ParsedStatement::from_node(node, source, &mut vec![])
+    }
+
+    /// create the parsed statement for a statement node, see `try_new`.
+    fn from_node(
+        node: Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<ParsedStatement<'a>, ParseError> {
+        let (has_error, statement) =
+            match CassandraStatement::try_from_node(&node, source, recovered) {
+                Ok(statement) => (node.is_error(), statement),
+                Err(_) if node.has_error() => (true, CassandraStatement::Unknown(source)),
+                Err(e) => return Err(e),
+            };
+        Ok(ParsedStatement {
+            has_error,
+            statement,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+        })
+    }
+
+    /// create the parsed statement for a group of statement nodes, a batch is held as an owned
+    /// statement.  The parts of the text read for the statement are only added to `recovered`
+    /// if it is not unknown.
+    pub(crate) fn try_from_nodes(
+        nodes: &[Node],
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<ParsedStatement<'a>, ParseError> {
+        let mut read = vec![];
+        let result = ParsedStatement::from_group(nodes, source, &mut read);
+        if result.as_ref().map_or(false, |x| {
+            !matches!(x.statement, CassandraStatement::Unknown(_))
+        }) {
+            recovered.append(&mut read);
+        }
+        result
+    }
+
+    /// create the parsed statement for a group of statement nodes, see `try_from_nodes`.
+    fn from_group(
+        nodes: &[Node],
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<ParsedStatement<'a>, ParseError> {
+        match nodes {
+            [node] => return ParsedStatement::from_node(*node, source, recovered),
+            [node, error]
+                if node.kind().eq("select_statement")
+                    && !cassandra_statement::CassandraStatement::is_batch(nodes) =>
+            {
+                let (has_error, statement) =
+                    match BorrowedParser::parse_continued_select(node, error, source, recovered) {
+                        Ok(select) => (false, CassandraStatement::Select(select)),
+                        Err(_) => (true, CassandraStatement::Unknown(source)),
+                    };
+                return Ok(ParsedStatement {
+                    has_error,
+                    statement,
+                    start_byte: node.start_byte(),
+                    end_byte: error.end_byte(),
+                });
+            }
+            [node, error] if BorrowedParser::continues_insert(node, error, source) => {
+                return Ok(ParsedStatement {
+                    has_error: false,
+                    statement: CassandraStatement::Insert(BorrowedParser::parse_continued_insert(
+                        node, error, source, recovered,
+                    )?),
+                    start_byte: node.start_byte(),
+                    end_byte: error.end_byte(),
+                });
+            }
+            _ => {}
+        }
+        let owned = cassandra_ast::ParsedStatement::try_from_nodes(nodes, source, recovered)?;
+        Ok(ParsedStatement {
+            has_error: owned.has_error,
+            statement: match owned.statement {
+                cassandra_statement::CassandraStatement::Unknown(_) => {
+                    CassandraStatement::Unknown(source)
+                }
+                statement => CassandraStatement::Owned(statement),
+            },
+            start_byte: owned.start_byte,
+            end_byte: owned.end_byte,
+        })
+    }
+
+    /// the equivalent owned statement.
+    pub fn into_owned(self) -> cassandra_ast::ParsedStatement {
+        cassandra_ast::ParsedStatement {
+            has_error: self.has_error,
+            statement: self.statement.into_owned(),
+            leading_comments: vec![],
+            trailing_comments: vec![],
+            start_byte: self.start_byte,
+            end_byte: self.end_byte,
+        }
+    }
+}
+
+/// A borrowed [cassandra_ast::CassandraAST].  The statements borrow from the query string.
+pub struct CassandraAST<'a> {
+    /// The query string
+    text: &'a str,
+    /// the tree-sitter tree
+    tree: Tree,
+    /// the parts of the text holding syntax errors that were read by the parser.
+    recovered: Vec<Recovered>,
+    /// the statements of the query
+    pub statements: Vec<ParsedStatement<'a>>,
+    /// the problems found while parsing the query.  Empty if the query parsed cleanly.
+    pub errors: Vec<ParseError>,
+    /// the comments in the query.  They are given to the statements by `into_owned`.
+    pub comments: Vec<Comment>,
+}
+
+impl<'a> CassandraAST<'a> {
+    /// create an AST from the query string using the thread's default `CqlParser`.
+    /// See [cassandra_ast::CassandraAST::try_new].
+    pub fn try_new(cassandra_statement: &'a str) -> Result<CassandraAST<'a>, ParseError> {
+        CqlParser::with_default(|parser| parser.parse_borrowed(cassandra_statement))?
+    }
+
+    /// create an AST from a tree produced by parsing the query string.
+    pub(crate) fn try_from_tree(
+        cassandra_statement: &'a str,
+        tree: Tree,
+        dialect: Dialect,
+    ) -> Result<CassandraAST<'a>, ParseError> {
+        let mut recovered = vec![];
+        let groups =
+            cassandra_statement::CassandraStatement::statement_groups(&tree, cassandra_statement);
+        let mut statements = groups
+            .iter()
+            .map(|nodes| {
+                ParsedStatement::try_from_nodes(nodes, cassandra_statement, &mut recovered)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let errors = cassandra_ast::CassandraAST::find_errors(
+            dialect,
+            &tree,
+            cassandra_statement,
+            &groups,
+            &recovered,
+        );
+        for statement in statements.iter_mut() {
+            statement.has_error = matches!(statement.statement, CassandraStatement::Unknown(_))
+                || errors
+                    .iter()
+                    .any(|x| x.is_within(Span::new(statement.start_byte, statement.end_byte)));
+        }
+        Ok(CassandraAST {
+            statements,
+            errors,
+            comments: Comment::find(cassandra_statement),
+            text: cassandra_statement,
+            tree,
+            recovered,
+        })
+    }
+
+    /// returns true if the parsing exposed an error in the query, see `errors`.
+    pub fn has_error(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// extracts the text for the statement from the original text.
+    pub fn extract_text(&self, statement: &ParsedStatement) -> &'a str {
+        &self.text[statement.start_byte..statement.end_byte]
+    }
+
+    /// the equivalent owned AST.
+    pub fn into_owned(self) -> cassandra_ast::CassandraAST {
+        let mut statements = self
+            .statements
+            .into_iter()
+            .map(ParsedStatement::into_owned)
+            .collect::<Vec<_>>();
+        cassandra_ast::CassandraAST::attach_comments(&mut statements, &self.comments, self.text);
+        cassandra_ast::CassandraAST {
+            text: self.text.to_string(),
+            tree: Some(self.tree),
+            recovered: self.recovered,
+            statements,
+            errors: self.errors,
+            comments: self.comments,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::borrowed::{
+        CassandraAST, CassandraStatement, Identifier, Literal, Operand, WhereExpression,
+    };
+    use crate::cassandra_ast;
+    use crate::common;
+    use std::borrow::Cow;
+
+    fn assert_same(query: &str) {
+        let borrowed = CassandraAST::try_new(query).unwrap();
+        let owned = cassandra_ast::CassandraAST::try_new(query).unwrap();
+        assert_eq!(borrowed.has_error(), owned.has_error());
+        let borrowed = borrowed.into_owned();
+        assert_eq!(owned.statements, borrowed.statements);
+        assert_eq!(owned.errors, borrowed.errors);
+        assert_eq!(owned.comments, borrowed.comments);
+        for (statement, other) in owned.statements.iter().zip(borrowed.statements.iter()) {
+            assert_eq!(owned.extract_text(statement), borrowed.extract_text(other));
+            assert_eq!(statement.statement.to_string(), other.statement.to_string());
+        }
+    }
+
+    #[test]
+    fn test_into_owned() {
+        for query in [
+            "SELECT DISTINCT JSON a, b AS c, foo(d) AS e FROM ks.tbl WHERE a = 5 AND (b, c) >= (1, 'x') AND d IN (?, :e) AND f CONTAINS KEY 'g' ORDER BY b DESC LIMIT 10 ALLOW FILTERING",
+            "SELECT * FROM \"My\"\"Table\" WHERE \"Col\" = NULL",
+            "SELECT CAST(a AS text), -b * 2, m['k'][1..], u.f, TTL(c), ks.f(d, 1) AS g FROM foo",
+            "BEGIN UNLOGGED BATCH USING TIMESTAMP 5 INSERT INTO foo (a, b, c, d) VALUES (1, {'k': 'v'}, [1, 2], {3, 4}) IF NOT EXISTS USING TTL 7",
+            "INSERT INTO foo JSON '{\"a\": 1}'",
+            "UPDATE ks.foo USING TTL 5 SET a = 1, b[2] = 'x', c = c + {1}, d = (1, 2) WHERE e = 2 IF f = 3",
+            "DELETE a, b['x'] FROM foo USING TIMESTAMP 10 WHERE c = 1 IF EXISTS",
+            "USE \"KeySpace\"",
+            "TRUNCATE TABLE ks.foo",
+            "CREATE TABLE foo (a int PRIMARY KEY, b text)",
+            "SELECT * FROM foo; not a statement; DROP TABLE bar",
+            "-- leading\nSELECT * FROM foo; -- trailing\nCREATE TABLE bar (a int PRIMARY KEY -- key\n)",
+        ] {
+            assert_same(query);
+        }
+    }
+
+    #[test]
+    fn test_borrowed_text() {
+        let query = "SELECT \"a\"\"b\", \"c\" FROM foo WHERE d = 'x'";
+        let ast = CassandraAST::try_new(query).unwrap();
+        let select = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select,
+            statement => panic!("not a select {:?}", statement),
+        };
+        assert!(matches!(
+            select.table_name.name,
+            Identifier::Unquoted(Cow::Borrowed("foo"))
+        ));
+        assert_eq!(select.table_name.name, common::Identifier::parse("FOO"));
+        match &select.where_clause {
+            Some(WhereExpression::Relation(relation)) => assert_eq!(
+                relation.value,
+                Operand::Literal(Literal::String(Cow::Borrowed("'x'")))
+            ),
+            where_clause => panic!("not a relation {:?}", where_clause),
+        }
+        let names = select
+            .columns
+            .iter()
+            .map(|column| match column {
+                crate::borrowed::SelectElement::Column(named) => &named.name,
+                element => panic!("not a column {:?}", element),
+            })
+            .collect::<Vec<_>>();
+        // the escaped quote can not be borrowed.
+        assert!(matches!(names[0], Identifier::Quoted(Cow::Owned(txt)) if txt == "a\"b"));
+        assert!(matches!(names[1], Identifier::Quoted(Cow::Borrowed("c"))));
+    }
+
+    #[test]
+    fn test_other_statements() {
+        let ast = CassandraAST::try_new("DROP TABLE foo; bad statement").unwrap();
+        assert!(matches!(
+            ast.statements[0].statement,
+            CassandraStatement::Owned(_)
+        ));
+        assert!(matches!(
+            ast.statements[1].statement,
+            CassandraStatement::Unknown(_)
+        ));
+        assert!(ast.statements[1].has_error);
+        assert_eq!("bad statement", ast.extract_text(&ast.statements[1]));
+    }
+}
@@ -4,13 +4,16 @@ use crate::alter_materialized_view::AlterMaterializedView;
 use crate::alter_table::{AlterTable, AlterTableOperation};
 use crate::alter_type::{AlterType, AlterTypeOperation};
 use crate::begin_batch::{BatchType, BeginBatch};
+use crate::borrowed;
 use crate::cassandra_statement::CassandraStatement;
+use crate::comment::Comment;
 use crate::common::{
-    ColumnDefinition, DataType, DataTypeName, FQName, Identifier, Operand, OptionValue,
-    OrderClause, PrimaryKey, Privilege, PrivilegeType, RelationElement, RelationOperator, Resource,
-    TtlTimestamp, WithItem,
+    move_byte, Bindable, ColumnDefinition, CqlDuration, DataType, FQName, FunctionCall, Identifier,
+    MoveSpans, Operand, OptionValue, PrimaryKey, Privilege, PrivilegeType, RelationElement,
+    RelationOperator, RelationTarget, Resource, Span, TtlTimestamp, WithItem,
 };
 use crate::common_drop::CommonDrop;
+use crate::cql_parser::CqlParser;
 use crate::create_function::CreateFunction;
 use crate::create_index::{CreateIndex, IndexColumnType};
 use crate::create_keyspace::CreateKeyspace;
@@ -19,60 +22,225 @@ use crate::create_table::CreateTable;
 use crate::create_trigger::CreateTrigger;
 use crate::create_type::CreateType;
 use crate::create_user::CreateUser;
-use crate::delete::{Delete, IndexedColumn};
+use crate::delete::Delete;
+use crate::dialect::Dialect;
 use crate::drop_trigger::DropTrigger;
-use crate::insert::{Insert, InsertValues};
+use crate::insert::Insert;
 use crate::list_role::ListRole;
+use crate::parse_error::{ParseError, ParseErrorKind};
 use crate::role_common::RoleCommon;
-use crate::select::{Named, Select, SelectElement};
-use crate::update::{AssignmentElement, AssignmentOperator, Update};
-use tree_sitter::{Node, Tree, TreeCursor};
+use crate::select::{ArithmeticOperator, Select, SelectElement};
+use crate::statement_summary::{StatementCategory, StatementSummary};
+use crate::update::Update;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use tree_sitter::{InputEdit, Node, Point, Tree, TreeCursor};
 
 /// Functions for common manipulation of the nodes in the AST tree.
-struct NodeFuncs {}
+pub(crate) struct NodeFuncs {}
 impl NodeFuncs {
     /// get the string value of the node
-    pub fn as_string(node: &Node, source: &str) -> String {
-        node.utf8_text(source.as_bytes()).unwrap().to_string()
+    pub fn as_string(node: &Node, source: &str) -> Result<String, ParseError> {
+        NodeFuncs::as_str(node, source).map(|txt| txt.to_string())
     }
     /// the the value of the node as a boolean
-    pub fn as_boolean(node: &Node, source: &str) -> bool {
-        NodeFuncs::as_string(node, source).to_uppercase().eq("TRUE")
+    pub fn as_boolean(node: &Node, source: &str) -> Result<bool, ParseError> {
+        Ok(NodeFuncs::as_str(node, source)?.to_uppercase().eq("TRUE"))
+    }
+
+    /// get the string value of the node as a borrowed string
+    pub fn as_cow<'a>(node: &Node, source: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        NodeFuncs::as_str(node, source).map(Cow::Borrowed)
     }
 
     /// get the string value of the node
-    pub fn as_str<'a>(node: &'a Node, source: &'a str) -> &'a str {
-        node.utf8_text(source.as_bytes()).unwrap()
+    pub fn as_str<'a>(node: &Node, source: &'a str) -> Result<&'a str, ParseError> {
+        source
+            .get(node.start_byte()..node.end_byte())
+            .ok_or_else(|| ParseError::invalid_value(node, source))
+    }
+
+    /// get the value of the node as a number
+    pub fn as_number<T: FromStr>(node: &Node, source: &str) -> Result<T, ParseError> {
+        NodeFuncs::as_str(node, source)?
+            .parse::<T>()
+            .map_err(|_| ParseError::invalid_value(node, source))
+    }
+
+    /// move the cursor to the next sibling.  Returns an error if there is no next sibling, this
+    /// guards loops that search for a node that is not present in an invalid statement.
+    pub fn goto_next_sibling(cursor: &mut TreeCursor, source: &str) -> Result<(), ParseError> {
+        if cursor.goto_next_sibling() {
+            Ok(())
+        } else {
+            Err(ParseError::unexpected(&cursor.node(), source))
+        }
+    }
+
+    /// count the bind markers in the tree below the node.  Within an error a bind marker is a
+    /// `?`, or a `:` that is directly followed by a name.
+    pub fn count_bind_markers(node: &Node, source: &str) -> usize {
+        let mut count = 0;
+        let mut cursor = node.walk();
+        loop {
+            let node = cursor.node();
+            let marker = match node.kind() {
+                "bind_marker" | "?" => true,
+                ":" => node.next_sibling().map_or(false, |x| {
+                    x.start_byte() == node.end_byte()
+                        && source
+                            .get(x.start_byte()..)
+                            .and_then(|x| x.chars().next())
+                            .map_or(false, |x| x.is_alphabetic() || x == '_' || x == '"')
+                }),
+                _ => false,
+            };
+            if marker {
+                count += 1;
+            }
+            // the `?` or `:` of a bind marker node is not counted again.
+            if (!node.kind().eq("bind_marker") && cursor.goto_first_child())
+                || cursor.goto_next_sibling()
+            {
+                continue;
+            }
+            loop {
+                if !cursor.goto_parent() {
+                    return count;
+                }
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// get the location of the node in the source
+    pub fn span(node: &Node) -> Span {
+        Span::new(node.start_byte(), node.end_byte())
+    }
+}
+
+/// The constructs that the grammar does not accept but that the parser reads from the query
+/// text.  The syntax errors within the text of a recovered construct are not reported.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Construct {
+    /// altering a column or adding a masked column with `ALTER TABLE`.
+    AlterColumn,
+    /// the `IF EXISTS` of an `ALTER TABLE` or `ALTER TYPE` statement.
+    AlterIfExists,
+    /// a `BEGIN BATCH USING TIMESTAMP` clause.
+    BatchTimestamp,
+    /// a `BEGIN BATCH` clause before a statement that is not allowed in a batch.
+    BeginBatch,
+    /// the `CUSTOM` of a `CREATE CUSTOM INDEX` statement.
+    CustomIndex,
+    /// a data type the grammar does not know, such as `vector<float, 3>`.
+    DataType,
+    /// an `ALTER TABLE`, `GRANT` or `REVOKE` statement for Cassandra 5.0 data masking.
+    DataMasking,
+    /// a function call without arguments.
+    EmptyArguments,
+    /// a function name qualified by its keyspace.
+    FunctionName,
+    /// the `USING` class and `WITH OPTIONS` of a `CREATE INDEX` statement.
+    IndexClauses,
+    /// the `VALUES(column)` target of a `CREATE INDEX` statement.
+    IndexValues,
+    /// the values of an `INSERT` statement.
+    InsertValues,
+    /// the columns of a `CREATE TABLE` statement after a masked column.
+    MaskedColumns,
+    /// the `ORDER BY` clause of a select statement.
+    OrderBy,
+    /// the `SELECT_MASKED` permission.
+    SelectMasked,
+    /// the clauses of a select statement that follow the `FROM` clause.
+    SelectClauses,
+    /// the selected elements of a select statement.
+    SelectElements,
+    /// the assignments of an `UPDATE` statement.
+    UpdateAssignments,
+    /// a `USING TTL` or `USING TIMESTAMP` clause.
+    Using,
+    /// an `INSERT` or `UPDATE` statement the grammar does not accept at all.
+    Write,
+}
+
+/// A part of the query text that holds syntax errors but was read by the parser.  Every parse
+/// function that reads text the grammar did not accept adds the part to the `recovered` list it
+/// is given.  A part is only kept when the statement it is in was parsed, see
+/// `ParsedStatement::try_from_nodes`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Recovered {
+    /// the construct that was read.
+    pub construct: Construct,
+    /// the beginning byte of the text that was read.
+    pub start_byte: usize,
+    /// the ending byte of the text that was read.
+    pub end_byte: usize,
+}
+
+impl Recovered {
+    /// the construct read from the text between the byte offsets.
+    pub fn new(construct: Construct, start_byte: usize, end_byte: usize) -> Recovered {
+        Recovered {
+            construct,
+            start_byte,
+            end_byte,
+        }
+    }
+
+    /// the construct read from the text of the node.
+    fn node(construct: Construct, node: &Node) -> Recovered {
+        Recovered::new(construct, node.start_byte(), node.end_byte())
+    }
+
+    /// true if the error is a syntax error within the recovered text.  Syntax the dialect does
+    /// not support is still reported.
+    pub(crate) fn covers(&self, error: &ParseError) -> bool {
+        let syntax = match &error.kind {
+            ParseErrorKind::Error | ParseErrorKind::Missing(_) => true,
+            ParseErrorKind::Unsupported(feature, dialect) => dialect.supports(*feature),
+            _ => false,
+        };
+        syntax && self.start_byte <= error.start_byte && error.end_byte <= self.end_byte
+    }
+}
+
+impl MoveSpans for Recovered {
+    fn move_spans(&mut self, offset: isize) {
+        self.start_byte = move_byte(self.start_byte, offset);
+        self.end_byte = move_byte(self.end_byte, offset);
     }
 }
 
+/// the index class of the `USING` clause and the options of the `WITH OPTIONS` clause of a
+/// create index statement.
+type IndexClauses = (Option<String>, Vec<(String, String)>);
+
 /// The parser that walks the AST tree and produces a CassandraStatement.
 pub struct CassandraParser {}
 impl CassandraParser {
-    pub fn parse_identifier(node: &Node, source: &str) -> Identifier {
-        Identifier::parse(NodeFuncs::as_str(node, source))
+    pub fn parse_identifier(node: &Node, source: &str) -> Result<Identifier, ParseError> {
+        Ok(Identifier::parse(NodeFuncs::as_str(node, source)?))
     }
 
-    pub fn parse_truncate(node: &Node, source: &str) -> FQName {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        // consume until 'table_name'
-        while !cursor.node().kind().eq("table_name") {
-            cursor.goto_next_sibling();
-        }
-        CassandraParser::parse_table_name(&cursor.node(), source)
+    pub fn parse_truncate(node: &Node, source: &str) -> Result<FQName, ParseError> {
+        BorrowedParser::parse_truncate(node, source).map(borrowed::FQName::into_owned)
     }
 
-    pub fn parse_use(node: &Node, source: &str) -> Identifier {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        // consume 'USE'
-        cursor.goto_next_sibling();
-        Identifier::parse(NodeFuncs::as_str(&cursor.node(), source))
+    pub fn parse_use(node: &Node, source: &str) -> Result<Identifier, ParseError> {
+        BorrowedParser::parse_use(node, source).map(borrowed::Identifier::into_owned)
     }
 
     /// parse the alter materialized view command
-    pub fn parse_alter_materialized_view(node: &Node, source: &str) -> AlterMaterializedView {
+    pub fn parse_alter_materialized_view(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<AlterMaterializedView, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume ALTER
@@ -81,23 +249,23 @@ impl CassandraParser {
         cursor.goto_next_sibling();
         // consume VIEW
         cursor.goto_next_sibling();
-        AlterMaterializedView {
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+        Ok(AlterMaterializedView {
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
             with_clause: if cursor.goto_next_sibling() {
-                CassandraParser::parse_with_element(&cursor.node(), source)
+                CassandraParser::parse_with_element(&cursor.node(), source, recovered)?
             } else {
                 vec![]
             },
-        }
+        })
     }
     /// parse init_condition for aggregate data.
-    fn parse_init_condition(node: &Node, source: &str) -> InitCondition {
+    fn parse_init_condition(node: &Node, source: &str) -> Result<InitCondition, ParseError> {
         let mut cursor = node.walk();
         if cursor.node().kind().eq("init_cond_definition") {
             cursor.goto_first_child();
         }
-        match cursor.node().kind() {
-            "constant" => InitCondition::Constant(NodeFuncs::as_string(&cursor.node(), source)),
+        Ok(match cursor.node().kind() {
+            "constant" => InitCondition::Constant(NodeFuncs::as_string(&cursor.node(), source)?),
             "init_cond_list" => {
                 let mut entries = vec![];
                 cursor.goto_first_child();
@@ -107,7 +275,7 @@ impl CassandraParser {
                         entries.push(InitCondition::Constant(NodeFuncs::as_string(
                             &cursor.node(),
                             source,
-                        )));
+                        )?));
                     }
                 }
                 InitCondition::List(entries)
@@ -120,7 +288,7 @@ impl CassandraParser {
                         entries.push(CassandraParser::parse_init_condition(
                             &cursor.node(),
                             source,
-                        ));
+                        )?);
                     }
                 }
                 InitCondition::List(entries)
@@ -131,27 +299,31 @@ impl CassandraParser {
                 while cursor.goto_next_sibling() {
                     if cursor.node().kind().eq("init_cond_hash_item") {
                         cursor.goto_first_child();
-                        let key = NodeFuncs::as_string(&cursor.node(), source);
+                        let key = NodeFuncs::as_string(&cursor.node(), source)?;
                         cursor.goto_next_sibling();
                         //consume ','
                         cursor.goto_next_sibling();
-                        let value = CassandraParser::parse_init_condition(&cursor.node(), source);
+                        let value = CassandraParser::parse_init_condition(&cursor.node(), source)?;
                         entries.push((key, value));
                         cursor.goto_parent();
                     }
                 }
                 InitCondition::Map(entries)
             }
-            _ => unreachable!(),
-        }
+            _ => return Err(ParseError::unexpected(&cursor.node(), source)),
+        })
     }
     /// parse a create aggregate data statement
-    pub fn parse_create_aggregate(node: &Node, source: &str) -> Aggregate {
+    pub fn parse_create_aggregate(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Aggregate, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'CREATE'
         cursor.goto_next_sibling();
-        Aggregate {
+        Ok(Aggregate {
             or_replace: if cursor.node().kind().eq("OR") {
                 // consume 'OR'
                 cursor.goto_next_sibling();
@@ -176,12 +348,12 @@ impl CassandraParser {
                     false
                 }
             },
-            name: { CassandraParser::parse_table_name(&cursor.node(), source) },
+            name: { CassandraParser::parse_table_name(&cursor.node(), source)? },
             data_type: {
                 cursor.goto_next_sibling();
                 // consume '('
                 cursor.goto_next_sibling();
-                CassandraParser::parse_data_type(&cursor.node(), source)
+                CassandraParser::parse_data_type(&cursor.node(), source, recovered)?
             },
             sfunc: {
                 cursor.goto_next_sibling();
@@ -189,37 +361,41 @@ impl CassandraParser {
                 cursor.goto_next_sibling();
                 // consume 'SFUNC'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                CassandraParser::parse_table_name(&cursor.node(), source)?
             },
             stype: {
                 cursor.goto_next_sibling();
                 // consume 'STYPE'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_data_type(&cursor.node(), source)
+                CassandraParser::parse_data_type(&cursor.node(), source, recovered)?
             },
             finalfunc: {
                 cursor.goto_next_sibling();
                 // consume 'FINALFUNC'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                CassandraParser::parse_table_name(&cursor.node(), source)?
             },
             init_cond: {
                 cursor.goto_next_sibling();
                 // consume 'INITCOND'
                 cursor.goto_next_sibling();
                 // on 'init_cond_definition;
-                CassandraParser::parse_init_condition(&cursor.node(), source)
+                CassandraParser::parse_init_condition(&cursor.node(), source)?
             },
-        }
+        })
     }
 
     /// parse a create function statement
-    pub fn parse_function_data(node: &Node, source: &str) -> CreateFunction {
+    pub fn parse_function_data(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CreateFunction, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'CREATE'
         cursor.goto_next_sibling();
-        CreateFunction {
+        Ok(CreateFunction {
             or_replace: if cursor.node().kind().eq("OR") {
                 // consume 'OR'
                 cursor.goto_next_sibling();
@@ -244,7 +420,7 @@ impl CassandraParser {
                     false
                 }
             },
-            name: { CassandraParser::parse_table_name(&cursor.node(), source) },
+            name: { CassandraParser::parse_table_name(&cursor.node(), source)? },
             params: {
                 let mut params = vec![];
                 while !cursor.node().kind().eq(")") {
@@ -252,9 +428,10 @@ impl CassandraParser {
                         params.push(CassandraParser::parse_column_definition(
                             &cursor.node(),
                             source,
-                        ));
+                            recovered,
+                        )?);
                     }
-                    cursor.goto_next_sibling();
+                    NodeFuncs::goto_next_sibling(&mut cursor, source)?;
                 }
                 params
             },
@@ -272,33 +449,44 @@ impl CassandraParser {
                 cursor.goto_next_sibling();
                 // consume 'RETURNS'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_data_type(&cursor.node(), source)
+                CassandraParser::parse_data_type(&cursor.node(), source, recovered)?
             },
             language: {
                 cursor.goto_next_sibling();
                 // consume 'LANGUAGE'
                 cursor.goto_next_sibling();
-                NodeFuncs::as_string(&cursor.node(), source)
+                NodeFuncs::as_string(&cursor.node(), source)?
             },
             code_block: {
                 cursor.goto_next_sibling();
                 // consume 'AS'
                 cursor.goto_next_sibling();
-                NodeFuncs::as_string(&cursor.node(), source)
+                NodeFuncs::as_string(&cursor.node(), source)?
             },
-        }
+        })
     }
 
     /// parse an alter type statement
-    pub fn parse_alter_type(node: &Node, source: &str) -> AlterType {
+    pub fn parse_alter_type(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<AlterType, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'ALTER'
         cursor.goto_next_sibling();
         // consume 'TYPE'
         cursor.goto_next_sibling();
-        AlterType {
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+        let (name, if_exists) = CassandraParser::parse_altered_name(
+            &mut cursor,
+            "alter_type_operation",
+            source,
+            recovered,
+        )?;
+        Ok(AlterType {
+            name,
+            if_exists,
             operation: {
                 cursor.goto_next_sibling();
                 // on 'alter_type_operation'
@@ -309,12 +497,12 @@ impl CassandraParser {
                         // consume 'ALTER'
                         cursor.goto_next_sibling();
                         AlterTypeOperation::AlterColumnType(AlterColumnType {
-                            name: CassandraParser::parse_identifier(&cursor.node(), source),
+                            name: CassandraParser::parse_identifier(&cursor.node(), source)?,
                             data_type: {
                                 cursor.goto_next_sibling();
                                 // consume 'TYPE'
                                 cursor.goto_next_sibling();
-                                CassandraParser::parse_data_type(&cursor.node(), source)
+                                CassandraParser::parse_data_type(&cursor.node(), source, recovered)?
                             },
                         })
                     }
@@ -327,7 +515,8 @@ impl CassandraParser {
                                 columns.push(CassandraParser::parse_column_definition(
                                     &cursor.node(),
                                     source,
-                                ));
+                                    recovered,
+                                )?);
                             }
                         }
                         AlterTypeOperation::Add(columns)
@@ -340,31 +529,35 @@ impl CassandraParser {
                             if cursor.node().kind().eq("alter_type_rename_item") {
                                 cursor.goto_first_child();
                                 let first =
-                                    CassandraParser::parse_identifier(&cursor.node(), source);
+                                    CassandraParser::parse_identifier(&cursor.node(), source)?;
                                 cursor.goto_next_sibling();
                                 // consume 'TO'
                                 cursor.goto_next_sibling();
                                 let second =
-                                    CassandraParser::parse_identifier(&cursor.node(), source);
+                                    CassandraParser::parse_identifier(&cursor.node(), source)?;
                                 pairs.push((first, second));
                                 cursor.goto_parent();
                             }
                         }
                         AlterTypeOperation::Rename(pairs)
                     }
-                    _ => unreachable!(),
+                    _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                 }
             },
-        }
+        })
     }
 
     /// parse an create type statement
-    pub fn parse_create_type(node: &Node, source: &str) -> CreateType {
+    pub fn parse_create_type(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CreateType, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let mut result = CreateType {
             not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
             columns: vec![],
         };
         while cursor.goto_next_sibling() {
@@ -374,33 +567,38 @@ impl CassandraParser {
                     .push(CassandraParser::parse_column_definition(
                         &cursor.node(),
                         source,
-                    ));
+                        recovered,
+                    )?);
             }
         }
-        result
+        Ok(result)
     }
 
     /// parse a create trigger statement
-    pub fn parse_create_trigger(node: &Node, source: &str) -> CreateTrigger {
+    pub fn parse_create_trigger(node: &Node, source: &str) -> Result<CreateTrigger, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        CreateTrigger {
+        Ok(CreateTrigger {
             not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
             class: {
                 cursor.goto_next_sibling();
                 // consume 'USING'
                 cursor.goto_next_sibling();
-                NodeFuncs::as_string(&cursor.node(), source)
+                NodeFuncs::as_string(&cursor.node(), source)?
             },
-        }
+        })
     }
 
     /// parse the alter table operation.
-    fn parse_alter_table_operation(node: &Node, source: &str) -> AlterTableOperation {
+    fn parse_alter_table_operation(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<AlterTableOperation, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        match cursor.node().kind() {
+        Ok(match cursor.node().kind() {
             "alter_table_add" => {
                 let mut columns: Vec<ColumnDefinition> = vec![];
                 cursor.goto_first_child();
@@ -410,7 +608,8 @@ impl CassandraParser {
                         columns.push(CassandraParser::parse_column_definition(
                             &cursor.node(),
                             source,
-                        ));
+                            recovered,
+                        )?);
                     }
                 }
                 AlterTableOperation::Add(columns)
@@ -421,7 +620,7 @@ impl CassandraParser {
                 // consume 'DROP'
                 while cursor.goto_next_sibling() {
                     if cursor.node().kind().eq("object_name") {
-                        columns.push(CassandraParser::parse_identifier(&cursor.node(), source));
+                        columns.push(CassandraParser::parse_identifier(&cursor.node(), source)?);
                     }
                 }
                 AlterTableOperation::DropColumns(columns)
@@ -431,41 +630,68 @@ impl CassandraParser {
                 cursor.goto_first_child();
                 // consume the 'FROM'
                 cursor.goto_next_sibling();
-                let from = CassandraParser::parse_identifier(&cursor.node(), source);
+                let from = CassandraParser::parse_identifier(&cursor.node(), source)?;
                 cursor.goto_next_sibling();
                 // consume the 'TO'
                 cursor.goto_next_sibling();
-                let to = CassandraParser::parse_identifier(&cursor.node(), source);
+                let to = CassandraParser::parse_identifier(&cursor.node(), source)?;
                 AlterTableOperation::Rename((from, to))
             }
             "with_element" => AlterTableOperation::With(CassandraParser::parse_with_element(
                 &cursor.node(),
                 source,
-            )),
-            _ => unreachable!(),
-        }
+                recovered,
+            )?),
+            _ => return Err(ParseError::unexpected(&cursor.node(), source)),
+        })
     }
 
     /// parse an alter table statement.
-    pub fn parse_alter_table(node: &Node, source: &str) -> AlterTable {
+    pub fn parse_alter_table(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<AlterTable, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'ALTER'
         cursor.goto_next_sibling();
         // consume 'TABLE'
         cursor.goto_next_sibling();
-        // get the name
-        AlterTable {
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+        let (name, if_exists) = CassandraParser::parse_altered_name(
+            &mut cursor,
+            "alter_table_operation",
+            source,
+            recovered,
+        )?;
+        Ok(AlterTable {
+            name,
+            if_exists,
             operation: {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_alter_table_operation(&cursor.node(), source)
+                if cursor.node().is_error() {
+                    // the grammar does not accept `ALTER column ... MASKED`.
+                    let operation = source
+                        .get(cursor.node().start_byte()..node.end_byte())
+                        .and_then(|x| {
+                            CassandraParser::parse_unparsed_alter_column(x, node.end_byte())
+                        })
+                        .ok_or_else(|| ParseError::unexpected(&cursor.node(), source))?;
+                    recovered.push(Recovered::new(
+                        Construct::AlterColumn,
+                        cursor.node().start_byte(),
+                        node.end_byte(),
+                    ));
+                    operation
+                } else {
+                    CassandraParser::parse_alter_table_operation(&cursor.node(), source, recovered)?
+                }
             },
-        }
+        })
     }
 
     /// parse the primary key.
-    fn parse_primary_key_element(node: &Node, source: &str) -> PrimaryKey {
+    fn parse_primary_key_element(node: &Node, source: &str) -> Result<PrimaryKey, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let mut primary_key = PrimaryKey {
@@ -480,7 +706,7 @@ impl CassandraParser {
                         cursor.goto_first_child();
                         primary_key
                             .partition
-                            .push(CassandraParser::parse_identifier(&cursor.node(), source));
+                            .push(CassandraParser::parse_identifier(&cursor.node(), source)?);
                         cursor.goto_next_sibling();
                         // consume the ','
                         cursor.goto_next_sibling();
@@ -493,7 +719,7 @@ impl CassandraParser {
                                     .push(CassandraParser::parse_identifier(
                                         &cursor.node(),
                                         source,
-                                    ));
+                                    )?);
                             }
                             process = cursor.goto_next_sibling();
                         }
@@ -511,7 +737,7 @@ impl CassandraParser {
                                                 CassandraParser::parse_identifier(
                                                     &cursor.node(),
                                                     source,
-                                                ),
+                                                )?,
                                             );
                                         }
                                         process = cursor.goto_next_sibling();
@@ -526,7 +752,7 @@ impl CassandraParser {
                                                 CassandraParser::parse_identifier(
                                                     &cursor.node(),
                                                     source,
-                                                ),
+                                                )?,
                                             );
                                         }
                                         process = cursor.goto_next_sibling();
@@ -540,54 +766,111 @@ impl CassandraParser {
                     }
                     _ => primary_key
                         .partition
-                        .push(CassandraParser::parse_identifier(&cursor.node(), source)),
+                        .push(CassandraParser::parse_identifier(&cursor.node(), source)?),
                 }
             }
         }
-        primary_key
+        Ok(primary_key)
     }
 
     /// parse the data type
-    fn parse_data_type(node: &Node, source: &str) -> DataType {
+    fn parse_data_type(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<DataType, ParseError> {
+        // the grammar does not accept data types nested more than one level deep (e.g.
+        // `LIST<FROZEN<udt>>`) or keyspace qualified user defined types.  These produce errors
+        // within or directly after the data type, the data type is then read from the query
+        // text and must end where the data type node, or the error that follows it, ends.
+        let mut following = *node;
+        while following.next_sibling().is_none() {
+            match following.parent() {
+                Some(parent) => following = parent,
+                None => break,
+            }
+        }
+        let error_follows = following
+            .next_sibling()
+            .filter(|x| x.is_error() && x.start_byte() == node.end_byte());
+        if node.has_error() || error_follows.is_some() {
+            if !node.kind().eq("data_type") {
+                return Err(ParseError::unexpected(node, source));
+            }
+            let end_byte = error_follows.unwrap_or(*node).end_byte();
+            let mut data_type = source
+                .get(node.start_byte()..end_byte)
+                .and_then(DataType::parse_prefix)
+                .filter(|(_, rest)| rest.is_empty())
+                .map(|(data_type, _)| data_type)
+                .ok_or_else(|| ParseError::unexpected(node, source))?;
+            data_type.move_spans(node.start_byte() as isize);
+            recovered.push(Recovered::new(
+                Construct::DataType,
+                node.start_byte(),
+                end_byte,
+            ));
+            return Ok(data_type);
+        }
         let mut cursor = node.walk();
         cursor.goto_first_child();
+        let name_span = NodeFuncs::span(&cursor.node());
         // extracting the name works because it is limited to a single child item so the text is correct
-        let mut result = DataType {
-            name: DataTypeName::from(NodeFuncs::as_string(&cursor.node(), source).as_str()),
-            definition: vec![],
-        };
-
+        let name = NodeFuncs::as_str(&cursor.node(), source)?;
+        let mut types = vec![];
         if cursor.goto_next_sibling() {
             cursor.goto_first_child();
             // consume the '<'
             while cursor.goto_next_sibling() {
                 let kind = cursor.node().kind();
                 if !(kind.eq(",") || kind.eq(">")) {
-                    result.definition.push(DataTypeName::from(
-                        NodeFuncs::as_string(&cursor.node(), source).as_str(),
-                    ));
+                    types.push(CassandraParser::parse_data_type(
+                        &cursor.node(),
+                        source,
+                        recovered,
+                    )?);
                 }
             }
         }
-        result
+        DataType::new(name, types)
+            .map(|x| x.with_name_span(name_span))
+            .ok_or_else(|| ParseError::unexpected(node, source))
     }
 
     /// parse a column definition
-    fn parse_column_definition(node: &Node, source: &str) -> ColumnDefinition {
+    fn parse_column_definition(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<ColumnDefinition, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        ColumnDefinition {
-            name: CassandraParser::parse_identifier(&cursor.node(), source),
+        Ok(ColumnDefinition {
+            span: Some(NodeFuncs::span(node)),
+            name_span: Some(NodeFuncs::span(&cursor.node())),
+            name: CassandraParser::parse_identifier(&cursor.node(), source)?,
             data_type: {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_data_type(&cursor.node(), source)
+                CassandraParser::parse_data_type(&cursor.node(), source, recovered)?
             },
-            primary_key: cursor.goto_next_sibling(),
-        }
+            primary_key: {
+                let mut primary_key = false;
+                while cursor.goto_next_sibling() {
+                    primary_key |= cursor.node().kind().eq("primary_key_column");
+                }
+                primary_key
+            },
+            mask: None,
+            comment: None,
+        })
     }
 
     /// parse table options
-    fn parse_table_options(node: &Node, source: &str) -> Vec<WithItem> {
+    fn parse_table_options(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<WithItem>, ParseError> {
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
         let mut result: Vec<WithItem> = vec![];
@@ -595,21 +878,22 @@ impl CassandraParser {
             match cursor.node().kind() {
                 "table_option_item" => {
                     cursor.goto_first_child();
-                    let key = NodeFuncs::as_string(&cursor.node(), source);
+                    let key = NodeFuncs::as_string(&cursor.node(), source)?;
                     cursor.goto_next_sibling();
                     // consume the '='
                     cursor.goto_next_sibling();
                     //
                     if cursor.node().kind().eq("table_option_value") {
                         if key.to_uppercase().eq("ID") {
-                            result.push(WithItem::ID(NodeFuncs::as_string(&cursor.node(), source)));
+                            result
+                                .push(WithItem::ID(NodeFuncs::as_string(&cursor.node(), source)?));
                         } else {
                             result.push(WithItem::Option {
                                 key,
                                 value: OptionValue::Literal(NodeFuncs::as_string(
                                     &cursor.node(),
                                     source,
-                                )),
+                                )?),
                             });
                         }
                     } else if cursor.node().kind().eq("option_hash") {
@@ -618,7 +902,7 @@ impl CassandraParser {
                             value: OptionValue::Map(CassandraParser::parse_map(
                                 &cursor.node(),
                                 source,
-                            )),
+                            )?),
                         });
                     }
                     cursor.goto_parent();
@@ -633,17 +917,12 @@ impl CassandraParser {
                     cursor.goto_next_sibling();
                     // consume '('
                     cursor.goto_next_sibling();
-                    result.push(WithItem::ClusterOrder(OrderClause {
-                        name: CassandraParser::parse_identifier(&cursor.node(), source),
-                        desc: {
-                            // consume the name
-                            if cursor.goto_next_sibling() {
-                                cursor.node().kind().eq("DESC")
-                            } else {
-                                false
-                            }
-                        },
-                    }));
+                    result.push(WithItem::ClusterOrder(
+                        BorrowedParser::parse_order_clauses(&mut cursor, source, recovered)?
+                            .into_iter()
+                            .map(borrowed::OrderClause::into_owned)
+                            .collect(),
+                    ));
                     cursor.goto_parent();
                 }
                 "compact_storage" => result.push(WithItem::CompactStorage),
@@ -651,20 +930,33 @@ impl CassandraParser {
             }
             process = cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
     /// parse materialized view where statement
-    fn parse_materialized_where(node: &Node, source: &str) -> Vec<RelationElement> {
+    fn parse_materialized_where(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<RelationElement>, ParseError> {
         let mut relations: Vec<RelationElement> = vec![];
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consumer the WHERE
         while cursor.goto_next_sibling() {
             if cursor.node().kind().eq("column_not_null") {
+                let relation = cursor.node();
+                // the relation ends with NULL
+                let null = relation.child(relation.child_count() - 1);
                 cursor.goto_first_child();
                 relations.push(RelationElement {
-                    obj: Operand::Column(CassandraParser::parse_identifier(&cursor.node(), source)),
+                    span: Some(NodeFuncs::span(&relation)),
+                    obj_span: Some(NodeFuncs::span(&cursor.node())),
+                    value_span: null.map(|x| NodeFuncs::span(&x)),
+                    obj: RelationTarget::Column(CassandraParser::parse_identifier(
+                        &cursor.node(),
+                        source,
+                    )?),
                     oper: RelationOperator::IsNot,
                     value: Operand::Null,
                 });
@@ -674,60 +966,69 @@ impl CassandraParser {
                 relations.push(CassandraParser::parse_relation_element(
                     &cursor.node(),
                     source,
-                ));
+                    recovered,
+                )?);
             }
         }
-        relations
+        Ok(relations)
     }
 
     /// parse a create materialized view statement
-    pub fn parse_create_materialized_vew(node: &Node, source: &str) -> CreateMaterializedView {
+    pub fn parse_create_materialized_vew(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CreateMaterializedView, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'CREATE'
         cursor.goto_next_sibling();
-        CreateMaterializedView {
+        Ok(CreateMaterializedView {
             if_not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
             columns: {
                 cursor.goto_next_sibling();
                 // consume 'AS'
                 cursor.goto_next_sibling();
                 // consume 'select'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_column_list(&cursor.node(), source)
+                CassandraParser::parse_column_list(&cursor.node(), source)?
             },
             table: {
                 cursor.goto_next_sibling();
                 // consume 'FROM'
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                CassandraParser::parse_table_name(&cursor.node(), source)?
             },
             where_clause: {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_materialized_where(&cursor.node(), source)
+                CassandraParser::parse_materialized_where(&cursor.node(), source, recovered)?
             },
             key: {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_primary_key_element(&cursor.node(), source)
+                CassandraParser::parse_primary_key_element(&cursor.node(), source)?
             },
             with_clause: {
                 if cursor.goto_next_sibling() {
-                    CassandraParser::parse_with_element(&cursor.node(), source)
+                    CassandraParser::parse_with_element(&cursor.node(), source, recovered)?
                 } else {
                     vec![]
                 }
             },
-        }
+        })
     }
 
     /// parse a create table statement
-    pub fn parse_create_table(node: &Node, source: &str) -> CreateTable {
+    pub fn parse_create_table(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CreateTable, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let mut result = CreateTable {
             if_not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
             columns: vec![],
             key: None,
             with_clause: vec![],
@@ -744,13 +1045,22 @@ impl CassandraParser {
                                 .push(CassandraParser::parse_column_definition(
                                     &cursor.node(),
                                     source,
-                                ))
+                                    recovered,
+                                )?)
                         }
                         if cursor.node().kind().eq("primary_key_element") {
                             result.key = Some(CassandraParser::parse_primary_key_element(
                                 &cursor.node(),
                                 source,
-                            ));
+                            )?);
+                        }
+                        if cursor.node().is_error() {
+                            CassandraParser::parse_unparsed_columns(
+                                &cursor.node(),
+                                source,
+                                &mut result,
+                                recovered,
+                            );
                         }
                         process = cursor.goto_next_sibling();
                     }
@@ -758,43 +1068,55 @@ impl CassandraParser {
                 }
                 "with_element" => {
                     result.with_clause =
-                        CassandraParser::parse_with_element(&cursor.node(), source);
+                        CassandraParser::parse_with_element(&cursor.node(), source, recovered)?;
+                }
+                "ERROR" => {
+                    CassandraParser::parse_unparsed_columns(
+                        &cursor.node(),
+                        source,
+                        &mut result,
+                        recovered,
+                    );
                 }
                 _ => {}
             }
         }
-        result
+        Ok(result)
     }
 
     /// parse the `with` element.
-    fn parse_with_element(node: &Node, source: &str) -> Vec<WithItem> {
+    fn parse_with_element(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<WithItem>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         while cursor.goto_next_sibling() {
             if cursor.node().kind().eq("table_options") {
-                return CassandraParser::parse_table_options(&cursor.node(), source);
+                return CassandraParser::parse_table_options(&cursor.node(), source, recovered);
             }
         }
-        vec![]
+        Ok(vec![])
     }
 
-    fn parse_index_column_spec(node: &Node, source: &str) -> IndexColumnType {
+    fn parse_index_column_spec(node: &Node, source: &str) -> Result<IndexColumnType, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        match cursor.node().kind() {
+        Ok(match cursor.node().kind() {
             "index_keys_spec" => {
                 cursor.goto_first_child();
                 cursor.goto_next_sibling();
                 // consume '('
                 cursor.goto_next_sibling();
-                IndexColumnType::Keys(CassandraParser::parse_identifier(&cursor.node(), source))
+                IndexColumnType::Keys(CassandraParser::parse_identifier(&cursor.node(), source)?)
             }
             "index_entries_s_spec" => {
                 cursor.goto_first_child();
                 cursor.goto_next_sibling();
                 // consume '('
                 cursor.goto_next_sibling();
-                IndexColumnType::Entries(CassandraParser::parse_identifier(&cursor.node(), source))
+                IndexColumnType::Entries(CassandraParser::parse_identifier(&cursor.node(), source)?)
             }
             "index_full_spec" => {
                 cursor.goto_next_sibling();
@@ -803,23 +1125,53 @@ impl CassandraParser {
                 cursor.goto_next_sibling();
                 // consume '('
                 cursor.goto_next_sibling();
-                IndexColumnType::Full(CassandraParser::parse_identifier(&cursor.node(), source))
+                IndexColumnType::Full(CassandraParser::parse_identifier(&cursor.node(), source)?)
             }
-            _ => IndexColumnType::Column(CassandraParser::parse_identifier(&cursor.node(), source)),
-        }
+            _ => {
+                IndexColumnType::Column(CassandraParser::parse_identifier(&cursor.node(), source)?)
+            }
+        })
     }
 
     /// parse create index statement.
-    pub fn parse_index(node: &Node, source: &str) -> CreateIndex {
+    /// The grammar does not accept `CUSTOM`, `VALUES(column)`, `USING` or `WITH OPTIONS`, these
+    /// are read from the errors within and directly after the statement.
+    pub fn parse_index(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CreateIndex, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        CreateIndex {
+        let following = node.next_sibling().filter(|x| {
+            x.is_error()
+                && source
+                    .get(node.end_byte()..x.start_byte())
+                    .map_or(false, |x| x.trim().is_empty())
+        });
+        let mut result = CreateIndex {
+            custom: {
+                let custom = cursor.node().next_sibling().map_or(false, |x| {
+                    x.is_error()
+                        && NodeFuncs::as_str(&x, source)
+                            .map_or(false, |x| x.eq_ignore_ascii_case("CUSTOM"))
+                });
+                if custom {
+                    // consume CREATE
+                    cursor.goto_next_sibling();
+                    recovered.push(Recovered::node(Construct::CustomIndex, &cursor.node()));
+                }
+                custom
+            },
             if_not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
 
             name: {
                 let mut nm = None;
                 if cursor.node().kind() == "short_index_name" {
-                    nm = Some(Identifier::parse(NodeFuncs::as_str(&cursor.node(), source)));
+                    nm = Some(Identifier::parse(NodeFuncs::as_str(
+                        &cursor.node(),
+                        source,
+                    )?));
                     cursor.goto_next_sibling();
                 }
                 nm
@@ -828,19 +1180,499 @@ impl CassandraParser {
             table: {
                 // consume ON
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                CassandraParser::parse_table_name(&cursor.node(), source)?
             },
             column: {
                 cursor.goto_next_sibling();
                 // consume '('
                 cursor.goto_next_sibling();
-                CassandraParser::parse_index_column_spec(&cursor.node(), source)
+                let column = CassandraParser::parse_index_column_spec(&cursor.node(), source)?;
+                let values = cursor.node().next_sibling().filter(|x| x.is_error());
+                let values_column = values
+                    .and_then(|x| NodeFuncs::as_str(&x, source).ok())
+                    .and_then(|x| x.strip_prefix('('))
+                    .and_then(CassandraParser::unparsed_identifier)
+                    .filter(|(_, rest)| rest.trim().is_empty());
+                match (column, values, values_column) {
+                    (
+                        IndexColumnType::Column(Identifier::Unquoted(name)),
+                        Some(values),
+                        Some((values_column, _)),
+                    ) if name.eq_ignore_ascii_case("VALUES") => {
+                        recovered.push(Recovered::node(Construct::IndexValues, &values));
+                        IndexColumnType::Values(values_column)
+                    }
+                    (column, _, _) => column,
+                }
             },
+            using: None,
+            options: vec![],
+        };
+        if let Some(following) = following {
+            if let Some((using, options)) = NodeFuncs::as_str(&following, source)
+                .ok()
+                .and_then(CassandraParser::parse_unparsed_index_options)
+            {
+                result.using = using;
+                result.options = options;
+                recovered.push(Recovered::node(Construct::IndexClauses, &following));
+            }
+        }
+        Ok(result)
+    }
+
+    /// true if the error that follows the create index statement node holds the `USING` and
+    /// `WITH OPTIONS` clauses of the statement, see `parse_index`.
+    pub(crate) fn continues_index(node: &Node, error: &Node, source: &str) -> bool {
+        node.kind().eq("create_index")
+            && error.is_error()
+            && source
+                .get(node.end_byte()..error.start_byte())
+                .map_or(false, |x| x.trim().is_empty())
+            && NodeFuncs::as_str(error, source)
+                .ok()
+                .and_then(CassandraParser::parse_unparsed_index_options)
+                .is_some()
+    }
+
+    /// parse the `USING` and `WITH OPTIONS` clauses of a create index statement, and the `)`
+    /// that ends `VALUES(column)`, from the text that follows the statement.  Returns the index
+    /// class and the unquoted options, or `None` if the text holds anything else.
+    fn parse_unparsed_index_options(text: &str) -> Option<IndexClauses> {
+        let constant = CassandraParser::unparsed_constant;
+        let keyword = CassandraParser::unparsed_keyword;
+        let symbol = CassandraParser::unparsed_symbol;
+        let mut text = symbol(text, ')').unwrap_or(text);
+        let mut using = None;
+        if let Some(rest) = keyword(text, "USING") {
+            let (class, rest) = constant(rest)?;
+            using = Some(Operand::unescape(class));
+            text = rest;
+        }
+        let mut options = vec![];
+        if let Some(rest) = keyword(text, "WITH") {
+            text = symbol(symbol(keyword(rest, "OPTIONS")?, '=')?, '{')?;
+            while let Some((key, rest)) = constant(text) {
+                let (value, rest) = constant(symbol(rest, ':')?)?;
+                options.push((Operand::unescape(key), Operand::unescape(value)));
+                text = symbol(rest, ',').unwrap_or(rest);
+            }
+            text = symbol(text, '}')?;
+        }
+        if !text.trim().is_empty() {
+            return None;
+        }
+        Some((using, options))
+    }
+
+    /// the length of the quoted text at the start of `text`, e.g. a string constant (`'...'`)
+    /// or a quoted identifier (`"..."`).  A quote within the text is doubled.
+    fn unparsed_quoted_len(text: &str, quote: char) -> Option<usize> {
+        let quoted = text.strip_prefix(quote)?;
+        // a doubled quote reads as two adjacent quoted texts.
+        let mut len = 0;
+        loop {
+            len += quoted[len..].find(quote)? + 2;
+            if !quoted[len - 1..].starts_with(quote) {
+                return Some(len);
+            }
+        }
+    }
+
+    /// split the string constant or word (which may hold dots) from the start of `text`.
+    fn unparsed_constant(text: &str) -> Option<(&str, &str)> {
+        let text = text.trim_start();
+        let len = CassandraParser::unparsed_quoted_len(text, '\'').unwrap_or_else(|| {
+            text.find(|x: char| !(x.is_alphanumeric() || x == '_' || x == '.'))
+                .unwrap_or(text.len())
+        });
+        if len == 0 {
+            None
+        } else {
+            Some(text.split_at(len))
+        }
+    }
+
+    /// the text after the keyword at the start of `text`.
+    fn unparsed_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+        CassandraParser::unparsed_constant(text)
+            .filter(|(word, _)| word.eq_ignore_ascii_case(keyword))
+            .map(|(_, rest)| rest)
+    }
+
+    /// the text after the symbol at the start of `text`.
+    fn unparsed_symbol(text: &str, symbol: char) -> Option<&str> {
+        text.trim_start().strip_prefix(symbol)
+    }
+
+    /// split the relation operator from the start of `text`.
+    fn unparsed_relation_operator(text: &str) -> Option<(RelationOperator, &str)> {
+        let keyword = CassandraParser::unparsed_keyword;
+        let text = text.trim_start();
+        for (symbol, operator) in [
+            ("<=", RelationOperator::LessThanOrEqual),
+            (">=", RelationOperator::GreaterThanOrEqual),
+            ("<>", RelationOperator::NotEqual),
+            ("!=", RelationOperator::NotEqual),
+            ("<", RelationOperator::LessThan),
+            (">", RelationOperator::GreaterThan),
+            ("=", RelationOperator::Equal),
+        ] {
+            if let Some(rest) = text.strip_prefix(symbol) {
+                return Some((operator, rest));
+            }
+        }
+        let contains = |rest, key, plain| match keyword(rest, "KEY") {
+            Some(rest) => (key, rest),
+            None => (plain, rest),
+        };
+        if let Some(rest) = keyword(text, "NOT") {
+            return match keyword(rest, "IN") {
+                Some(rest) => Some((RelationOperator::NotIn, rest)),
+                None => Some(contains(
+                    keyword(rest, "CONTAINS")?,
+                    RelationOperator::NotContainsKey,
+                    RelationOperator::NotContains,
+                )),
+            };
+        }
+        if let Some(rest) = keyword(text, "CONTAINS") {
+            return Some(contains(
+                rest,
+                RelationOperator::ContainsKey,
+                RelationOperator::Contains,
+            ));
+        }
+        if let Some(rest) = keyword(text, "IS").and_then(|x| keyword(x, "NOT")) {
+            return Some((RelationOperator::IsNot, rest));
+        }
+        if let Some(rest) = keyword(text, "IN") {
+            return Some((RelationOperator::In, rest));
+        }
+        keyword(text, "LIKE").map(|rest| (RelationOperator::Like, rest))
+    }
+
+    /// split the identifier from the start of `text`.
+    fn unparsed_identifier(text: &str) -> Option<(Identifier, &str)> {
+        BorrowedParser::unparsed_identifier(text).map(|(name, rest)| (name.into_owned(), rest))
+    }
+
+    /// split `MASKED WITH function` from the start of `text`, the text ends at `end_byte` in
+    /// the query text.
+    fn unparsed_mask(text: &str, end_byte: usize) -> Option<(FunctionCall, &str)> {
+        let rest = CassandraParser::unparsed_keyword(text, "MASKED")?;
+        let rest = CassandraParser::unparsed_keyword(rest, "WITH")?;
+        match BorrowedParser::parse_unparsed_operand(rest, end_byte)? {
+            (borrowed::Operand::Func(function), rest) => Some((function.into_owned(), rest)),
+            _ => None,
+        }
+    }
+
+    /// the text after `PRIMARY KEY` at the start of `text`.
+    fn unparsed_primary_key(text: &str) -> Option<&str> {
+        CassandraParser::unparsed_keyword(text, "PRIMARY")
+            .and_then(|x| CassandraParser::unparsed_keyword(x, "KEY"))
+    }
+
+    /// split the `(key)` of a `PRIMARY KEY (key)` clause from the start of `text`.
+    fn parse_unparsed_key_definition(text: &str) -> Option<(PrimaryKey, &str)> {
+        let identifier = CassandraParser::unparsed_identifier;
+        let symbol = CassandraParser::unparsed_symbol;
+        let mut key = PrimaryKey {
+            partition: vec![],
+            clustering: vec![],
+        };
+        let mut text = symbol(text, '(')?;
+        if let Some(rest) = symbol(text, '(') {
+            text = rest;
+            loop {
+                let (column, rest) = identifier(text)?;
+                key.partition.push(column);
+                match symbol(rest, ',') {
+                    Some(rest) => text = rest,
+                    None => {
+                        text = symbol(rest, ')')?;
+                        break;
+                    }
+                }
+            }
+        } else {
+            let (column, rest) = identifier(text)?;
+            key.partition.push(column);
+            text = rest;
+        }
+        while let Some(rest) = symbol(text, ',') {
+            let (column, rest) = identifier(rest)?;
+            key.clustering.push(column);
+            text = rest;
+        }
+        Some((key, symbol(text, ')')?))
+    }
+
+    /// parse the column mask of a create table statement.  The grammar does not accept
+    /// `MASKED WITH`, the error that starts with the mask may also hold the rest of the column
+    /// list.
+    /// The table is only updated if the whole error can be read.
+    fn parse_unparsed_columns(
+        node: &Node,
+        source: &str,
+        table: &mut CreateTable,
+        recovered: &mut Vec<Recovered>,
+    ) -> Option<()> {
+        let position = |rest: &str| node.end_byte() - rest.len();
+        let text = NodeFuncs::as_str(node, source).ok()?;
+        let (mask, mut text) = CassandraParser::unparsed_mask(text, node.end_byte())?;
+        let masked_key = CassandraParser::unparsed_primary_key(text);
+        text = masked_key.unwrap_or(text);
+        let masked_end = position(text);
+        let mut columns = vec![];
+        let mut key = None;
+        while let Some(rest) = CassandraParser::unparsed_symbol(text, ',') {
+            if let Some(rest) = CassandraParser::unparsed_primary_key(rest) {
+                let (definition, rest) = CassandraParser::parse_unparsed_key_definition(rest)?;
+                key = Some(definition);
+                text = rest;
+                continue;
+            }
+            let start = position(rest.trim_start());
+            let (name, rest) = CassandraParser::unparsed_identifier(rest)?;
+            let name_span = Some(Span::new(start, position(rest)));
+            let (data_type, mut rest) = DataType::parse_prefix(rest)?;
+            let mut column = ColumnDefinition {
+                name,
+                data_type,
+                primary_key: false,
+                mask: None,
+                comment: None,
+                span: None,
+                name_span,
+            };
+            if let Some((mask, after)) = CassandraParser::unparsed_mask(rest, node.end_byte()) {
+                column.mask = Some(mask);
+                rest = after;
+            }
+            if let Some(after) = CassandraParser::unparsed_primary_key(rest) {
+                column.primary_key = true;
+                rest = after;
+            }
+            column.span = Some(Span::new(start, position(rest)));
+            columns.push(column);
+            text = rest;
+        }
+        if !text.trim().is_empty() {
+            return None;
+        }
+        let masked = table.columns.last_mut()?;
+        masked.mask = Some(mask);
+        masked.primary_key |= masked_key.is_some();
+        masked.span = masked.span.map(|x| Span::new(x.start_byte, masked_end));
+        table.columns.append(&mut columns);
+        if key.is_some() {
+            table.key = key;
+        }
+        recovered.push(Recovered::node(Construct::MaskedColumns, node));
+        Some(())
+    }
+
+    /// parse `ALTER column MASKED WITH function`, `ALTER column DROP MASKED` and adding
+    /// columns with `MASKED WITH function`, the grammar does not accept altering a column or
+    /// adding a masked column.  The text ends at `end_byte` in the query text.
+    fn parse_unparsed_alter_column(text: &str, end_byte: usize) -> Option<AlterTableOperation> {
+        let keyword = CassandraParser::unparsed_keyword;
+        if let Some(mut text) = keyword(text, "ADD") {
+            let position = |rest: &str| end_byte - rest.len();
+            let mut columns = vec![];
+            loop {
+                let start = position(text.trim_start());
+                let (name, rest) = CassandraParser::unparsed_identifier(text)?;
+                let name_span = Some(Span::new(start, position(rest)));
+                let (data_type, mut rest) = DataType::parse_prefix(rest)?;
+                let mut column = ColumnDefinition {
+                    name,
+                    data_type,
+                    primary_key: false,
+                    mask: None,
+                    comment: None,
+                    span: None,
+                    name_span,
+                };
+                if let Some((mask, after)) = CassandraParser::unparsed_mask(rest, end_byte) {
+                    column.mask = Some(mask);
+                    rest = after;
+                }
+                column.span = Some(Span::new(start, position(rest)));
+                columns.push(column);
+                match CassandraParser::unparsed_symbol(rest, ',') {
+                    Some(after) => text = after,
+                    None => {
+                        text = rest;
+                        break;
+                    }
+                }
+            }
+            return if text.trim().is_empty() {
+                Some(AlterTableOperation::Add(columns))
+            } else {
+                None
+            };
+        }
+        let (column, rest) = CassandraParser::unparsed_identifier(keyword(text, "ALTER")?)?;
+        let (operation, rest) = match CassandraParser::unparsed_mask(rest, end_byte) {
+            Some((mask, rest)) => (AlterTableOperation::Mask((column, mask)), rest),
+            None => (
+                AlterTableOperation::DropMask(column),
+                keyword(keyword(rest, "DROP")?, "MASKED")?,
+            ),
+        };
+        if rest.trim().is_empty() {
+            Some(operation)
+        } else {
+            None
+        }
+    }
+
+    /// split the keyspace or table resource from the start of `text`.  The text ends at
+    /// `end_byte` in the query text.
+    fn parse_unparsed_resource(text: &str, end_byte: usize) -> Option<(Resource, &str)> {
+        let keyword = CassandraParser::unparsed_keyword;
+        if let Some(rest) = keyword(text, "ALL").and_then(|x| keyword(x, "KEYSPACES")) {
+            return Some((Resource::AllKeyspaces, rest));
+        }
+        if let Some(rest) = keyword(text, "KEYSPACE") {
+            let (keyspace, rest) = CassandraParser::unparsed_identifier(rest)?;
+            return Some((Resource::Keyspace(keyspace), rest));
+        }
+        let text = keyword(text, "TABLE").unwrap_or(text);
+        let (name, rest) = CassandraParser::unparsed_name(text, end_byte)?;
+        Some((Resource::Table(name), rest))
+    }
+
+    /// split the name, which may start with the keyspace, from the start of `text`.  The text
+    /// ends at `end_byte` in the query text.
+    fn unparsed_name(text: &str, end_byte: usize) -> Option<(FQName, &str)> {
+        let text = text.trim_start();
+        let (first, rest) = CassandraParser::unparsed_identifier(text)?;
+        let (keyspace, name, rest) = match CassandraParser::unparsed_symbol(rest, '.') {
+            Some(rest) => {
+                let (name, rest) = CassandraParser::unparsed_identifier(rest)?;
+                (Some(first), name, rest)
+            }
+            None => (None, first, rest),
+        };
+        let name = FQName {
+            keyspace,
+            name,
+            span: Some(Span::new(end_byte - text.len(), end_byte - rest.len())),
+        };
+        Some((name, rest))
+    }
+
+    /// parse the name of the table or type that is altered and whether `IF EXISTS` is given.
+    /// The grammar does not accept `IF EXISTS` (Cassandra 4.1) and reads `IF` as the name, so
+    /// then the name is read from the text before the `operation` node.  The cursor is left on
+    /// the node before the operation.
+    fn parse_altered_name(
+        cursor: &mut TreeCursor,
+        operation: &str,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<(FQName, bool), ParseError> {
+        let node = cursor.node();
+        let keyword = CassandraParser::unparsed_keyword;
+        let text = source.get(node.start_byte()..).unwrap_or_default();
+        if keyword(text, "IF")
+            .and_then(|x| keyword(x, "EXISTS"))
+            .is_none()
+        {
+            return Ok((CassandraParser::parse_table_name(&node, source)?, false));
+        }
+        while let Some(next) = cursor.node().next_sibling() {
+            if next.kind().eq(operation) {
+                let name = source
+                    .get(node.start_byte()..next.start_byte())
+                    .and_then(|x| keyword(keyword(x, "IF")?, "EXISTS"))
+                    .and_then(|x| CassandraParser::unparsed_name(x, next.start_byte()))
+                    .filter(|(_, rest)| rest.trim().is_empty());
+                if let Some((name, _)) = name {
+                    recovered.push(Recovered::new(
+                        Construct::AlterIfExists,
+                        node.start_byte(),
+                        next.start_byte(),
+                    ));
+                    return Ok((name, true));
+                }
+                break;
+            }
+            cursor.goto_next_sibling();
+        }
+        Err(ParseError::unexpected(&node, source))
+    }
+
+    /// parse a statement that the grammar does not accept at all.  Only insert and update
+    /// statements (see `BorrowedParser::parse_unparsed_write`) and the Cassandra 5.0 data
+    /// masking statements are read: altering or adding masked columns with `ALTER TABLE` and
+    /// granting or revoking the `UNMASK` and `SELECT_MASKED` permissions.
+    pub(crate) fn parse_unparsed_statement(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Option<CassandraStatement> {
+        let statement = CassandraParser::unparsed_statement(node, source)?;
+        let construct = match statement {
+            CassandraStatement::Insert(_) | CassandraStatement::Update(_) => Construct::Write,
+            _ => Construct::DataMasking,
+        };
+        recovered.push(Recovered::node(construct, node));
+        Some(statement)
+    }
+
+    /// parse a statement that the grammar does not accept, see `parse_unparsed_statement`.
+    fn unparsed_statement(node: &Node, source: &str) -> Option<CassandraStatement> {
+        if let Some(statement) = BorrowedParser::unparsed_write(node, source) {
+            return Some(statement.into_owned());
+        }
+        let keyword = CassandraParser::unparsed_keyword;
+        let text = NodeFuncs::as_str(node, source).ok()?;
+        if let Some(rest) = keyword(text, "ALTER").and_then(|x| keyword(x, "TABLE")) {
+            let if_exists = keyword(rest, "IF").and_then(|x| keyword(x, "EXISTS"));
+            let (name, rest) =
+                CassandraParser::unparsed_name(if_exists.unwrap_or(rest), node.end_byte())?;
+            return Some(CassandraStatement::AlterTable(AlterTable {
+                name,
+                if_exists: if_exists.is_some(),
+                operation: CassandraParser::parse_unparsed_alter_column(rest, node.end_byte())?,
+            }));
+        }
+        let (command, rest) = CassandraParser::unparsed_constant(text)?;
+        let (privilege, rest) = CassandraParser::unparsed_constant(rest)?;
+        let privilege = match privilege.to_uppercase().as_str() {
+            "UNMASK" => PrivilegeType::Unmask,
+            "SELECT_MASKED" => PrivilegeType::SelectMasked,
+            _ => return None,
+        };
+        let (resource, rest) =
+            CassandraParser::parse_unparsed_resource(keyword(rest, "ON")?, node.end_byte())?;
+        let grant = command.eq_ignore_ascii_case("GRANT");
+        let role = keyword(rest, if grant { "TO" } else { "FROM" })?.trim();
+        if role.is_empty() || role.contains(char::is_whitespace) {
+            return None;
+        }
+        let privilege = Privilege {
+            privilege,
+            resource: Some(resource),
+            role: Some(role.to_string()),
+        };
+        if grant {
+            Some(CassandraStatement::Grant(privilege))
+        } else if command.eq_ignore_ascii_case("REVOKE") {
+            Some(CassandraStatement::Revoke(privilege))
+        } else {
+            None
         }
     }
 
     /// parse the list roles statement
-    pub fn parse_list_role_data(node: &Node, source: &str) -> ListRole {
+    pub fn parse_list_role_data(node: &Node, source: &str) -> Result<ListRole, ParseError> {
         let mut cursor = node.walk();
         let mut result = ListRole {
             of: None,
@@ -853,20 +1685,20 @@ impl CassandraParser {
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
                 "role" => {
-                    result.of = Some(CassandraParser::parse_identifier(&cursor.node(), source))
+                    result.of = Some(CassandraParser::parse_identifier(&cursor.node(), source)?)
                 }
                 "NORECURSIVE" => result.no_recurse = true,
                 _ => {}
             }
         }
-        result
+        Ok(result)
     }
 
     /// parse a resource type
-    fn parse_resource(node: &Node, source: &str) -> Resource {
+    fn parse_resource(node: &Node, source: &str) -> Result<Resource, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        match cursor.node().kind() {
+        Ok(match cursor.node().kind() {
             "ALL" => {
                 cursor.goto_next_sibling();
                 match cursor.node().kind() {
@@ -879,43 +1711,43 @@ impl CassandraParser {
                             Resource::AllFunctions(Some(NodeFuncs::as_string(
                                 &cursor.node(),
                                 source,
-                            )))
+                            )?))
                         } else {
                             Resource::AllFunctions(None)
                         }
                     }
                     "KEYSPACES" => Resource::AllKeyspaces,
                     "ROLES" => Resource::AllRoles,
-                    _ => unreachable!(),
+                    _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                 }
             }
             "FUNCTION" => {
                 cursor.goto_next_sibling();
-                Resource::Function(CassandraParser::parse_dotted_name(&mut cursor, source))
+                Resource::Function(CassandraParser::parse_dotted_name(&mut cursor, source)?)
             }
             "KEYSPACE" => {
                 cursor.goto_next_sibling();
-                Resource::Keyspace(CassandraParser::parse_identifier(&cursor.node(), source))
+                Resource::Keyspace(CassandraParser::parse_identifier(&cursor.node(), source)?)
             }
             "ROLE" => {
                 cursor.goto_next_sibling();
-                Resource::Role(NodeFuncs::as_string(&cursor.node(), source))
+                Resource::Role(NodeFuncs::as_string(&cursor.node(), source)?)
             }
             "TABLE" => {
                 cursor.goto_next_sibling();
-                Resource::Table(CassandraParser::parse_dotted_name(&mut cursor, source))
+                Resource::Table(CassandraParser::parse_dotted_name(&mut cursor, source)?)
             }
-            _ => Resource::Table(CassandraParser::parse_dotted_name(&mut cursor, source)),
-        }
+            _ => Resource::Table(CassandraParser::parse_dotted_name(&mut cursor, source)?),
+        })
     }
 
     /// parse the create role statement
-    pub fn parse_create_role(node: &Node, source: &str) -> RoleCommon {
+    pub fn parse_create_role(node: &Node, source: &str) -> Result<RoleCommon, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let if_not_exists = CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor);
         let mut result = RoleCommon {
-            name: CassandraParser::parse_identifier(&cursor.node(), source),
+            name: CassandraParser::parse_identifier(&cursor.node(), source)?,
             password: None,
             superuser: None,
             login: None,
@@ -934,37 +1766,37 @@ impl CassandraParser {
                             cursor.goto_next_sibling();
                             // consume the '='
                             cursor.goto_next_sibling();
-                            result.password = Some(NodeFuncs::as_string(&cursor.node(), source));
+                            result.password = Some(NodeFuncs::as_string(&cursor.node(), source)?);
                             cursor.goto_next_sibling();
                         }
                         "LOGIN" => {
                             cursor.goto_next_sibling();
                             // consume the '='
                             cursor.goto_next_sibling();
-                            result.login = Some(NodeFuncs::as_boolean(&cursor.node(), source));
+                            result.login = Some(NodeFuncs::as_boolean(&cursor.node(), source)?);
                             cursor.goto_next_sibling();
                         }
                         "SUPERUSER" => {
                             cursor.goto_next_sibling();
                             // consume the '='
                             cursor.goto_next_sibling();
-                            result.superuser = Some(NodeFuncs::as_boolean(&cursor.node(), source));
+                            result.superuser = Some(NodeFuncs::as_boolean(&cursor.node(), source)?);
                             cursor.goto_next_sibling();
                         }
                         "OPTIONS" => {
                             cursor.goto_next_sibling();
                             // consume the '='
                             cursor.goto_next_sibling();
-                            result.options = CassandraParser::parse_map(&cursor.node(), source);
+                            result.options = CassandraParser::parse_map(&cursor.node(), source)?;
                             cursor.goto_next_sibling();
                         }
-                        _ => unreachable!(),
+                        _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                     }
                     cursor.goto_parent();
                 }
             }
         }
-        result
+        Ok(result)
     }
 
     /// consume 2 keywords and check the not exists flag.
@@ -1004,12 +1836,12 @@ impl CassandraParser {
     }
 
     /// parse the create keyspace command
-    pub fn parse_keyspace_data(node: &Node, source: &str) -> CreateKeyspace {
+    pub fn parse_keyspace_data(node: &Node, source: &str) -> Result<CreateKeyspace, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let if_not_exists = CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor);
         let mut result = CreateKeyspace {
-            name: CassandraParser::parse_identifier(&cursor.node(), source),
+            name: CassandraParser::parse_identifier(&cursor.node(), source)?,
             replication: vec![],
             durable_writes: None,
             if_not_exists,
@@ -1017,7 +1849,7 @@ impl CassandraParser {
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
                 "replication_list" => {
-                    result.replication = CassandraParser::parse_map(&cursor.node(), source);
+                    result.replication = CassandraParser::parse_map(&cursor.node(), source)?;
                 }
                 "durable_writes" => {
                     cursor.goto_first_child();
@@ -1025,24 +1857,24 @@ impl CassandraParser {
                     cursor.goto_next_sibling();
                     // consume "="
                     cursor.goto_next_sibling();
-                    result.durable_writes = Some(NodeFuncs::as_boolean(&cursor.node(), source));
+                    result.durable_writes = Some(NodeFuncs::as_boolean(&cursor.node(), source)?);
                     cursor.goto_parent();
                 }
                 _ => {}
             }
         }
 
-        result
+        Ok(result)
     }
 
     /// parse the create user statement
-    pub fn parse_create_user(node: &Node, source: &str) -> CreateUser {
+    pub fn parse_create_user(node: &Node, source: &str) -> Result<CreateUser, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let if_not_exists = CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor);
 
         let mut result = CreateUser {
-            name: CassandraParser::parse_identifier(&cursor.node(), source),
+            name: CassandraParser::parse_identifier(&cursor.node(), source)?,
             password: None,
             superuser: false,
             no_superuser: false,
@@ -1058,7 +1890,7 @@ impl CassandraParser {
                         cursor.goto_first_child();
                         // consumer "PASSWORD"
                         cursor.goto_next_sibling();
-                        result.password = Some(NodeFuncs::as_string(&cursor.node(), source));
+                        result.password = Some(NodeFuncs::as_string(&cursor.node(), source)?);
                         cursor.goto_parent();
                     }
                     "user_super_user" => {
@@ -1066,145 +1898,768 @@ impl CassandraParser {
                         match cursor.node().kind() {
                             "SUPERUSER" => result.superuser = true,
                             "NOSUPERUSER" => result.no_superuser = true,
-                            _ => unreachable!(),
+                            _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                         }
                     }
-                    _ => unreachable!(),
+                    _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                 }
             }
         }
-        result
+        Ok(result)
     }
 
-    fn parse_update_assignments(node: &Node, source: &str) -> Vec<AssignmentElement> {
-        let mut result = vec![];
-        let mut cursor = node.walk();
-        let mut process = cursor.goto_first_child();
-        while process {
-            if cursor.node().kind().eq("assignment_element") {
-                result.push(CassandraParser::parse_assignment_element(
-                    &cursor.node(),
-                    source,
-                ));
+    fn check_begin_batch(
+        cursor: &mut TreeCursor,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Option<BeginBatch>, ParseError> {
+        Ok(if cursor.node().kind().eq("begin_batch") {
+            let mut result = CassandraParser::parse_begin_batch(&cursor.node(), source)?;
+            cursor.goto_next_sibling();
+            // the grammar does not accept a bind marker for the timestamp, the clause is then
+            // read from the error that holds it.  A batch does not take a `TTL`.
+            let error = cursor.node();
+            if let Some(using) = Some(error)
+                .filter(Node::is_error)
+                .and_then(|x| CassandraParser::unparsed_using_clause(&x, &x, source))
+                .filter(|x| x.ttl.is_none() && x.timeout.is_none())
+            {
+                result.timestamp = using.timestamp;
+                recovered.push(Recovered::node(Construct::BatchTimestamp, &error));
+                cursor.goto_next_sibling();
             }
-            process = cursor.goto_next_sibling();
-        }
-        result
+            Some(result)
+        } else {
+            None
+        })
     }
 
-    fn check_begin_batch(cursor: &mut TreeCursor, source: &str) -> Option<BeginBatch> {
-        if cursor.node().kind().eq("begin_batch") {
-            let result = Some(CassandraParser::parse_begin_batch(&cursor.node(), source));
-            cursor.goto_next_sibling();
-            result
+    /// parse the `BEGIN BATCH` clause from the text of an error.  The grammar only accepts the
+    /// clause before an insert, update or delete statement, before any other statement the
+    /// clause is an error of its own.
+    pub(crate) fn unparsed_begin_batch(node: &Node, source: &str) -> Option<BeginBatch> {
+        let keyword = CassandraParser::unparsed_keyword;
+        if !node.is_error() {
+            return None;
+        }
+        let rest = keyword(NodeFuncs::as_str(node, source).ok()?, "BEGIN")?;
+        let (ty, rest) = if let Some(rest) = keyword(rest, "UNLOGGED") {
+            (BatchType::Unlogged, rest)
+        } else if let Some(rest) = keyword(rest, "COUNTER") {
+            (BatchType::Counter, rest)
         } else {
-            None
+            (BatchType::Logged, keyword(rest, "LOGGED").unwrap_or(rest))
+        };
+        let rest = keyword(rest, "BATCH")?;
+        if rest.trim().is_empty() {
+            return Some(BeginBatch {
+                ty,
+                timestamp: None,
+            });
+        }
+        match BorrowedParser::unparsed_using(keyword(rest, "USING")?, node.end_byte())? {
+            (using, rest)
+                if using.ttl.is_none() && using.timeout.is_none() && rest.trim().is_empty() =>
+            {
+                Some(BeginBatch {
+                    ty,
+                    timestamp: using.timestamp,
+                })
+            }
+            _ => None,
         }
     }
+
     /// parse the update statement.
-    pub fn parse_update(node: &Node, source: &str) -> Update {
-        let mut cursor = node.walk();
+    pub fn parse_update(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Update, ParseError> {
+        BorrowedParser::parse_update(node, source, recovered).map(borrowed::Update::into_owned)
+    }
+
+    /// parse the privilege
+    fn parse_privilege_type(node: &Node, source: &str) -> Result<PrivilegeType, ParseError> {
+        Ok(
+            match NodeFuncs::as_string(node, source)?.to_uppercase().as_str() {
+                "ALL" | "ALL PERMISSIONS" => PrivilegeType::All,
+                "ALTER" => PrivilegeType::Alter,
+                "AUTHORIZE" => PrivilegeType::Authorize,
+                "DESCRIBE" => PrivilegeType::Describe,
+                "EXECUTE" => PrivilegeType::Execute,
+                "CREATE" => PrivilegeType::Create,
+                "DROP" => PrivilegeType::Drop,
+                "MODIFY" => PrivilegeType::Modify,
+                "SELECT" => PrivilegeType::Select,
+                _ => return Err(ParseError::unexpected(node, source)),
+            },
+        )
+    }
+
+    /// parse the privilege data.
+    pub fn parse_privilege(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Privilege, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+
+        let mut privilege: Option<PrivilegeType> = None;
+        let mut resource: Option<Resource> = None;
+        let mut role: Option<String> = None;
+        // consume 'GRANT/REVOKE'
+        while cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "privilege" => {
+                    privilege = Some(CassandraParser::parse_privilege_type(
+                        &cursor.node(),
+                        source,
+                    )?);
+                }
+                "resource" => {
+                    resource = Some(CassandraParser::parse_resource(&cursor.node(), source)?);
+                }
+                "role" => role = Some(NodeFuncs::as_string(&cursor.node(), source)?),
+                // the grammar reads `SELECT_MASKED` as `SELECT` followed by an error.
+                "ERROR"
+                    if privilege == Some(PrivilegeType::Select)
+                        && NodeFuncs::as_str(&cursor.node(), source)?
+                            .eq_ignore_ascii_case("_MASKED") =>
+                {
+                    privilege = Some(PrivilegeType::SelectMasked);
+                    recovered.push(Recovered::node(Construct::SelectMasked, &cursor.node()));
+                }
+                _ => {}
+            }
+        }
+        Ok(Privilege {
+            privilege: privilege.ok_or_else(|| ParseError::unexpected(node, source))?,
+            resource,
+            role,
+        })
+    }
+
+    pub fn parse_delete_statement(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Delete, ParseError> {
+        BorrowedParser::parse_delete_statement(node, source, recovered)
+            .map(borrowed::Delete::into_owned)
+    }
+
+    /// parse an insert statement.
+    pub fn parse_insert(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Insert, ParseError> {
+        BorrowedParser::parse_insert(node, source, recovered).map(borrowed::Insert::into_owned)
+    }
+
+    /// parse a column list
+    fn parse_column_list(node: &Node, source: &str) -> Result<Vec<Identifier>, ParseError> {
+        Ok(BorrowedParser::parse_column_list(node, source)?
+            .into_iter()
+            .map(borrowed::Identifier::into_owned)
+            .collect())
+    }
+
+    /// parse the using timestamp sttement.
+    fn parse_using_timestamp(
+        node: &Node,
+        source: &str,
+    ) -> Result<Option<Bindable<u64>>, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume "USING"
+        cursor.goto_next_sibling();
+        // consume "TIMESTAMP"
+        cursor.goto_next_sibling();
+        Ok(Some(Bindable::Literal(NodeFuncs::as_number(
+            &cursor.node(),
+            source,
+        )?)))
+    }
+
+    /// parse the using ttl timestamp element.  `error` is the error that follows the element
+    /// and holds the rest of the clause, e.g. `AND TTL ?`.  The grammar does not accept a bind
+    /// marker for the `TTL` or `TIMESTAMP` so a clause that holds one is read from its text.
+    fn parse_ttl_timestamp(
+        node: &Node,
+        error: Option<&Node>,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<TtlTimestamp, ParseError> {
+        let end = error.unwrap_or(node);
+        if end.has_error() {
+            return match CassandraParser::unparsed_using_clause(node, end, source) {
+                Some(using) => {
+                    recovered.push(Recovered::new(
+                        Construct::Using,
+                        node.start_byte(),
+                        end.end_byte(),
+                    ));
+                    Ok(using)
+                }
+                None => Err(ParseError::unexpected(end, source)),
+            };
+        }
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume "USING"
+        let mut ttl = None;
+        let mut timestamp = None;
+        while (ttl.is_none() || timestamp.is_none()) && cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "ttl" => {
+                    ttl = Some(Bindable::Literal(NodeFuncs::as_number(
+                        &cursor.node(),
+                        source,
+                    )?));
+                }
+                "time" => {
+                    timestamp = Some(Bindable::Literal(NodeFuncs::as_number(
+                        &cursor.node(),
+                        source,
+                    )?));
+                }
+                _ => {}
+            }
+        }
+        Ok(TtlTimestamp {
+            ttl,
+            timestamp,
+            timeout: None,
+        })
+    }
+
+    /// read the `USING` clause from the text of the nodes from `node` to `end`.  Returns
+    /// `None` if the text holds anything else.
+    fn unparsed_using_clause(node: &Node, end: &Node, source: &str) -> Option<TtlTimestamp> {
+        let text = source.get(node.start_byte()..end.end_byte())?;
+        match BorrowedParser::unparsed_using(
+            CassandraParser::unparsed_keyword(text, "USING")?,
+            end.end_byte(),
+        )? {
+            (using, rest) if rest.trim().is_empty() => Some(using),
+            _ => None,
+        }
+    }
+
+    /// true if the error that follows the using ttl timestamp element holds the rest of the
+    /// clause, see `parse_ttl_timestamp`.
+    fn continues_using(node: &Node, error: &Node, source: &str) -> bool {
+        node.kind().eq("using_ttl_timestamp")
+            && error.is_error()
+            && CassandraParser::unparsed_using_clause(node, error, source).is_some()
+    }
+
+    /// parse the `FROM` clause
+    pub fn parse_from_spec(node: &Node, source: &str) -> Result<FQName, ParseError> {
+        BorrowedParser::parse_from_spec(node, source).map(borrowed::FQName::into_owned)
+    }
+
+    /// parse a name that may have a keyspace specified.
+    fn parse_dotted_name(cursor: &mut TreeCursor, source: &str) -> Result<FQName, ParseError> {
+        BorrowedParser::parse_dotted_name(cursor, source).map(borrowed::FQName::into_owned)
+    }
+
+    /// parse a table name
+    fn parse_table_name(node: &Node, source: &str) -> Result<FQName, ParseError> {
+        BorrowedParser::parse_table_name(node, source).map(borrowed::FQName::into_owned)
+    }
+
+    /// parses lists of option_hash_item or replication_list_item
+    fn parse_map(node: &Node, source: &str) -> Result<Vec<(String, String)>, ParseError> {
+        let mut cursor = node.walk();
+
+        cursor.goto_first_child();
+        let mut entries: Vec<(String, String)> = vec![];
+        // { operand : operand, ... }
+        // we are on the '{' so we can just skip it
+        while cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "}" | "," => {}
+                "option_hash_item" | "replication_list_item" => {
+                    cursor.goto_first_child();
+                    let key = NodeFuncs::as_string(&cursor.node(), source)?;
+                    cursor.goto_next_sibling();
+                    // consume the ':'
+                    cursor.goto_next_sibling();
+                    let value = NodeFuncs::as_string(&cursor.node(), source)?;
+                    entries.push((key, value));
+                    cursor.goto_parent();
+                }
+                _ => return Err(ParseError::unexpected(&cursor.node(), source)),
+            }
+        }
+        cursor.goto_parent();
+        Ok(entries)
+    }
+
+    /// parse the statements of a batch, from the statement that starts with `BEGIN BATCH` to
+    /// `APPLY BATCH`.
+    pub fn parse_batch(
+        nodes: &[Node],
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CassandraStatement, ParseError> {
+        let mut begin_batch = None;
+        let mut statements = vec![];
+        let mut statement_nodes = nodes[..nodes.len() - 1].iter().peekable();
+        // the clause is an error of its own before a statement that is not allowed in a batch.
+        if let Some(begin) = CassandraParser::unparsed_begin_batch(&nodes[0], source) {
+            recovered.push(Recovered::node(Construct::BeginBatch, &nodes[0]));
+            begin_batch = Some(begin);
+            statement_nodes.next();
+        }
+        while let Some(node) = statement_nodes.next() {
+            // an insert statement may be followed by the error that holds its `USING` clause.
+            let mut statement = match statement_nodes
+                .next_if(|x| BorrowedParser::continues_insert(node, x, source))
+            {
+                Some(error) => CassandraStatement::Insert(
+                    BorrowedParser::parse_continued_insert(node, error, source, recovered)?
+                        .into_owned(),
+                ),
+                None => CassandraStatement::try_from_node(node, source, recovered)?,
+            };
+            let (begin, span) = match &mut statement {
+                CassandraStatement::Delete(delete) => (delete.begin_batch.take(), &mut delete.span),
+                CassandraStatement::Insert(insert) => (insert.begin_batch.take(), &mut insert.span),
+                CassandraStatement::Update(update) => (update.begin_batch.take(), &mut update.span),
+                _ => (None, &mut None),
+            };
+            // the `BEGIN BATCH` clause belongs to the batch, not to the statement.
+            if let (Some(_), Some(span), Some(first)) = (&begin, span, node.child(1)) {
+                span.start_byte = first.start_byte();
+            }
+            begin_batch = begin_batch.or(begin);
+            statements.push(statement);
+        }
+        match begin_batch {
+            Some(begin_batch) => Ok(CassandraStatement::Batch {
+                ty: begin_batch.ty,
+                using: TtlTimestamp {
+                    ttl: None,
+                    timestamp: begin_batch.timestamp,
+                    timeout: None,
+                },
+                statements,
+            }),
+            None => Err(ParseError::unexpected(&nodes[0], source)),
+        }
+    }
+
+    /// parse a `BEGIN BATCH` clause
+    fn parse_begin_batch(node: &Node, source: &str) -> Result<BeginBatch, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume BEGIN
+        cursor.goto_next_sibling();
+
+        let node = cursor.node();
+        let ty = match node.kind() {
+            "COUNTER" => {
+                cursor.goto_next_sibling();
+                BatchType::Counter
+            }
+            "UNLOGGED" => {
+                cursor.goto_next_sibling();
+                BatchType::Unlogged
+            }
+            "LOGGED" => {
+                cursor.goto_next_sibling();
+                BatchType::Logged
+            }
+            _ => BatchType::Logged,
+        };
+
+        // consume BATCH
+        let timestamp = if cursor.goto_next_sibling() {
+            // we should have using_timestamp_spec
+            CassandraParser::parse_using_timestamp(&cursor.node(), source)?
+        } else {
+            None
+        };
+
+        Ok(BeginBatch { ty, timestamp })
+    }
+
+    pub fn parse_select_elements(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<SelectElement>, ParseError> {
+        Ok(
+            BorrowedParser::parse_select_elements(node, source, recovered)?
+                .into_iter()
+                .map(borrowed::SelectElement::into_owned)
+                .collect(),
+        )
+    }
+
+    /// parse a select statement
+    pub fn parse_select(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Select, ParseError> {
+        BorrowedParser::parse_select(node, source, recovered).map(borrowed::Select::into_owned)
+    }
+
+    /// parse a relaiton element.
+    fn parse_relation_element(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<RelationElement, ParseError> {
+        BorrowedParser::parse_relation_element(node, source, recovered)
+            .map(borrowed::RelationElement::into_owned)
+    }
+
+    // Parse an Operator
+    fn parse_operator(
+        cursor: &mut TreeCursor,
+        source: &str,
+    ) -> Result<RelationOperator, ParseError> {
+        Ok(match cursor.node().kind() {
+            "<" => RelationOperator::LessThan,
+            "<=" => RelationOperator::LessThanOrEqual,
+            "<>" => RelationOperator::NotEqual,
+            "=" => RelationOperator::Equal,
+            ">=" => RelationOperator::GreaterThanOrEqual,
+            ">" => RelationOperator::GreaterThan,
+            "IN" => RelationOperator::In,
+            _ => return Err(ParseError::unexpected(&cursor.node(), source)),
+        })
+    }
+
+    /// parse a select element
+    pub fn parse_select_element(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<SelectElement, ParseError> {
+        BorrowedParser::parse_select_element(node, source, recovered)
+            .map(borrowed::SelectElement::into_owned)
+    }
+
+    /// parse the standard drop specification.
+    pub fn parse_standard_drop(node: &Node, source: &str) -> Result<CommonDrop, ParseError> {
+        let mut cursor = node.walk();
+        let mut if_exists = false;
+        cursor.goto_first_child();
+        // consume 'DROP'
+        cursor.goto_next_sibling();
+        // consume type
+        if cursor.node().kind().eq("MATERIALIZED") {
+            cursor.goto_next_sibling();
+        }
+        cursor.goto_next_sibling();
+        if cursor.node().kind().eq("IF") {
+            if_exists = true;
+            // consume 'IF'
+            cursor.goto_next_sibling();
+            // consume 'EXISTS'
+            cursor.goto_next_sibling();
+        }
+        Ok(CommonDrop {
+            name: CassandraParser::parse_table_name(&cursor.node(), source)?,
+            if_exists,
+        })
+    }
+
+    /// summarize a statement without parsing the parts of the statement that do not name the
+    /// keyspace or table it acts on.
+    pub fn parse_statement_summary(
+        node: &Node,
+        source: &str,
+    ) -> Result<StatementSummary, ParseError> {
+        let (kind, category) = StatementSummary::kind_of(node.kind());
+        let mut name = None;
+        let mut table = None;
+        let mut cursor = node.walk();
+        let mut process = cursor.goto_first_child();
+        while process {
+            let child = cursor.node();
+            let result = match child.kind() {
+                "from_spec" => {
+                    CassandraParser::parse_from_spec(&child, source).map(|x| table = Some(x))
+                }
+                "table_name" => {
+                    CassandraParser::parse_table_name(&child, source).map(|x| table = Some(x))
+                }
+                "aggregate_name"
+                | "function_name"
+                | "index_name"
+                | "keyspace_name"
+                | "materialized_view_name"
+                | "trigger_name"
+                | "type_name"
+                    if name.is_none() =>
+                {
+                    CassandraParser::parse_table_name(&child, source).map(|x| name = Some(x))
+                }
+                _ => Ok(()),
+            };
+            match result {
+                // the names of a statement with syntax errors may be incomplete.
+                Err(_) if node.has_error() => {}
+                result => result?,
+            }
+            process = cursor.goto_next_sibling();
+        }
+        let keyspace = match node.kind() {
+            "alter_keyspace" | "create_keyspace" | "drop_keyspace" => name.map(|x| x.name),
+            "use" => match CassandraParser::parse_use(node, source) {
+                Err(_) if node.has_error() => None,
+                result => Some(result?),
+            },
+            _ => name.or_else(|| table.clone()).and_then(|x| x.keyspace),
+        };
+        Ok(StatementSummary {
+            kind,
+            category,
+            keyspace,
+            table,
+            bind_markers: NodeFuncs::count_bind_markers(node, source),
+            has_error: node.has_error(),
+            span: NodeFuncs::span(node),
+        })
+    }
+
+    /// summarize a batch, from the statement that starts with `BEGIN BATCH` to `APPLY BATCH`.
+    /// A batch may act on several tables so no keyspace or table is given.
+    pub fn parse_batch_summary(nodes: &[Node], source: &str) -> StatementSummary {
+        let (first, last) = (nodes[0], nodes[nodes.len() - 1]);
+        StatementSummary {
+            kind: "BATCH",
+            category: StatementCategory::Write,
+            keyspace: None,
+            table: None,
+            bind_markers: nodes
+                .iter()
+                .map(|node| NodeFuncs::count_bind_markers(node, source))
+                .sum(),
+            has_error: nodes[..nodes.len() - 1]
+                .iter()
+                .any(|x| x.has_error() || !CassandraStatement::allowed_in_batch(x)),
+            span: Span::new(first.start_byte(), last.end_byte()),
+        }
+    }
+
+    /// parse a drop trigger statement.
+    pub fn parse_drop_trigger(node: &Node, source: &str) -> Result<DropTrigger, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        Ok(DropTrigger {
+            if_exists: CassandraParser::consume_2_keywords_and_check_exists(&mut cursor),
+            name: { CassandraParser::parse_table_name(&cursor.node(), source)? },
+            table: {
+                cursor.goto_next_sibling();
+                // consume 'ON'
+                cursor.goto_next_sibling();
+                CassandraParser::parse_table_name(&cursor.node(), source)?
+            },
+        })
+    }
+}
+
+/// the clauses of a select statement that follow the table name.
+struct SelectClauses<'a> {
+    where_clause: Option<borrowed::WhereExpression<'a>>,
+    group_by: Vec<borrowed::Identifier<'a>>,
+    order: Vec<borrowed::OrderClause<'a>>,
+    per_partition_limit: Option<Bindable<i32>>,
+    limit: Option<Bindable<i32>>,
+    filtering: bool,
+    bypass_cache: bool,
+    timeout: Option<Bindable<CqlDuration>>,
+}
+
+/// whether the column before an error that holds order clauses is descending and where its
+/// clause ends, if the error starts with its order, and the order clauses in the error.
+type OrderColumns<'a> = (Option<(bool, usize)>, Vec<borrowed::OrderClause<'a>>);
+
+/// The parser that walks the AST tree and produces the borrowed statements of [borrowed].
+pub struct BorrowedParser {}
+impl BorrowedParser {
+    pub fn parse_identifier<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::Identifier<'a>, ParseError> {
+        Ok(borrowed::Identifier::parse(NodeFuncs::as_str(
+            node, source,
+        )?))
+    }
+
+    pub fn parse_truncate<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::FQName<'a>, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume until 'table_name'
+        while !cursor.node().kind().eq("table_name") {
+            NodeFuncs::goto_next_sibling(&mut cursor, source)?;
+        }
+        BorrowedParser::parse_table_name(&cursor.node(), source)
+    }
+
+    pub fn parse_use<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::Identifier<'a>, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume 'USE'
+        cursor.goto_next_sibling();
+        Ok(borrowed::Identifier::parse(NodeFuncs::as_str(
+            &cursor.node(),
+            source,
+        )?))
+    }
+
+    fn parse_update_assignments<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::AssignmentElement<'a>>, ParseError> {
+        let mut result = vec![];
+        let mut cursor = node.walk();
+        let mut process = cursor.goto_first_child();
+        while process {
+            if cursor.node().kind().eq("assignment_element") {
+                result.push(BorrowedParser::parse_assignment_element(
+                    &cursor.node(),
+                    source,
+                    recovered,
+                )?);
+            }
+            process = cursor.goto_next_sibling();
+        }
+        Ok(result)
+    }
+    /// parse the update statement.
+    pub fn parse_update<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Update<'a>, ParseError> {
+        let mut cursor = node.walk();
         cursor.goto_first_child();
 
-        Update {
-            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source),
+        Ok(borrowed::Update {
+            span: Some(NodeFuncs::span(node)),
+            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source, recovered)?,
             table_name: {
                 // consume UPDATE
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                BorrowedParser::parse_table_name(&cursor.node(), source)?
             },
             using_ttl: {
                 cursor.goto_next_sibling();
                 if cursor.node().kind().eq("using_ttl_timestamp") {
-                    let result = Some(CassandraParser::parse_ttl_timestamp(&cursor.node(), source));
+                    let using = cursor.node();
+                    let error = using
+                        .next_sibling()
+                        .filter(|x| CassandraParser::continues_using(&using, x, source));
+                    let result = Some(CassandraParser::parse_ttl_timestamp(
+                        &using,
+                        error.as_ref(),
+                        source,
+                        recovered,
+                    )?);
                     cursor.goto_next_sibling();
+                    if error.is_some() {
+                        cursor.goto_next_sibling();
+                    }
                     result
+                } else if let Some(using) = Some(cursor.node())
+                    .filter(Node::is_error)
+                    .and_then(|x| CassandraParser::unparsed_using_clause(&x, &x, source))
+                {
+                    recovered.push(Recovered::node(Construct::Using, &cursor.node()));
+                    cursor.goto_next_sibling();
+                    Some(using)
                 } else {
                     None
                 }
             },
-            assignments: { CassandraParser::parse_update_assignments(&cursor.node(), source) },
+            assignments: {
+                let assignments = cursor.node();
+                let mut end = assignments;
+                while let Some(error) = end.next_sibling().filter(|x| x.is_error()) {
+                    end = error;
+                }
+                let unparsed = if assignments.has_error() || end != assignments {
+                    source
+                        .get(assignments.start_byte()..end.end_byte())
+                        .and_then(|x| BorrowedParser::parse_unparsed_assignments(x, end.end_byte()))
+                } else {
+                    None
+                };
+                match unparsed {
+                    Some(result) => {
+                        recovered.push(Recovered::new(
+                            Construct::UpdateAssignments,
+                            assignments.start_byte(),
+                            end.end_byte(),
+                        ));
+                        while cursor.node() != end {
+                            cursor.goto_next_sibling();
+                        }
+                        result
+                    }
+                    None => {
+                        BorrowedParser::parse_update_assignments(&assignments, source, recovered)?
+                    }
+                }
+            },
             where_clause: {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_where_spec(&cursor.node(), source)
+                BorrowedParser::parse_where_spec(&cursor.node(), source, recovered)?
             },
             if_exists: {
                 cursor.goto_next_sibling();
                 if cursor.node().kind().eq("IF") {
                     // consume EXISTS
-                    cursor.goto_next_sibling();
-                    true
-                } else {
-                    false
-                }
-            },
-            if_clause: if cursor.node().kind().eq("if_spec") {
-                cursor.goto_first_child();
-                // consume IF
-                cursor.goto_next_sibling();
-                CassandraParser::parse_if_condition_list(&cursor.node(), source)
-            } else {
-                vec![]
-            },
-        }
-    }
-
-    /// parse the privilege
-    fn parse_privilege_type(node: &Node, source: &str) -> PrivilegeType {
-        match NodeFuncs::as_string(node, source).to_uppercase().as_str() {
-            "ALL" | "ALL PERMISSIONS" => PrivilegeType::All,
-            "ALTER" => PrivilegeType::Alter,
-            "AUTHORIZE" => PrivilegeType::Authorize,
-            "DESCRIBE" => PrivilegeType::Describe,
-            "EXECUTE" => PrivilegeType::Execute,
-            "CREATE" => PrivilegeType::Create,
-            "DROP" => PrivilegeType::Drop,
-            "MODIFY" => PrivilegeType::Modify,
-            "SELECT" => PrivilegeType::Select,
-            _ => unreachable!(),
-        }
-    }
-
-    /// parse the privilege data.
-    pub fn parse_privilege(node: &Node, source: &str) -> Privilege {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-
-        let mut privilege: Option<PrivilegeType> = None;
-        let mut resource: Option<Resource> = None;
-        let mut role: Option<String> = None;
-        // consume 'GRANT/REVOKE'
-        while cursor.goto_next_sibling() {
-            match cursor.node().kind() {
-                "privilege" => {
-                    privilege = Some(CassandraParser::parse_privilege_type(
-                        &cursor.node(),
-                        source,
-                    ));
-                }
-                "resource" => {
-                    resource = Some(CassandraParser::parse_resource(&cursor.node(), source));
+                    cursor.goto_next_sibling();
+                    true
+                } else {
+                    false
                 }
-                "role" => role = Some(NodeFuncs::as_string(&cursor.node(), source)),
-                _ => {}
-            }
-        }
-        Privilege {
-            privilege: privilege.unwrap(),
-            resource,
-            role,
-        }
+            },
+            if_clause: if cursor.node().kind().eq("if_spec") {
+                cursor.goto_first_child();
+                // consume IF
+                cursor.goto_next_sibling();
+                BorrowedParser::parse_if_condition_list(&cursor.node(), source, recovered)?
+            } else {
+                vec![]
+            },
+        })
     }
 
     /// parse an assignment element
-    fn parse_assignment_element(node: &Node, source: &str) -> AssignmentElement {
+    fn parse_assignment_element<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::AssignmentElement<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        let name = CassandraParser::parse_indexed_column(&mut cursor, source);
+        let name = BorrowedParser::parse_indexed_column(&mut cursor, source)?;
         // consume the '='
         cursor.goto_next_sibling();
-        let value = CassandraParser::parse_operand(&cursor.node(), source);
-        let mut result = AssignmentElement {
+        let value = BorrowedParser::parse_operand(&cursor.node(), source, recovered)?;
+        let mut result = borrowed::AssignmentElement {
+            span: Some(NodeFuncs::span(node)),
+            value_span: Some(NodeFuncs::span(&cursor.node())),
             name,
             value,
             operator: None,
@@ -1213,153 +2668,254 @@ impl CassandraParser {
             // we have +/- value
             result.operator = Some(if cursor.node().kind().eq("+") {
                 cursor.goto_next_sibling();
-                AssignmentOperator::Plus(CassandraParser::parse_operand(&cursor.node(), source))
+                borrowed::AssignmentOperator::Plus(BorrowedParser::parse_operand(
+                    &cursor.node(),
+                    source,
+                    recovered,
+                )?)
             } else {
                 cursor.goto_next_sibling();
-                AssignmentOperator::Minus(CassandraParser::parse_operand(&cursor.node(), source))
+                borrowed::AssignmentOperator::Minus(BorrowedParser::parse_operand(
+                    &cursor.node(),
+                    source,
+                    recovered,
+                )?)
             });
         }
-        result
+        Ok(result)
     }
 
-    pub fn parse_delete_statement(node: &Node, source: &str) -> Delete {
+    pub fn parse_delete_statement<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Delete<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        Delete {
-            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source),
+        Ok(borrowed::Delete {
+            span: Some(NodeFuncs::span(node)),
+            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source, recovered)?,
             columns: {
                 // consume DELETE
                 cursor.goto_next_sibling();
                 let mut result = vec![];
                 if cursor.node().kind().eq("delete_column_list") {
-                    result = CassandraParser::parse_delete_column_list(&cursor.node(), source);
+                    result = BorrowedParser::parse_delete_column_list(&cursor.node(), source)?;
                     cursor.goto_next_sibling();
                 }
                 result
             },
-            table_name: { CassandraParser::parse_from_spec(&cursor.node(), source) },
+            table_name: { BorrowedParser::parse_from_spec(&cursor.node(), source)? },
             timestamp: {
                 cursor.goto_next_sibling();
                 let mut result = None;
                 if cursor.node().kind().eq("using_timestamp_spec") {
-                    result = CassandraParser::parse_using_timestamp(&cursor.node(), source);
+                    result = CassandraParser::parse_using_timestamp(&cursor.node(), source)?;
+                    cursor.goto_next_sibling();
+                } else if let Some(using) = Some(cursor.node())
+                    .filter(Node::is_error)
+                    .and_then(|x| CassandraParser::unparsed_using_clause(&x, &x, source))
+                    .filter(|x| x.ttl.is_none() && x.timeout.is_none())
+                {
+                    recovered.push(Recovered::node(Construct::Using, &cursor.node()));
+                    result = using.timestamp;
                     cursor.goto_next_sibling();
                 }
                 result
             },
-            where_clause: CassandraParser::parse_where_spec(&cursor.node(), source),
+            where_clause: BorrowedParser::parse_where_spec(&cursor.node(), source, recovered)?,
             if_clause: {
                 cursor.goto_next_sibling();
                 if cursor.node().kind().eq("if_spec") {
                     cursor.goto_first_child();
                     // consume the IF
                     cursor.goto_next_sibling();
-                    CassandraParser::parse_if_condition_list(&cursor.node(), source)
+                    BorrowedParser::parse_if_condition_list(&cursor.node(), source, recovered)?
                 } else {
                     vec![]
                 }
             },
             if_exists: cursor.node().kind().eq("IF"),
-        }
+        })
     }
 
     /// parse an `IF` condition list
-    fn parse_if_condition_list(node: &Node, source: &str) -> Vec<RelationElement> {
-        let mut result: Vec<RelationElement> = vec![];
+    fn parse_if_condition_list<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::RelationElement<'a>>, ParseError> {
+        let mut result: Vec<borrowed::RelationElement<'a>> = vec![];
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
         while process {
-            result.push(CassandraParser::parse_relation_element(
+            result.push(BorrowedParser::parse_relation_element(
                 &cursor.node(),
                 source,
-            ));
+                recovered,
+            )?);
             process = cursor.goto_next_sibling();
             // consume the 'AND' if it exists
             cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
-    fn parse_delete_column_list(node: &Node, source: &str) -> Vec<IndexedColumn> {
+    fn parse_delete_column_list<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<Vec<borrowed::IndexedColumn<'a>>, ParseError> {
         let mut cursor = node.walk();
         let mut result = vec![];
         let mut process = cursor.goto_first_child();
         while process {
             if cursor.node().kind().eq("delete_column_item") {
-                result.push(CassandraParser::parse_delete_column_item(
+                result.push(BorrowedParser::parse_delete_column_item(
                     &cursor.node(),
                     source,
-                ));
+                )?);
             }
             process = cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
     /// parse a delete column item
-    fn parse_delete_column_item(node: &Node, source: &str) -> IndexedColumn {
+    fn parse_delete_column_item<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::IndexedColumn<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        CassandraParser::parse_indexed_column(&mut cursor, source)
+        BorrowedParser::parse_indexed_column(&mut cursor, source)
     }
 
     /// parse an indexed column
-    fn parse_indexed_column(cursor: &mut TreeCursor, source: &str) -> IndexedColumn {
-        IndexedColumn {
-            column: CassandraParser::parse_identifier(&cursor.node(), source),
+    fn parse_indexed_column<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+    ) -> Result<borrowed::IndexedColumn<'a>, ParseError> {
+        let column = cursor.node();
+        let mut end_byte = column.end_byte();
+        Ok(borrowed::IndexedColumn {
+            column: BorrowedParser::parse_identifier(&column, source)?,
 
             idx: if cursor.goto_next_sibling() && cursor.node().kind().eq("[") {
                 // consume '['
                 cursor.goto_next_sibling();
-                let result = Some(NodeFuncs::as_string(&cursor.node(), source));
+                let result = Some(NodeFuncs::as_cow(&cursor.node(), source)?);
                 // consume ']'
                 cursor.goto_next_sibling();
+                end_byte = cursor.node().end_byte();
                 result
             } else {
                 None
             },
-        }
+            span: Some(Span::new(column.start_byte(), end_byte)),
+            column_span: Some(NodeFuncs::span(&column)),
+        })
     }
 
     /// parse an insert statement.
-    pub fn parse_insert(node: &Node, source: &str) -> Insert {
+    pub fn parse_insert<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Insert<'a>, ParseError> {
+        BorrowedParser::parse_insert_nodes(node, None, source, recovered)
+    }
+
+    /// true if the error that follows the insert statement node holds its `USING` clause, or
+    /// the rest of it.  The grammar does not accept bind markers for `TTL` and `TIMESTAMP` in
+    /// an insert statement and ends the statement before the bind marker.
+    pub(crate) fn continues_insert(node: &Node, error: &Node, source: &str) -> bool {
+        let mut cursor = node.walk();
+        let using = node
+            .children(&mut cursor)
+            .find(|x| x.kind().eq("using_ttl_timestamp"));
+        node.kind().eq("insert_statement")
+            && error.is_error()
+            && source
+                .get(node.end_byte()..error.start_byte())
+                .map_or(false, |x| x.trim().is_empty())
+            && CassandraParser::unparsed_using_clause(
+                using.as_ref().unwrap_or(error),
+                error,
+                source,
+            )
+            .is_some()
+    }
+
+    /// parse an insert statement whose `USING` clause is the error that follows it, see
+    /// `continues_insert`.
+    pub(crate) fn parse_continued_insert<'a>(
+        node: &Node,
+        error: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Insert<'a>, ParseError> {
+        BorrowedParser::parse_insert_nodes(node, Some(error), source, recovered)
+    }
+
+    /// parse an insert statement, `error` is the error that follows the statement node and
+    /// holds its `USING` clause.
+    fn parse_insert_nodes<'a>(
+        node: &Node,
+        error: Option<&Node>,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Insert<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        Insert {
-            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source),
+        Ok(borrowed::Insert {
+            span: Some(match error {
+                Some(error) => Span::new(node.start_byte(), error.end_byte()),
+                None => NodeFuncs::span(node),
+            }),
+            begin_batch: CassandraParser::check_begin_batch(&mut cursor, source, recovered)?,
             table_name: {
                 // consume INSERT
                 cursor.goto_next_sibling();
                 // consume INTO
                 cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
+                BorrowedParser::parse_table_name(&cursor.node(), source)?
             },
             columns: {
                 cursor.goto_next_sibling();
                 cursor.goto_first_child();
                 // consume the '(' at the beginning
                 cursor.goto_next_sibling();
-                let result = CassandraParser::parse_column_list(&cursor.node(), source);
+                let result = BorrowedParser::parse_column_list(&cursor.node(), source)?;
                 cursor.goto_parent();
                 result
             },
             values: {
                 cursor.goto_next_sibling();
+                let spec = cursor.node();
                 cursor.goto_first_child();
                 let result = match cursor.node().kind() {
-                    "VALUES" => {
-                        cursor.goto_next_sibling();
-                        // consume the '('
-                        cursor.goto_next_sibling();
-                        let expression_list =
-                            CassandraParser::parse_expression_list(&cursor.node(), source);
-                        InsertValues::Values(expression_list)
-                    }
+                    "VALUES" => match BorrowedParser::parse_unparsed_values(&spec, source) {
+                        Some(values) => {
+                            recovered.push(Recovered::node(Construct::InsertValues, &spec));
+                            borrowed::InsertValues::Values(values)
+                        }
+                        None => {
+                            cursor.goto_next_sibling();
+                            // consume the '('
+                            cursor.goto_next_sibling();
+                            let expression_list = BorrowedParser::parse_expression_list(
+                                &cursor.node(),
+                                source,
+                                recovered,
+                            )?;
+                            borrowed::InsertValues::Values(expression_list)
+                        }
+                    },
                     "JSON" => {
                         cursor.goto_next_sibling();
-                        InsertValues::Json(NodeFuncs::as_string(&cursor.node(), source))
+                        borrowed::InsertValues::Json(NodeFuncs::as_cow(&cursor.node(), source)?)
                     }
-                    _ => unreachable!(),
+                    _ => return Err(ParseError::unexpected(&cursor.node(), source)),
                 };
                 cursor.goto_parent();
                 result
@@ -1383,126 +2939,178 @@ impl CassandraParser {
             },
             using_ttl: {
                 if cursor.node().kind().eq("using_ttl_timestamp") {
-                    Some(CassandraParser::parse_ttl_timestamp(&cursor.node(), source))
+                    Some(CassandraParser::parse_ttl_timestamp(
+                        &cursor.node(),
+                        error,
+                        source,
+                        recovered,
+                    )?)
+                } else if let Some(error) = error {
+                    Some(CassandraParser::parse_ttl_timestamp(
+                        error, None, source, recovered,
+                    )?)
                 } else {
                     None
                 }
             },
-        }
+        })
     }
 
     /// parse a column list
-    fn parse_column_list(node: &Node, source: &str) -> Vec<Identifier> {
-        let mut result: Vec<Identifier> = vec![];
+    fn parse_column_list<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<Vec<borrowed::Identifier<'a>>, ParseError> {
+        let mut result: Vec<borrowed::Identifier<'a>> = vec![];
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
 
         while process {
             if cursor.node().kind().eq("column") {
-                result.push(CassandraParser::parse_identifier(&cursor.node(), source));
+                result.push(BorrowedParser::parse_identifier(&cursor.node(), source)?);
             }
             process = cursor.goto_next_sibling();
             // consume ',' if it is there
             cursor.goto_next_sibling();
         }
-        result
-    }
-
-    /// parse the using timestamp sttement.
-    fn parse_using_timestamp(node: &Node, source: &str) -> Option<u64> {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        // consume "USING"
-        cursor.goto_next_sibling();
-        // consume "TIMESTAMP"
-        cursor.goto_next_sibling();
-        Some(
-            NodeFuncs::as_string(&cursor.node(), source)
-                .parse::<u64>()
-                .unwrap(),
-        )
-    }
-
-    /// parse the using ttl timestamp element.
-    fn parse_ttl_timestamp(node: &Node, source: &str) -> TtlTimestamp {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        // consume "USING"
-        let mut ttl: Option<u64> = None;
-        let mut timestamp: Option<u64> = None;
-        while (ttl.is_none() || timestamp.is_none()) && cursor.goto_next_sibling() {
-            match cursor.node().kind() {
-                "ttl" => {
-                    ttl = Some(
-                        NodeFuncs::as_string(&cursor.node(), source)
-                            .parse::<u64>()
-                            .unwrap(),
-                    );
-                }
-                "time" => {
-                    timestamp = Some(
-                        NodeFuncs::as_string(&cursor.node(), source)
-                            .parse::<u64>()
-                            .unwrap(),
-                    );
-                }
-                _ => {}
-            }
-        }
-        TtlTimestamp { ttl, timestamp }
+        Ok(result)
     }
 
     /// parse the `FROM` clause
-    pub fn parse_from_spec(node: &Node, source: &str) -> FQName {
+    pub fn parse_from_spec<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::FQName<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume 'FROM'
         cursor.goto_next_sibling();
-        CassandraParser::parse_table_name(&cursor.node(), source)
+        BorrowedParser::parse_table_name(&cursor.node(), source)
     }
 
     /// parse a name that may have a keyspace specified.
-    fn parse_dotted_name(cursor: &mut TreeCursor, source: &str) -> FQName {
+    fn parse_dotted_name<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+    ) -> Result<borrowed::FQName<'a>, ParseError> {
         let result = &cursor.node();
-        if cursor.goto_next_sibling() {
+        Ok(if cursor.goto_next_sibling() {
             // we have fully qualified name
             // consume '.'
             cursor.goto_next_sibling();
-            FQName::new(
-                NodeFuncs::as_str(result, source),
-                NodeFuncs::as_str(&cursor.node(), source),
-            )
+            borrowed::FQName {
+                keyspace: Some(BorrowedParser::parse_identifier(result, source)?),
+                name: BorrowedParser::parse_identifier(&cursor.node(), source)?,
+                span: Some(Span::new(result.start_byte(), cursor.node().end_byte())),
+            }
         } else {
-            FQName::simple(NodeFuncs::as_str(result, source))
-        }
+            borrowed::FQName {
+                keyspace: None,
+                name: BorrowedParser::parse_identifier(result, source)?,
+                span: Some(NodeFuncs::span(result)),
+            }
+        })
     }
 
     /// parse a table name
-    fn parse_table_name(node: &Node, source: &str) -> FQName {
+    fn parse_table_name<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Result<borrowed::FQName<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        CassandraParser::parse_dotted_name(&mut cursor, source)
+        BorrowedParser::parse_dotted_name(&mut cursor, source)
     }
 
     /// parse the function args.
-    fn parse_function_args(node: &Node, source: &str) -> Vec<Operand> {
+    fn parse_function_args<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::Operand<'a>>, ParseError> {
         let mut result = vec![];
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
 
         while process {
-            result.push(CassandraParser::parse_operand(&cursor.node(), source));
+            result.push(BorrowedParser::parse_operand(
+                &cursor.node(),
+                source,
+                recovered,
+            )?);
             process = cursor.goto_next_sibling();
             if process {
                 // skip over the ','
                 cursor.goto_next_sibling();
             }
         }
-        result
+        Ok(result)
+    }
+
+    /// parse a function call.
+    fn parse_function_call<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::FunctionCall<'a>, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        let name = cursor.node();
+        let mut result = borrowed::FunctionCall {
+            name: borrowed::FQName {
+                keyspace: None,
+                name: BorrowedParser::parse_identifier(&name, source)?,
+                span: Some(NodeFuncs::span(&name)),
+            },
+            args: vec![],
+        };
+        cursor.goto_next_sibling();
+        // the grammar does not accept a keyspace in the name, `ks.f` is read as `ks` followed
+        // by the error `.f`.
+        if cursor.node().is_error() {
+            let error = cursor.node();
+            let function = NodeFuncs::as_str(&error, source)?
+                .strip_prefix('.')
+                .ok_or_else(|| ParseError::unexpected(&error, source))?;
+            result.name.keyspace = Some(std::mem::replace(
+                &mut result.name.name,
+                borrowed::Identifier::parse(function.trim()),
+            ));
+            result.name.span = Some(Span::new(name.start_byte(), error.end_byte()));
+            recovered.push(Recovered::node(Construct::FunctionName, &error));
+            cursor.goto_next_sibling();
+        }
+        // consume '('
+        cursor.goto_next_sibling();
+        match cursor.node().kind() {
+            "*" => {
+                result.args = vec![borrowed::Operand::Const(NodeFuncs::as_cow(
+                    &cursor.node(),
+                    source,
+                )?)]
+            }
+            "function_args" => {
+                // the grammar reads the arguments of `f()` as a missing constant.
+                let args = BorrowedParser::parse_function_args(&cursor.node(), source, recovered)?;
+                if args.len() == 1
+                    && matches!(&args[0], borrowed::Operand::Const(txt) if txt.is_empty())
+                {
+                    recovered.push(Recovered::node(Construct::EmptyArguments, &cursor.node()));
+                } else {
+                    result.args = args;
+                }
+            }
+            _ => {}
+        }
+        Ok(result)
     }
 
     /// parse an expressin list.
-    fn parse_expression_list(node: &Node, source: &str) -> Vec<Operand> {
+    fn parse_expression_list<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::Operand<'a>>, ParseError> {
         let mut result = vec![];
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
@@ -1510,262 +3118,646 @@ impl CassandraParser {
         while process {
             if cursor.node().kind().eq("expression") {
                 cursor.goto_first_child();
-                result.push(CassandraParser::parse_operand(&cursor.node(), source));
+                result.push(BorrowedParser::parse_operand(
+                    &cursor.node(),
+                    source,
+                    recovered,
+                )?);
                 cursor.goto_parent();
             }
             process = cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
     /// parse an operand
-    fn parse_operand(node: &Node, source: &str) -> Operand {
-        match node.kind() {
+    fn parse_operand<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Operand<'a>, ParseError> {
+        Ok(match node.kind() {
             "assignment_operand" | "constant" => {
-                let txt = NodeFuncs::as_string(node, source);
-                if txt.to_uppercase().eq("NULL") {
-                    Operand::Null
+                let txt = NodeFuncs::as_str(node, source)?;
+                if txt.eq_ignore_ascii_case("NULL") {
+                    borrowed::Operand::Null
                 } else {
-                    Operand::Const(txt)
+                    BorrowedParser::parse_constant(txt)
                 }
             }
-            "bind_marker" => Operand::Param(NodeFuncs::as_string(node, source)),
+            "bind_marker" => borrowed::Operand::Param(NodeFuncs::as_cow(node, source)?),
             "object_name" | "column" => {
-                Operand::Column(CassandraParser::parse_identifier(node, source))
-            }
-            "assignment_tuple" => {
-                Operand::Tuple(CassandraParser::parse_assignment_tuple(node, source))
+                borrowed::Operand::Column(BorrowedParser::parse_identifier(node, source)?)
             }
-            "assignment_map" => Operand::Map(CassandraParser::parse_assignment_map(node, source)),
-            "assignment_list" => {
-                Operand::List(CassandraParser::parse_assignment_list(node, source))
-            }
-            "assignment_set" => Operand::Set(CassandraParser::parse_assignment_set(node, source)),
-            "function_args" => Operand::Tuple(CassandraParser::parse_function_args(node, source)),
-            "function_call" => Operand::Func(NodeFuncs::as_string(node, source)),
+            "assignment_tuple" => borrowed::Operand::Tuple(BorrowedParser::parse_assignment_tuple(
+                node, source, recovered,
+            )?),
+            "assignment_map" => borrowed::Operand::Map(BorrowedParser::parse_assignment_map(
+                node, source, recovered,
+            )?),
+            "assignment_list" => borrowed::Operand::List(BorrowedParser::parse_assignment_list(
+                node, source, recovered,
+            )?),
+            "assignment_set" => borrowed::Operand::Set(BorrowedParser::parse_assignment_set(
+                node, source, recovered,
+            )?),
+            "function_args" => borrowed::Operand::Tuple(BorrowedParser::parse_function_args(
+                node, source, recovered,
+            )?),
+            "function_call" => borrowed::Operand::Func(BorrowedParser::parse_function_call(
+                node, source, recovered,
+            )?),
             _ => {
-                unreachable!("{}", node.kind())
+                return Err(ParseError::unexpected(node, source));
             }
-        }
+        })
     }
 
-    /// parses lists of option_hash_item or replication_list_item
-    fn parse_map(node: &Node, source: &str) -> Vec<(String, String)> {
-        let mut cursor = node.walk();
-
-        cursor.goto_first_child();
-        let mut entries: Vec<(String, String)> = vec![];
-        // { const : const, ... }
-        // we are on the '{' so we can just skip it
-        while cursor.goto_next_sibling() {
-            match cursor.node().kind() {
-                "}" | "," => {}
-                "option_hash_item" | "replication_list_item" => {
-                    cursor.goto_first_child();
-                    let key = NodeFuncs::as_string(&cursor.node(), source);
-                    cursor.goto_next_sibling();
-                    // consume the ':'
-                    cursor.goto_next_sibling();
-                    let value = NodeFuncs::as_string(&cursor.node(), source);
-                    entries.push((key, value));
-                    cursor.goto_parent();
-                }
-                _ => unreachable!(),
-            }
+    /// the operand for the text of a constant.  Text that is not a literal is held as a
+    /// constant.
+    fn parse_constant(text: &str) -> borrowed::Operand<'_> {
+        match borrowed::Literal::parse(text) {
+            Some(literal) => borrowed::Operand::Literal(literal),
+            None => borrowed::Operand::Const(Cow::Borrowed(text)),
         }
-        cursor.goto_parent();
-        entries
     }
 
     /// parse an assignment map.
-    fn parse_assignment_map(node: &Node, source: &str) -> Vec<(String, String)> {
+    fn parse_assignment_map<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::MapEntry<'a>>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        let mut entries: Vec<(String, String)> = vec![];
+        let mut entries: Vec<borrowed::MapEntry<'a>> = vec![];
         cursor.goto_first_child();
-        // { const : const, ... }
+        // { operand : operand, ... }
         // we are on the '{' so we can just skip it
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
                 "}" | "," => {}
                 _ => {
-                    let key = NodeFuncs::as_string(&cursor.node(), source);
+                    let key = BorrowedParser::parse_operand(&cursor.node(), source, recovered)?;
                     cursor.goto_next_sibling();
                     // consume the ':'
                     cursor.goto_next_sibling();
-                    let value = NodeFuncs::as_string(&cursor.node(), source);
+                    let value = BorrowedParser::parse_operand(&cursor.node(), source, recovered)?;
                     entries.push((key, value));
                 }
             }
         }
         cursor.goto_parent();
-        entries
+        Ok(entries)
     }
 
     /// parse an assignment list
-    fn parse_assignment_list(node: &Node, source: &str) -> Vec<String> {
+    fn parse_assignment_list<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::Operand<'a>>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        // [ const, const, ... ]
-        let mut entries: Vec<String> = vec![];
+        // [ operand, operand, ... ]
+        let mut entries: Vec<borrowed::Operand<'a>> = vec![];
         // we are on the '[' so we can just skip it
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
                 "]" | "," => {}
                 _ => {
-                    entries.push(NodeFuncs::as_string(&cursor.node(), source));
+                    entries.push(BorrowedParser::parse_operand(
+                        &cursor.node(),
+                        source,
+                        recovered,
+                    )?);
                 }
             }
         }
-        entries
+        Ok(entries)
     }
 
     /// parse an assignment set
-    fn parse_assignment_set(node: &Node, source: &str) -> Vec<String> {
+    fn parse_assignment_set<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::Operand<'a>>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        // { const, const, ... }
-        let mut entries: Vec<String> = vec![];
+        // { operand, operand, ... }
+        let mut entries: Vec<borrowed::Operand<'a>> = vec![];
         // we are on the '{' so we can just skip it
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
                 "}" | "," => {}
                 _ => {
-                    entries.push(NodeFuncs::as_string(&cursor.node(), source));
+                    entries.push(BorrowedParser::parse_operand(
+                        &cursor.node(),
+                        source,
+                        recovered,
+                    )?);
                 }
             }
         }
-        entries
+        Ok(entries)
     }
 
     /// parse and assignment tuple
-    fn parse_assignment_tuple(node: &Node, source: &str) -> Vec<Operand> {
+    fn parse_assignment_tuple<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::Operand<'a>>, ParseError> {
         // ( expression, expression ... )
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume '('
         cursor.goto_next_sibling();
         // now on 'expression-list'
-        CassandraParser::parse_expression_list(&cursor.node(), source)
-    }
-
-    /// parse a `BEGIN BATCH` clause
-    fn parse_begin_batch(node: &Node, source: &str) -> BeginBatch {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        // consume BEGIN
-        cursor.goto_next_sibling();
-
-        let node = cursor.node();
-        let ty = match node.kind() {
-            "COUNTER" => {
-                cursor.goto_next_sibling();
-                BatchType::Counter
-            }
-            "UNLOGGED" => {
-                cursor.goto_next_sibling();
-                BatchType::Unlogged
-            }
-            "LOGGED" => {
-                cursor.goto_next_sibling();
-                BatchType::Logged
-            }
-            _ => BatchType::Logged,
-        };
-
-        // consume BATCH
-        let timestamp = if cursor.goto_next_sibling() {
-            // we should have using_timestamp_spec
-            CassandraParser::parse_using_timestamp(&cursor.node(), source)
-        } else {
-            None
-        };
-
-        BeginBatch { ty, timestamp }
+        BorrowedParser::parse_expression_list(&cursor.node(), source, recovered)
     }
 
-    pub fn parse_select_elements(node: &Node, source: &str) -> Vec<SelectElement> {
+    pub fn parse_select_elements<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::SelectElement<'a>>, ParseError> {
         let mut cursor = node.walk();
         let mut result = vec![];
         let mut process = cursor.goto_first_child();
         while process {
             match cursor.node().kind() {
-                "select_element" => result.push(CassandraParser::parse_select_element(
+                "select_element" => result.push(BorrowedParser::parse_select_element(
                     &cursor.node(),
                     source,
-                )),
-                "*" => result.push(SelectElement::Star),
+                    recovered,
+                )?),
+                "*" => result.push(borrowed::SelectElement::Star),
                 _ => {}
             }
             process = cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
     /// parse a select statement
-    pub fn parse_select(node: &Node, source: &str) -> Select {
+    pub fn parse_select<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Select<'a>, ParseError> {
+        BorrowedParser::parse_select_nodes(node, None, source, recovered)
+    }
+
+    /// parse a select statement whose clauses continue in the error that follows it, see
+    /// `continues_select`.
+    pub(crate) fn parse_continued_select<'a>(
+        node: &Node,
+        error: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Select<'a>, ParseError> {
+        BorrowedParser::parse_select_nodes(node, Some(error), source, recovered)
+    }
+
+    /// parse a select statement, `error` is the error that follows the statement node and
+    /// holds the rest of its clauses.
+    fn parse_select_nodes<'a>(
+        node: &Node,
+        error: Option<&Node>,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Select<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume SELECT
         cursor.goto_next_sibling();
 
-        Select {
-            distinct: if cursor.node().kind().eq("DISTINCT") {
-                cursor.goto_next_sibling();
-                true
-            } else {
-                false
-            },
-            json: if cursor.node().kind().eq("JSON") {
+        let span = Some(match error {
+            Some(error) => Span::new(node.start_byte(), error.end_byte()),
+            None => NodeFuncs::span(node),
+        });
+        let distinct = if cursor.node().kind().eq("DISTINCT") {
+            cursor.goto_next_sibling();
+            true
+        } else {
+            false
+        };
+        let json = if cursor.node().kind().eq("JSON") {
+            cursor.goto_next_sibling();
+            true
+        } else {
+            false
+        };
+        let columns = {
+            // the grammar only accepts columns and function calls as select elements, other
+            // selectors produce errors within or after the elements and are read from the
+            // text.  The text must be read up to the `FROM` clause.
+            let elements = cursor.node();
+            let mut unparsed = elements.has_error();
+            while let Some(next) = cursor
+                .node()
+                .next_sibling()
+                .filter(|x| !x.kind().eq("from_spec") && (unparsed || x.is_error()))
+            {
                 cursor.goto_next_sibling();
-                true
+                unparsed |= next.is_error();
+            }
+            if !unparsed {
+                BorrowedParser::parse_select_elements(&elements, source, recovered)?
             } else {
-                false
-            },
-            columns: CassandraParser::parse_select_elements(&cursor.node(), source),
-            table_name: {
+                let end_byte = cursor.node().end_byte();
+                let columns = source
+                    .get(elements.start_byte()..end_byte)
+                    .filter(|_| {
+                        cursor
+                            .node()
+                            .next_sibling()
+                            .map_or(false, |x| x.kind().eq("from_spec"))
+                    })
+                    .and_then(|text| BorrowedParser::parse_unparsed_select_elements(text, end_byte))
+                    .ok_or_else(|| ParseError::unexpected(&elements, source))?;
+                recovered.push(Recovered::new(
+                    Construct::SelectElements,
+                    elements.start_byte(),
+                    end_byte,
+                ));
+                columns
+            }
+        };
+        cursor.goto_next_sibling();
+        let table_name = BorrowedParser::parse_from_spec(&cursor.node(), source)?;
+        let from_end = cursor.node().end_byte();
+        // the grammar does not accept many of the clauses that follow the table name, e.g. `OR`
+        // and `NOT IN` in the where clause, `GROUP BY` or `LIMIT ?`.  Clauses that hold errors
+        // are read from the text, the statement is an error if the text can not be read.
+        let unparsed = match error {
+            Some(error) => Some(BorrowedParser::select_continuation(node, error, source)),
+            None => cursor
+                .node()
+                .next_sibling()
+                .filter(|start| {
+                    std::iter::successors(Some(*start), Node::next_sibling).any(|x| x.has_error())
+                })
+                .map(|start| {
+                    source
+                        .get(start.start_byte()..node.end_byte())
+                        .and_then(|text| {
+                            BorrowedParser::parse_unparsed_select_clauses(text, node.end_byte())
+                        })
+                }),
+        };
+        let clauses = match unparsed {
+            Some(Some(clauses)) => {
+                recovered.push(Recovered::new(
+                    Construct::SelectClauses,
+                    from_end,
+                    error.unwrap_or(node).end_byte(),
+                ));
+                clauses
+            }
+            Some(None) => return Err(ParseError::unexpected(node, source)),
+            None => {
                 cursor.goto_next_sibling();
-                CassandraParser::parse_from_spec(&cursor.node(), source)
-            },
+                BorrowedParser::parse_select_clauses(&mut cursor, source, recovered)?
+            }
+        };
+        Ok(borrowed::Select {
+            distinct,
+            json,
+            columns,
+            table_name,
+            where_clause: clauses.where_clause,
+            group_by: clauses.group_by,
+            order: clauses.order,
+            per_partition_limit: clauses.per_partition_limit,
+            limit: clauses.limit,
+            filtering: clauses.filtering,
+            bypass_cache: clauses.bypass_cache,
+            timeout: clauses.timeout,
+            span,
+        })
+    }
+
+    /// parse the clauses of a select statement that follow the table name and that the grammar
+    /// accepts.  The cursor is on the node after the `FROM` clause.
+    fn parse_select_clauses<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<SelectClauses<'a>, ParseError> {
+        Ok(SelectClauses {
             where_clause: {
-                cursor.goto_next_sibling();
-                let mut result = vec![];
+                let mut result = None;
                 if cursor.node().kind().eq("where_spec") {
-                    result = CassandraParser::parse_where_spec(&cursor.node(), source);
+                    result = borrowed::WhereExpression::from_relations(
+                        BorrowedParser::parse_where_spec(&cursor.node(), source, recovered)?,
+                    );
                     cursor.goto_next_sibling();
                 }
                 result
             },
+            group_by: vec![],
             order: {
-                let mut result = None;
+                let mut result = vec![];
                 if cursor.node().kind().eq("order_spec") {
-                    result = CassandraParser::parse_order_spec(&cursor.node(), source);
+                    result = BorrowedParser::parse_order_spec(&cursor.node(), source, recovered)?;
                     cursor.goto_next_sibling();
                 }
                 result
             },
+            per_partition_limit: None,
             limit: {
                 let mut result = None;
                 if cursor.node().kind().eq("limit_spec") {
-                    cursor.goto_first_child();
-                    // consume LIMIT
-                    cursor.goto_next_sibling();
-                    result = Some(
-                        NodeFuncs::as_string(&cursor.node(), source)
-                            .parse::<i32>()
-                            .unwrap(),
-                    );
-                    cursor.goto_parent();
+                    result = Some(BorrowedParser::parse_limit_spec(&cursor.node(), source)?);
                     cursor.goto_next_sibling();
                 }
                 result
             },
             filtering: cursor.node().kind().eq("ALLOW"),
+            bypass_cache: false,
+            timeout: None,
+        })
+    }
+
+    /// true if the error that follows the select statement node holds the rest of its
+    /// clauses.  The grammar ends the statement before a where clause it does not accept, e.g.
+    /// one that starts with a parenthesis, and the rest of the statement is an error.
+    pub(crate) fn continues_select(node: &Node, error: &Node, source: &str) -> bool {
+        BorrowedParser::select_continuation(node, error, source).is_some()
+    }
+
+    /// the clauses of a select statement that continue in the error that follows the
+    /// statement node.  Returns `None` if there is more than whitespace between them or the
+    /// text of the clauses can not be read.
+    fn select_continuation<'a>(
+        node: &Node,
+        error: &Node,
+        source: &'a str,
+    ) -> Option<SelectClauses<'a>> {
+        if !node.kind().eq("select_statement")
+            || !error.is_error()
+            || !source
+                .get(node.end_byte()..error.start_byte())?
+                .trim()
+                .is_empty()
+        {
+            return None;
+        }
+        // the clauses start at the node after the `FROM` clause.
+        let mut cursor = node.walk();
+        let mut process = cursor.goto_first_child();
+        while process && !cursor.node().kind().eq("from_spec") {
+            process = cursor.goto_next_sibling();
+        }
+        if !process {
+            return None;
+        }
+        let start_byte = match cursor.node().next_sibling() {
+            Some(start) => start.start_byte(),
+            None => error.start_byte(),
+        };
+        source
+            .get(start_byte..error.end_byte())
+            .and_then(|text| BorrowedParser::parse_unparsed_select_clauses(text, error.end_byte()))
+    }
+
+    /// read the clauses of a select statement that follow the table name from `text`, which
+    /// ends at `end_byte` in the query text.  Returns `None` if the text can not be read.
+    fn parse_unparsed_select_clauses(text: &str, end_byte: usize) -> Option<SelectClauses<'_>> {
+        let keyword = CassandraParser::unparsed_keyword;
+        let symbol = CassandraParser::unparsed_symbol;
+        let position = |rest: &str| end_byte - rest.len();
+        let mut rest = text;
+        let mut where_clause = None;
+        if let Some(after) = keyword(rest, "WHERE") {
+            let (expression, after) = BorrowedParser::parse_unparsed_where(after, end_byte)?;
+            where_clause = Some(expression);
+            rest = after;
+        }
+        let mut group_by = vec![];
+        if let Some(mut after) = keyword(rest, "GROUP").and_then(|x| keyword(x, "BY")) {
+            loop {
+                let (column, next) = BorrowedParser::unparsed_identifier(after)?;
+                group_by.push(column);
+                rest = next;
+                match symbol(next, ',') {
+                    Some(next) => after = next,
+                    None => break,
+                }
+            }
+        }
+        let mut order = vec![];
+        if let Some(mut after) = keyword(rest, "ORDER").and_then(|x| keyword(x, "BY")) {
+            loop {
+                let start = position(after.trim_start());
+                let (name, mut next) = BorrowedParser::unparsed_identifier(after)?;
+                let name_span = Some(Span::new(start, position(next)));
+                let mut desc = false;
+                if let Some(x) = keyword(next, "DESC") {
+                    desc = true;
+                    next = x;
+                } else if let Some(x) = keyword(next, "ASC") {
+                    next = x;
+                }
+                let mut ann_of = None;
+                if let Some(x) = keyword(next, "ANN").and_then(|x| keyword(x, "OF")) {
+                    let (vector, x) = BorrowedParser::parse_unparsed_operand(x, end_byte)?;
+                    if !matches!(
+                        vector,
+                        borrowed::Operand::Param(_) | borrowed::Operand::List(_)
+                    ) {
+                        return None;
+                    }
+                    ann_of = Some(vector);
+                    next = x;
+                }
+                order.push(borrowed::OrderClause {
+                    name,
+                    desc,
+                    ann_of,
+                    span: Some(Span::new(start, position(next))),
+                    name_span,
+                });
+                rest = next;
+                match symbol(next, ',') {
+                    Some(next) => after = next,
+                    None => break,
+                }
+            }
+        }
+        let mut per_partition_limit = None;
+        if let Some(after) = keyword(rest, "PER")
+            .and_then(|x| keyword(x, "PARTITION"))
+            .and_then(|x| keyword(x, "LIMIT"))
+        {
+            let (value, after) = BorrowedParser::unparsed_bindable(after, end_byte)?;
+            per_partition_limit = Some(value);
+            rest = after;
+        }
+        let mut limit = None;
+        if let Some(after) = keyword(rest, "LIMIT") {
+            let (value, after) = BorrowedParser::unparsed_bindable(after, end_byte)?;
+            limit = Some(value);
+            rest = after;
+        }
+        let mut filtering = false;
+        if let Some(after) = keyword(rest, "ALLOW").and_then(|x| keyword(x, "FILTERING")) {
+            filtering = true;
+            rest = after;
+        }
+        // the ScyllaDB extensions.
+        let mut bypass_cache = false;
+        if let Some(after) = keyword(rest, "BYPASS").and_then(|x| keyword(x, "CACHE")) {
+            bypass_cache = true;
+            rest = after;
+        }
+        let mut timeout = None;
+        if let Some(after) = keyword(rest, "USING").and_then(|x| keyword(x, "TIMEOUT")) {
+            let (value, after) = BorrowedParser::unparsed_duration(after, end_byte)?;
+            timeout = Some(value);
+            rest = after;
+        }
+        if !rest.trim().is_empty() {
+            return None;
+        }
+        Some(SelectClauses {
+            where_clause,
+            group_by,
+            order,
+            per_partition_limit,
+            limit,
+            filtering,
+            bypass_cache,
+            timeout,
+        })
+    }
+
+    /// split a number or bind marker from the start of `text`, which ends at `end_byte` in the
+    /// query text.
+    fn unparsed_bindable<T: FromStr>(text: &str, end_byte: usize) -> Option<(Bindable<T>, &str)> {
+        match BorrowedParser::parse_unparsed_operand(text, end_byte)? {
+            (borrowed::Operand::Param(name), rest) => {
+                Some((Bindable::Param(name.to_string()), rest))
+            }
+            (borrowed::Operand::Literal(borrowed::Literal::Integer(value)), rest) => {
+                Some((Bindable::Literal(value.parse().ok()?), rest))
+            }
+            _ => None,
+        }
+    }
+
+    /// split a duration or bind marker from the start of `text`, which ends at `end_byte` in the
+    /// query text.
+    fn unparsed_duration(text: &str, end_byte: usize) -> Option<(Bindable<CqlDuration>, &str)> {
+        match BorrowedParser::parse_unparsed_operand(text, end_byte)? {
+            (borrowed::Operand::Param(name), rest) => {
+                Some((Bindable::Param(name.to_string()), rest))
+            }
+            (borrowed::Operand::Literal(borrowed::Literal::Duration(value)), rest) => {
+                Some((Bindable::Literal(CqlDuration::parse(&value)?), rest))
+            }
+            _ => None,
+        }
+    }
+
+    /// split the condition of a where clause from the start of `text`, which ends at
+    /// `end_byte` in the query text.  `AND` binds more tightly than `OR`.
+    pub(crate) fn parse_unparsed_where(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::WhereExpression<'_>, &str)> {
+        BorrowedParser::parse_unparsed_junction(text, end_byte, "OR")
+    }
+
+    /// split the expressions joined by `junction`, either `OR` or `AND`, from the start of
+    /// `text`.  A parenthesized expression joined by the same keyword is merged into the list.
+    fn parse_unparsed_junction<'a>(
+        text: &'a str,
+        end_byte: usize,
+        junction: &str,
+    ) -> Option<(borrowed::WhereExpression<'a>, &'a str)> {
+        let or = junction.eq("OR");
+        let mut items = vec![];
+        let mut rest = text;
+        loop {
+            let (item, after) = if or {
+                BorrowedParser::parse_unparsed_junction(rest, end_byte, "AND")?
+            } else {
+                BorrowedParser::parse_unparsed_condition(rest, end_byte)?
+            };
+            match item {
+                borrowed::WhereExpression::Or(x) if or => items.extend(x),
+                borrowed::WhereExpression::And(x) if !or => items.extend(x),
+                item => items.push(item),
+            }
+            match CassandraParser::unparsed_keyword(after, junction) {
+                Some(after) => rest = after,
+                None if items.len() == 1 => return Some((items.remove(0), after)),
+                None if or => return Some((borrowed::WhereExpression::Or(items), after)),
+                None => return Some((borrowed::WhereExpression::And(items), after)),
+            }
+        }
+    }
+
+    /// split a relation or a parenthesized expression from the start of `text`.  No version
+    /// of Cassandra accepts `NOT` before a condition, only the `NOT IN`, `NOT CONTAINS` and
+    /// `NOT CONTAINS KEY` operators of a relation.
+    fn parse_unparsed_condition(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::WhereExpression<'_>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        // a parenthesis starts either an expression or the columns of a multi-column relation.
+        let nested = symbol(text, '(')
+            .and_then(|after| BorrowedParser::parse_unparsed_where(after, end_byte))
+            .and_then(|(item, rest)| Some((item, symbol(rest, ')')?)));
+        if nested.is_some() {
+            return nested;
+        }
+        let (relation, rest) = BorrowedParser::parse_unparsed_relation(text, end_byte)?;
+        Some((borrowed::WhereExpression::Relation(relation), rest))
+    }
+
+    /// split a relation, e.g. `a = 1` or `(a, b) IN ?`, from the start of `text`.
+    fn parse_unparsed_relation(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::RelationElement<'_>, &str)> {
+        let position = |rest: &str| end_byte - rest.len();
+        let text = text.trim_start();
+        let (obj, rest) = BorrowedParser::parse_unparsed_operand(text, end_byte)?;
+        if !matches!(
+            obj,
+            borrowed::Operand::Column(_) | borrowed::Operand::Tuple(_) | borrowed::Operand::Func(_)
+        ) {
+            return None;
         }
+        let obj_span = Span::new(position(text), position(rest));
+        let (oper, rest) = CassandraParser::unparsed_relation_operator(rest)?;
+        let value_start = position(rest.trim_start());
+        let (value, rest) = BorrowedParser::parse_unparsed_term(rest, end_byte, 1)?;
+        Some((
+            borrowed::RelationElement {
+                obj: borrowed::RelationTarget::from_operand(obj),
+                oper,
+                value,
+                span: Some(Span::new(position(text), position(rest))),
+                obj_span: Some(obj_span),
+                value_span: Some(Span::new(value_start, position(rest))),
+            },
+            rest,
+        ))
     }
 
     /// parse the where clause
-    fn parse_where_spec(node: &Node, source: &str) -> Vec<RelationElement> {
+    fn parse_where_spec<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::RelationElement<'a>>, ParseError> {
         // (where_spec (relation_elements (relation_element (constant))))
         let mut result = vec![];
         let mut cursor = node.walk();
@@ -1776,26 +3768,36 @@ impl CassandraParser {
         let mut process = cursor.goto_first_child();
         // now on first relation.
         while process {
-            result.push(CassandraParser::parse_relation_element(
+            result.push(BorrowedParser::parse_relation_element(
                 &cursor.node(),
                 source,
-            ));
+                recovered,
+            )?);
             process = cursor.goto_next_sibling();
             // consume the 'AND' if it exists
             cursor.goto_next_sibling();
         }
-        result
+        Ok(result)
     }
 
     /// parse a relaiton element.
-    fn parse_relation_element(node: &Node, source: &str) -> RelationElement {
+    fn parse_relation_element<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::RelationElement<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        match cursor.node().kind() {
+        Ok(match cursor.node().kind() {
             "relation_contains_key" => {
                 cursor.goto_first_child();
-                RelationElement {
-                    obj: Operand::Column(CassandraParser::parse_identifier(&cursor.node(), source)),
+                borrowed::RelationElement {
+                    span: Some(NodeFuncs::span(node)),
+                    obj_span: Some(NodeFuncs::span(&cursor.node())),
+                    obj: borrowed::RelationTarget::Column(BorrowedParser::parse_identifier(
+                        &cursor.node(),
+                        source,
+                    )?),
                     oper: RelationOperator::ContainsKey,
                     value: {
                         // consume column value
@@ -1804,120 +3806,768 @@ impl CassandraParser {
                         cursor.goto_next_sibling();
                         // consume 'KEY'
                         cursor.goto_next_sibling();
-                        Operand::Const(NodeFuncs::as_string(&cursor.node(), source))
+                        BorrowedParser::parse_constant(NodeFuncs::as_str(&cursor.node(), source)?)
                     },
+                    value_span: Some(NodeFuncs::span(&cursor.node())),
                 }
             }
             "relation_contains" => {
                 cursor.goto_first_child();
-                RelationElement {
-                    obj: Operand::Column(CassandraParser::parse_identifier(&cursor.node(), source)),
+                borrowed::RelationElement {
+                    span: Some(NodeFuncs::span(node)),
+                    obj_span: Some(NodeFuncs::span(&cursor.node())),
+                    obj: borrowed::RelationTarget::Column(BorrowedParser::parse_identifier(
+                        &cursor.node(),
+                        source,
+                    )?),
                     oper: RelationOperator::Contains,
                     value: {
                         // consume column value
                         cursor.goto_next_sibling();
                         // consume 'CONTAINS'
                         cursor.goto_next_sibling();
-                        Operand::Const(NodeFuncs::as_string(&cursor.node(), source))
+                        BorrowedParser::parse_constant(NodeFuncs::as_str(&cursor.node(), source)?)
                     },
+                    value_span: Some(NodeFuncs::span(&cursor.node())),
                 }
             }
             _ => {
-                RelationElement {
-                    obj: CassandraParser::parse_relation_value(&mut cursor, source),
-                    oper: {
-                        // consume the obj
-                        cursor.goto_next_sibling();
-                        CassandraParser::parse_operator(&mut cursor)
-                    },
+                let obj_start = cursor.node().start_byte();
+                let obj = borrowed::RelationTarget::from_operand(
+                    BorrowedParser::parse_relation_value(&mut cursor, source, recovered)?,
+                );
+                let obj_span = Span::new(obj_start, cursor.node().end_byte());
+                // consume the obj
+                cursor.goto_next_sibling();
+                let oper = CassandraParser::parse_operator(&mut cursor, source)?;
+                // consume the oper
+                cursor.goto_next_sibling();
+                // the value is the rest of the relation.
+                let value_span = Span::new(cursor.node().start_byte(), node.end_byte());
+                borrowed::RelationElement {
+                    span: Some(NodeFuncs::span(node)),
+                    obj,
+                    oper,
+                    obj_span: Some(obj_span),
+                    value_span: Some(value_span),
                     value: {
-                        // consume the oper
-                        cursor.goto_next_sibling();
-
-                        if cursor.node().kind() == "(" {
+                        // the values of `IN (...)` are a tuple even when there is only one.
+                        // A list of values that are not tuples is held in one `function_args`.
+                        let mut listed = cursor.node().kind() == "(";
+                        if listed {
                             cursor.goto_next_sibling();
+                            listed = !cursor.node().kind().eq("function_args");
                         }
-                        let mut values =
-                            vec![CassandraParser::parse_operand(&cursor.node(), source)];
+                        let mut values = vec![BorrowedParser::parse_operand(
+                            &cursor.node(),
+                            source,
+                            recovered,
+                        )?];
                         cursor.goto_next_sibling();
                         while cursor.node().kind() == "," {
                             cursor.goto_next_sibling();
-                            values.push(CassandraParser::parse_operand(&cursor.node(), source));
+                            values.push(BorrowedParser::parse_operand(
+                                &cursor.node(),
+                                source,
+                                recovered,
+                            )?);
                         }
-                        if values.len() > 1 {
-                            Operand::Tuple(values)
+                        if listed || values.len() > 1 {
+                            borrowed::Operand::Tuple(values)
                         } else {
                             values.remove(0)
                         }
                     },
                 }
             }
-        }
-    }
-
-    // Parse an Operator
-    fn parse_operator(cursor: &mut TreeCursor) -> RelationOperator {
-        match cursor.node().kind() {
-            "<" => RelationOperator::LessThan,
-            "<=" => RelationOperator::LessThanOrEqual,
-            "<>" => RelationOperator::NotEqual,
-            "=" => RelationOperator::Equal,
-            ">=" => RelationOperator::GreaterThanOrEqual,
-            ">" => RelationOperator::GreaterThan,
-            "IN" => RelationOperator::In,
-            kind => {
-                unreachable!("Unknown operator: {}", kind);
-            }
-        }
+        })
     }
 
     /// parse a relation value
-    fn parse_relation_value(cursor: &mut TreeCursor, source: &str) -> Operand {
+    fn parse_relation_value<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::Operand<'a>, ParseError> {
         let node = cursor.node();
         let kind = node.kind();
-        match kind {
-            "column" => Operand::Column(CassandraParser::parse_identifier(&node, source)),
-            "function_call" => Operand::Func(NodeFuncs::as_string(&node, source)),
+        Ok(match kind {
+            "column" => borrowed::Operand::Column(BorrowedParser::parse_identifier(&node, source)?),
+            "function_call" => borrowed::Operand::Func(BorrowedParser::parse_function_call(
+                &node, source, recovered,
+            )?),
             "(" => {
-                let mut values: Vec<Operand> = Vec::new();
+                let mut values: Vec<borrowed::Operand<'a>> = Vec::new();
                 // consume '('
-                cursor.goto_next_sibling();
+                NodeFuncs::goto_next_sibling(cursor, source)?;
                 while !cursor.node().kind().eq(")") {
                     match cursor.node().kind() {
                         "," => {}
-                        _ => values.push(CassandraParser::parse_relation_value(cursor, source)),
+                        _ => values.push(BorrowedParser::parse_relation_value(
+                            cursor, source, recovered,
+                        )?),
                     }
-                    cursor.goto_next_sibling();
+                    NodeFuncs::goto_next_sibling(cursor, source)?;
+                }
+                borrowed::Operand::Tuple(values)
+            }
+            _ => BorrowedParser::parse_constant(NodeFuncs::as_str(&node, source)?),
+        })
+    }
+
+    /// split an operand or an arithmetic operation, e.g. `now() - 1d`, from the start of
+    /// `text`.  Only the operators whose precedence is at least `precedence` are read.
+    fn parse_unparsed_term(
+        text: &str,
+        end_byte: usize,
+        precedence: u8,
+    ) -> Option<(borrowed::Operand<'_>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        // a parenthesis starts either an operation or a tuple.
+        let nested = symbol(text, '(')
+            .and_then(|after| BorrowedParser::parse_unparsed_term(after, end_byte, 1))
+            .filter(|(operand, _)| matches!(operand, borrowed::Operand::Arithmetic(..)))
+            .and_then(|(operand, rest)| Some((operand, symbol(rest, ')')?)));
+        let (mut left, mut rest) = match nested {
+            Some(nested) => nested,
+            None => BorrowedParser::parse_unparsed_operand(text, end_byte)?,
+        };
+        loop {
+            let operator = match rest.trim_start().chars().next() {
+                Some('+') => ArithmeticOperator::Add,
+                Some('-') => ArithmeticOperator::Subtract,
+                Some('*') => ArithmeticOperator::Multiply,
+                Some('/') => ArithmeticOperator::Divide,
+                Some('%') => ArithmeticOperator::Modulo,
+                _ => return Some((left, rest)),
+            };
+            if operator.precedence() < precedence {
+                return Some((left, rest));
+            }
+            // operators of the same precedence are evaluated from left to right.
+            let (right, after) = BorrowedParser::parse_unparsed_term(
+                &rest.trim_start()[1..],
+                end_byte,
+                operator.precedence() + 1,
+            )?;
+            left = borrowed::Operand::Arithmetic(Box::new(left), operator, Box::new(right));
+            rest = after;
+        }
+    }
+
+    /// split the operand from the start of `text`.  The text ends at `end_byte` in the query
+    /// text.  The grammar does not accept function calls as values so the values that hold them
+    /// are read from the text of the errors.
+    fn parse_unparsed_operand(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::Operand<'_>, &str)> {
+        let text = text.trim_start();
+        let symbol = CassandraParser::unparsed_symbol;
+        // the operands up to the `close` symbol, a map entry is held as the operand for its key
+        // and the operand for its value.
+        let items = |mut rest, close| {
+            let mut items = vec![];
+            if let Some(rest) = symbol(rest, close) {
+                return Some((items, rest));
+            }
+            loop {
+                let (operand, after) = BorrowedParser::parse_unparsed_operand(rest, end_byte)?;
+                let entry = if close == '}' {
+                    symbol(after, ':')
+                } else {
+                    None
+                };
+                let after = match entry {
+                    Some(value) => {
+                        let (value, after) =
+                            BorrowedParser::parse_unparsed_operand(value, end_byte)?;
+                        items.push((Some(operand), value));
+                        after
+                    }
+                    None => {
+                        items.push((None, operand));
+                        after
+                    }
+                };
+                match symbol(after, ',') {
+                    Some(after) => rest = after,
+                    None => return Some((items, symbol(after, close)?)),
+                }
+            }
+        };
+        let first = text.chars().next()?;
+        let len = match first {
+            '?' => 1,
+            ':' => text[1..]
+                .find(|x: char| !(x.is_alphanumeric() || x == '_'))
+                .unwrap_or(text.len() - 1)
+                .checked_add(1)?,
+            '\'' | '"' => CassandraParser::unparsed_quoted_len(text, first)?,
+            '$' => text.get(2..)?.find("$$")?.checked_add(4)?,
+            '[' | '{' | '(' => {
+                let close = match first {
+                    '[' => ']',
+                    '{' => '}',
+                    _ => ')',
+                };
+                let (items, rest) = items(&text[1..], close)?;
+                let operand = match first {
+                    '[' => borrowed::Operand::List(items.into_iter().map(|(_, x)| x).collect()),
+                    '(' => borrowed::Operand::Tuple(items.into_iter().map(|(_, x)| x).collect()),
+                    _ if items.is_empty() => borrowed::Operand::Set(vec![]),
+                    // a user defined type literal names its fields where a map has values
+                    _ if items
+                        .iter()
+                        .all(|(key, _)| matches!(key, Some(borrowed::Operand::Column(_)))) =>
+                    {
+                        borrowed::Operand::Udt(
+                            items
+                                .into_iter()
+                                .filter_map(|(key, x)| match key {
+                                    Some(borrowed::Operand::Column(field)) => Some((field, x)),
+                                    _ => None,
+                                })
+                                .collect(),
+                        )
+                    }
+                    _ if items.iter().all(|(key, _)| key.is_some()) => borrowed::Operand::Map(
+                        items
+                            .into_iter()
+                            .filter_map(|(key, x)| key.map(|key| (key, x)))
+                            .collect(),
+                    ),
+                    _ if items.iter().all(|(key, _)| key.is_none()) => {
+                        borrowed::Operand::Set(items.into_iter().map(|(_, x)| x).collect())
+                    }
+                    _ => return None,
+                };
+                return Some((operand, rest));
+            }
+            _ => {
+                let len = |colon: bool| {
+                    text.find(|x: char| {
+                        !(x.is_alphanumeric()
+                            || matches!(x, '_' | '.' | '-' | '+')
+                            || colon && x == ':')
+                    })
+                    .unwrap_or(text.len())
+                };
+                // the ISO 8601 alternative format of a duration holds colons, e.g.
+                // `P0001-02-03T04:05:06`.
+                match len(true) {
+                    len if matches!(
+                        borrowed::Literal::parse(&text[..len]),
+                        Some(borrowed::Literal::Duration(_))
+                    ) =>
+                    {
+                        len
+                    }
+                    _ => len(false),
+                }
+            }
+        };
+        if len < 2 && first == ':' || len == 0 {
+            return None;
+        }
+        let (word, rest) = text.split_at(len);
+        let operand = match first {
+            '?' | ':' => borrowed::Operand::Param(Cow::Borrowed(word)),
+            '\'' | '$' => BorrowedParser::parse_constant(word),
+            _ => {
+                if let Some(args) = symbol(rest, '(') {
+                    let (name, keyspace) = match word.rsplit_once('.') {
+                        Some((keyspace, name)) => (name, Some(keyspace)),
+                        None => (word, None),
+                    };
+                    let name = borrowed::FQName {
+                        keyspace: keyspace.map(borrowed::Identifier::parse),
+                        name: borrowed::Identifier::parse(name),
+                        span: Some(Span::new(end_byte - text.len(), end_byte - rest.len())),
+                    };
+                    let (args, rest) = match symbol(args, '*') {
+                        Some(after) => (
+                            vec![borrowed::Operand::Const(Cow::Borrowed("*"))],
+                            symbol(after, ')')?,
+                        ),
+                        None => {
+                            let (args, rest) = items(args, ')')?;
+                            (args.into_iter().map(|(_, x)| x).collect(), rest)
+                        }
+                    };
+                    return Some((
+                        borrowed::Operand::Func(borrowed::FunctionCall { name, args }),
+                        rest,
+                    ));
+                }
+                // an ISO 8601 duration, e.g. `P1DT2H`, starts with a letter.
+                let keyword = matches!(
+                    borrowed::Literal::parse(word),
+                    Some(
+                        borrowed::Literal::Boolean(_)
+                            | borrowed::Literal::NaN(_)
+                            | borrowed::Literal::Infinity(_)
+                            | borrowed::Literal::Duration(_)
+                    )
+                );
+                if word.eq_ignore_ascii_case("NULL") {
+                    borrowed::Operand::Null
+                } else if first == '"'
+                    || !(first.is_ascii_digit() || matches!(first, '-' | '+' | '.') || keyword)
+                {
+                    borrowed::Operand::Column(borrowed::Identifier::parse(word))
+                } else {
+                    BorrowedParser::parse_constant(word)
+                }
+            }
+        };
+        Some((operand, rest))
+    }
+
+    /// parse the `VALUES (...)` of an insert statement that holds errors.  The grammar does not
+    /// accept function calls as values so the values are read from the text.  Returns `None`
+    /// if the node holds no errors or the text can not be read.
+    fn parse_unparsed_values<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Option<Vec<borrowed::Operand<'a>>> {
+        if !node.has_error() {
+            return None;
+        }
+        let text =
+            CassandraParser::unparsed_keyword(NodeFuncs::as_str(node, source).ok()?, "VALUES")?;
+        match BorrowedParser::parse_unparsed_operand(text, node.end_byte())? {
+            (borrowed::Operand::Tuple(values), rest) if rest.trim().is_empty() => Some(values),
+            _ => None,
+        }
+    }
+
+    /// parse the `SET` clause of an update statement that holds errors.  The grammar does not
+    /// accept function calls as values so the assignments are read from the text, which ends at
+    /// `end_byte` in the query text.  Returns `None` if the text can not be read.
+    fn parse_unparsed_assignments(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<Vec<borrowed::AssignmentElement<'_>>> {
+        match BorrowedParser::unparsed_assignments(text, end_byte)? {
+            (result, rest) if rest.trim().is_empty() => Some(result),
+            _ => None,
+        }
+    }
+
+    /// split the `SET` clause of an update statement from the start of `text`, see
+    /// `parse_unparsed_assignments`.
+    fn unparsed_assignments(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(Vec<borrowed::AssignmentElement<'_>>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        let operand = BorrowedParser::parse_unparsed_operand;
+        let position = |rest: &str| end_byte - rest.len();
+        let mut text = CassandraParser::unparsed_keyword(text, "SET")?;
+        let mut result = vec![];
+        loop {
+            let rest = text.trim_start();
+            let start = position(rest);
+            let len = CassandraParser::unparsed_quoted_len(rest, '"').unwrap_or_else(|| {
+                rest.find(|x: char| !(x.is_alphanumeric() || x == '_'))
+                    .unwrap_or(rest.len())
+            });
+            if len == 0 {
+                return None;
+            }
+            let (column, mut rest) = rest.split_at(len);
+            let mut idx = None;
+            if let Some(after) = symbol(rest, '[') {
+                let after = after.trim_start();
+                let (_, end) = operand(after, end_byte)?;
+                idx = Some(Cow::Borrowed(&after[..after.len() - end.len()]));
+                rest = symbol(end, ']')?;
+            }
+            let name = borrowed::IndexedColumn {
+                column: borrowed::Identifier::parse(column),
+                idx,
+                span: Some(Span::new(start, position(rest))),
+                column_span: Some(Span::new(start, start + column.len())),
+            };
+            let after = symbol(rest, '=')?;
+            let value_start = position(after.trim_start());
+            let (value, mut rest) = operand(after, end_byte)?;
+            let value_span = Some(Span::new(value_start, position(rest)));
+            let mut operator = None;
+            if let Some(after) = symbol(rest, '+') {
+                let (value, after) = operand(after, end_byte)?;
+                operator = Some(borrowed::AssignmentOperator::Plus(value));
+                rest = after;
+            } else if let Some(after) = symbol(rest, '-') {
+                let (value, after) = operand(after, end_byte)?;
+                operator = Some(borrowed::AssignmentOperator::Minus(value));
+                rest = after;
+            }
+            result.push(borrowed::AssignmentElement {
+                name,
+                value,
+                operator,
+                span: Some(Span::new(start, position(rest))),
+                value_span,
+            });
+            match symbol(rest, ',') {
+                Some(rest) => text = rest,
+                None => return Some((result, rest)),
+            }
+        }
+    }
+
+    /// parse an insert or update statement that the grammar does not accept at all, e.g. an
+    /// update whose first assignment is a function call or an insert of an ISO 8601 duration.
+    /// The statement is read from the text of the error that holds it.  Returns `None` if the
+    /// node is not such an error.
+    pub(crate) fn parse_unparsed_write<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Option<borrowed::CassandraStatement<'a>> {
+        let statement = BorrowedParser::unparsed_write(node, source)?;
+        recovered.push(Recovered::node(Construct::Write, node));
+        Some(statement)
+    }
+
+    /// parse an insert or update statement from the text of an error, see
+    /// `parse_unparsed_write`.
+    fn unparsed_write<'a>(
+        node: &Node,
+        source: &'a str,
+    ) -> Option<borrowed::CassandraStatement<'a>> {
+        if !node.is_error() {
+            return None;
+        }
+        match node.child(0)?.kind() {
+            "INSERT" => BorrowedParser::unparsed_insert(node, source)
+                .map(borrowed::CassandraStatement::Insert),
+            "UPDATE" => BorrowedParser::unparsed_update(node, source)
+                .map(borrowed::CassandraStatement::Update),
+            _ => None,
+        }
+    }
+
+    /// parse an insert statement from the text of an error, see `parse_unparsed_write`.  Only
+    /// the `VALUES` form is read.
+    fn unparsed_insert<'a>(node: &Node, source: &'a str) -> Option<borrowed::Insert<'a>> {
+        let keyword = CassandraParser::unparsed_keyword;
+        let end_byte = node.end_byte();
+        let mut cursor = node.walk();
+        let mut children = node.children(&mut cursor);
+        children.next();
+        if !children.next()?.kind().eq("INTO") {
+            return None;
+        }
+        let name = children.next().filter(|x| x.kind().eq("table_name"))?;
+        let columns = children
+            .next()
+            .filter(|x| x.kind().eq("insert_column_spec") && !x.has_error())?;
+        let mut inner = columns.walk();
+        let column_list = columns
+            .children(&mut inner)
+            .find(|x| x.kind().eq("column_list"))?;
+        let rest = source.get(columns.end_byte()..end_byte)?;
+        let (values, mut rest) =
+            match BorrowedParser::parse_unparsed_operand(keyword(rest, "VALUES")?, end_byte)? {
+                (borrowed::Operand::Tuple(values), rest) => (values, rest),
+                _ => return None,
+            };
+        let mut if_not_exists = false;
+        if let Some(after) = keyword(rest, "IF")
+            .and_then(|x| keyword(x, "NOT"))
+            .and_then(|x| keyword(x, "EXISTS"))
+        {
+            if_not_exists = true;
+            rest = after;
+        }
+        let mut using_ttl = None;
+        if let Some(after) = keyword(rest, "USING") {
+            let (using, after) = BorrowedParser::unparsed_using(after, end_byte)?;
+            using_ttl = Some(using);
+            rest = after;
+        }
+        if !rest.trim().is_empty() {
+            return None;
+        }
+        Some(borrowed::Insert {
+            begin_batch: None,
+            table_name: BorrowedParser::parse_table_name(&name, source).ok()?,
+            columns: BorrowedParser::parse_column_list(&column_list, source).ok()?,
+            values: borrowed::InsertValues::Values(values),
+            using_ttl,
+            if_not_exists,
+            span: Some(NodeFuncs::span(node)),
+        })
+    }
+
+    /// split the `TTL` and `TIMESTAMP` of a `USING` clause from the start of `text`, which
+    /// follows the `USING` keyword and ends at `end_byte` in the query text.
+    fn unparsed_using(text: &str, end_byte: usize) -> Option<(TtlTimestamp, &str)> {
+        let keyword = CassandraParser::unparsed_keyword;
+        let bindable = BorrowedParser::unparsed_bindable;
+        let mut using = TtlTimestamp {
+            ttl: None,
+            timestamp: None,
+            timeout: None,
+        };
+        let mut text = text;
+        loop {
+            // each value may only be given once.
+            if let Some(rest) = keyword(text, "TTL").filter(|_| using.ttl.is_none()) {
+                let (value, rest) = bindable(rest, end_byte)?;
+                using.ttl = Some(value);
+                text = rest;
+            } else if let Some(rest) = keyword(text, "TIMEOUT").filter(|_| using.timeout.is_none())
+            {
+                let (value, rest) = BorrowedParser::unparsed_duration(rest, end_byte)?;
+                using.timeout = Some(value);
+                text = rest;
+            } else {
+                let rest = keyword(text, "TIMESTAMP").filter(|_| using.timestamp.is_none())?;
+                let (value, rest) = bindable(rest, end_byte)?;
+                using.timestamp = Some(value);
+                text = rest;
+            }
+            match keyword(text, "AND") {
+                Some(rest) => text = rest,
+                None => return Some((using, text)),
+            }
+        }
+    }
+
+    /// parse an update statement from the text of an error, see `parse_unparsed_write`.
+    fn unparsed_update<'a>(node: &Node, source: &'a str) -> Option<borrowed::Update<'a>> {
+        let keyword = CassandraParser::unparsed_keyword;
+        let end_byte = node.end_byte();
+        let mut cursor = node.walk();
+        let mut children = node.children(&mut cursor);
+        children.next();
+        let name = children.next().filter(|x| x.kind().eq("table_name"))?;
+        let mut rest = source.get(name.end_byte()..end_byte)?;
+        let mut using_ttl = None;
+        if let Some(after) = keyword(rest, "USING") {
+            let (using, after) = BorrowedParser::unparsed_using(after, end_byte)?;
+            using_ttl = Some(using);
+            rest = after;
+        }
+        let (assignments, after) = BorrowedParser::unparsed_assignments(rest, end_byte)?;
+        let (where_clause, mut rest) =
+            BorrowedParser::unparsed_relations(keyword(after, "WHERE")?, end_byte)?;
+        let mut if_exists = false;
+        let mut if_clause = vec![];
+        if let Some(after) = keyword(rest, "IF") {
+            match keyword(after, "EXISTS") {
+                Some(after) => {
+                    if_exists = true;
+                    rest = after;
+                }
+                None => {
+                    let (relations, after) = BorrowedParser::unparsed_relations(after, end_byte)?;
+                    if_clause = relations;
+                    rest = after;
                 }
-                Operand::Tuple(values)
             }
-            _ => Operand::Const(NodeFuncs::as_string(&node, source)),
         }
+        if !rest.trim().is_empty() {
+            return None;
+        }
+        Some(borrowed::Update {
+            begin_batch: None,
+            table_name: BorrowedParser::parse_table_name(&name, source).ok()?,
+            using_ttl,
+            assignments,
+            where_clause,
+            if_clause,
+            if_exists,
+            span: Some(NodeFuncs::span(node)),
+        })
+    }
+
+    /// split the relations joined by `AND` from the start of `text`, which ends at `end_byte`
+    /// in the query text.
+    fn unparsed_relations(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(Vec<borrowed::RelationElement<'_>>, &str)> {
+        let mut result = vec![];
+        let mut text = text;
+        loop {
+            let (relation, rest) = BorrowedParser::parse_unparsed_relation(text, end_byte)?;
+            result.push(relation);
+            match CassandraParser::unparsed_keyword(rest, "AND") {
+                Some(rest) => text = rest,
+                None => return Some((result, rest)),
+            }
+        }
+    }
+
+    /// parse a limit clause
+    fn parse_limit_spec(node: &Node, source: &str) -> Result<Bindable<i32>, ParseError> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        // consume LIMIT
+        cursor.goto_next_sibling();
+        Ok(Bindable::Literal(NodeFuncs::as_number(
+            &cursor.node(),
+            source,
+        )?))
     }
 
     /// parse an order clause
-    fn parse_order_spec(node: &Node, source: &str) -> Option<OrderClause> {
+    fn parse_order_spec<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::OrderClause<'a>>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume "ORDER"
         cursor.goto_next_sibling();
         // consume "BY"
         cursor.goto_next_sibling();
-        Some(OrderClause {
-            name: CassandraParser::parse_identifier(&cursor.node(), source),
-            desc: {
-                // consume the name
-                if cursor.goto_next_sibling() {
-                    cursor.node().kind().eq("DESC")
-                } else {
-                    false
+        BorrowedParser::parse_order_clauses(&mut cursor, source, recovered)
+    }
+
+    /// parse the columns of an order clause, each a column name followed by an optional `ASC`
+    /// or `DESC`.  The cursor is on the first column and is left on the node after the last.
+    fn parse_order_clauses<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<Vec<borrowed::OrderClause<'a>>, ParseError> {
+        let mut result = vec![BorrowedParser::parse_order_clause(cursor, source)?];
+        if matches!(cursor.node().kind(), "ASC" | "DESC") {
+            cursor.goto_next_sibling();
+        }
+        BorrowedParser::parse_unparsed_order(cursor, source, &mut result, recovered);
+        Ok(result)
+    }
+
+    /// add the columns of an order clause after the first.  The grammar only accepts one
+    /// column so the others, e.g. `, b DESC, c`, are read from the error the cursor is on.  The
+    /// `ASC` or `DESC` of the column before the error may start the error, and that of the
+    /// last column may follow it.  The cursor is moved past the columns that are added.
+    fn parse_unparsed_order<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+        result: &mut Vec<borrowed::OrderClause<'a>>,
+        recovered: &mut Vec<Recovered>,
+    ) {
+        // the column before the error may only be given one order.
+        let ordered = |x: &borrowed::OrderClause| {
+            x.span.map(|x| x.end_byte) != x.name_span.map(|x| x.end_byte)
+        };
+        if let Some((order, columns)) =
+            BorrowedParser::unparsed_order_columns(&cursor.node(), source)
+                .filter(|(order, _)| order.is_none() || !result.last().map_or(true, ordered))
+        {
+            recovered.push(Recovered::node(Construct::OrderBy, &cursor.node()));
+            if let (Some((desc, end_byte)), Some(last)) = (order, result.last_mut()) {
+                last.desc = desc;
+                last.span = last.span.map(|x| Span::new(x.start_byte, end_byte));
+            }
+            result.extend(columns);
+            if cursor.goto_next_sibling() && matches!(cursor.node().kind(), "ASC" | "DESC") {
+                if let Some(last) = result.last_mut() {
+                    last.desc = cursor.node().kind().eq("DESC");
+                    last.span = last
+                        .span
+                        .map(|x| Span::new(x.start_byte, cursor.node().end_byte()));
                 }
-            },
+                cursor.goto_next_sibling();
+            }
+        }
+    }
+
+    /// the order clauses in the text of an error node that starts with a comma, or with the
+    /// `ASC` or `DESC` of the column before the error and a comma.  Returns whether that column
+    /// is descending and where its clause ends, if given, and the clauses.  Returns `None` if
+    /// the node is not such an error.
+    fn unparsed_order_columns<'a>(node: &Node, source: &'a str) -> Option<OrderColumns<'a>> {
+        let mut text = NodeFuncs::as_str(node, source).ok()?;
+        if !node.is_error() {
+            return None;
+        }
+        let mut start = node.start_byte();
+        let mut order = None;
+        for (keyword, desc) in [("ASC", false), ("DESC", true)] {
+            if let Some(rest) = CassandraParser::unparsed_keyword(text, keyword) {
+                let end_byte = node.end_byte() - rest.len();
+                let rest = rest.trim_start();
+                start = node.end_byte() - rest.len();
+                order = Some((desc, end_byte));
+                text = rest;
+                break;
+            }
+        }
+        if !text.starts_with(',') {
+            return None;
+        }
+        let mut result = vec![];
+        for column in text.split(',').skip(1) {
+            start += 1;
+            let trimmed = column.trim();
+            let offset = start + column.len() - column.trim_start().len();
+            start += column.len();
+            let mut words = trimmed.split_whitespace();
+            let name = words.next()?;
+            let desc = match words.next() {
+                None => false,
+                Some(x) if x.eq_ignore_ascii_case("ASC") => false,
+                Some(x) if x.eq_ignore_ascii_case("DESC") => true,
+                Some(_) => return None,
+            };
+            if words.next().is_some() {
+                return None;
+            }
+            result.push(borrowed::OrderClause {
+                name: borrowed::Identifier::parse(name),
+                desc,
+                ann_of: None,
+                span: Some(Span::new(offset, offset + trimmed.len())),
+                name_span: Some(Span::new(offset, offset + name.len())),
+            });
+        }
+        Some((order, result))
+    }
+
+    /// parse a column name followed by an optional `ASC` or `DESC`
+    fn parse_order_clause<'a>(
+        cursor: &mut TreeCursor,
+        source: &'a str,
+    ) -> Result<borrowed::OrderClause<'a>, ParseError> {
+        let name = cursor.node();
+        let mut end_byte = name.end_byte();
+        let mut desc = false;
+        // consume the name
+        if cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "DESC" => {
+                    desc = true;
+                    end_byte = cursor.node().end_byte();
+                }
+                "ASC" => end_byte = cursor.node().end_byte(),
+                _ => {}
+            }
+        }
+        Ok(borrowed::OrderClause {
+            name: BorrowedParser::parse_identifier(&name, source)?,
+            desc,
+            ann_of: None,
+            span: Some(Span::new(name.start_byte(), end_byte)),
+            name_span: Some(NodeFuncs::span(&name)),
         })
     }
 
     /// parse a select element
-    pub fn parse_select_element(node: &Node, source: &str) -> SelectElement {
+    pub fn parse_select_element<'a>(
+        node: &Node,
+        source: &'a str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<borrowed::SelectElement<'a>, ParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
 
@@ -1931,63 +4581,404 @@ impl CassandraParser {
         } else {
             None
         };
-        match (type_.kind(), alias) {
-            ("column", Some(alias)) => SelectElement::Column(Named::new(
-                NodeFuncs::as_str(&type_, source),
-                NodeFuncs::as_str(&alias, source),
-            )),
-            ("column", None) => {
-                SelectElement::Column(Named::simple(NodeFuncs::as_str(&type_, source)))
+        let alias = match alias {
+            Some(alias) => Some(BorrowedParser::parse_identifier(&alias, source)?),
+            None => None,
+        };
+        Ok(match type_.kind() {
+            "column" => borrowed::SelectElement::Column(borrowed::Named {
+                name: BorrowedParser::parse_identifier(&type_, source)?,
+                alias,
+                span: Some(NodeFuncs::span(node)),
+                name_span: Some(NodeFuncs::span(&type_)),
+            }),
+            "function_call" => {
+                let function = BorrowedParser::parse_function_call(&type_, source, recovered)?;
+                let args = function
+                    .args
+                    .into_iter()
+                    .map(BorrowedParser::operand_selector)
+                    .collect();
+                BorrowedParser::select_element(
+                    BorrowedParser::function_selector(function.name, args),
+                    Some(NodeFuncs::span(&type_)),
+                    alias,
+                    Some(NodeFuncs::span(node)),
+                )
+            }
+            _ => return Err(ParseError::unexpected(&type_, source)),
+        })
+    }
+
+    /// the selector for an operand of a function call.
+    fn operand_selector(operand: borrowed::Operand) -> borrowed::Selector {
+        match operand {
+            borrowed::Operand::Column(name) => borrowed::Selector::Column(name),
+            borrowed::Operand::Func(function) => BorrowedParser::function_selector(
+                function.name,
+                function
+                    .args
+                    .into_iter()
+                    .map(BorrowedParser::operand_selector)
+                    .collect(),
+            ),
+            term => borrowed::Selector::Term(term),
+        }
+    }
+
+    /// the operand for a selector that is a column, term or function call on such selectors.
+    fn selector_operand<'a>(selector: &borrowed::Selector<'a>) -> Option<borrowed::Operand<'a>> {
+        Some(match selector {
+            borrowed::Selector::Column(name) => borrowed::Operand::Column(name.clone()),
+            borrowed::Selector::Term(term) => term.clone(),
+            borrowed::Selector::Function(name, args) => {
+                borrowed::Operand::Func(borrowed::FunctionCall {
+                    name: name.clone(),
+                    args: args
+                        .iter()
+                        .map(BorrowedParser::selector_operand)
+                        .collect::<Option<_>>()?,
+                })
+            }
+            _ => return None,
+        })
+    }
+
+    /// the selector for a call of the named function.  `WRITETIME`, `MAXWRITETIME` and `TTL`
+    /// are not functions.
+    fn function_selector<'a>(
+        name: borrowed::FQName<'a>,
+        mut args: Vec<borrowed::Selector<'a>>,
+    ) -> borrowed::Selector<'a> {
+        let keyword = match (&name.keyspace, &name.name) {
+            (None, borrowed::Identifier::Unquoted(name)) if args.len() == 1 => {
+                name.to_ascii_uppercase()
+            }
+            _ => return borrowed::Selector::Function(name, args),
+        };
+        match keyword.as_str() {
+            "WRITETIME" => borrowed::Selector::WriteTime(Box::new(args.remove(0))),
+            "MAXWRITETIME" => borrowed::Selector::MaxWriteTime(Box::new(args.remove(0))),
+            "TTL" => borrowed::Selector::Ttl(Box::new(args.remove(0))),
+            _ => borrowed::Selector::Function(name, args),
+        }
+    }
+
+    /// the select element for a selector.  Columns and function calls on columns and terms
+    /// have their own elements.
+    fn select_element<'a>(
+        selector: borrowed::Selector<'a>,
+        selector_span: Option<Span>,
+        alias: Option<borrowed::Identifier<'a>>,
+        span: Option<Span>,
+    ) -> borrowed::SelectElement<'a> {
+        let operand = BorrowedParser::selector_operand(&selector);
+        match (selector, operand) {
+            (borrowed::Selector::Column(name), _) => {
+                borrowed::SelectElement::Column(borrowed::Named {
+                    name,
+                    alias,
+                    span,
+                    name_span: selector_span,
+                })
+            }
+            (_, Some(borrowed::Operand::Func(function))) => {
+                borrowed::SelectElement::Function(borrowed::NamedFunction {
+                    function,
+                    alias,
+                    span,
+                })
+            }
+            (selector, _) => borrowed::SelectElement::Selector(borrowed::NamedSelector {
+                selector,
+                alias,
+                span,
+            }),
+        }
+    }
+
+    /// the identifier at the start of `text`.  An unquoted identifier starts with a letter.
+    fn unparsed_identifier(text: &str) -> Option<(borrowed::Identifier<'_>, &str)> {
+        let text = text.trim_start();
+        let len = CassandraParser::unparsed_quoted_len(text, '"').unwrap_or_else(|| {
+            text.find(|x: char| !(x.is_alphanumeric() || x == '_'))
+                .unwrap_or(text.len())
+        });
+        if len == 0 || !text.starts_with(|x: char| x.is_alphabetic() || x == '"') {
+            None
+        } else {
+            let (name, rest) = text.split_at(len);
+            Some((borrowed::Identifier::parse(name), rest))
+        }
+    }
+
+    /// read the select elements from `text`, which ends at `end_byte` in the query text.
+    /// Returns `None` if the text can not be read.
+    fn parse_unparsed_select_elements(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<Vec<borrowed::SelectElement<'_>>> {
+        let symbol = CassandraParser::unparsed_symbol;
+        if symbol(text, '*').map_or(false, |rest| rest.trim().is_empty()) {
+            return Some(vec![borrowed::SelectElement::Star]);
+        }
+        let mut result = vec![];
+        let mut rest = text;
+        loop {
+            let start = end_byte - rest.trim_start().len();
+            let (selector, after) = BorrowedParser::parse_unparsed_selector(rest, end_byte)?;
+            let selector_span = Span::new(start, end_byte - after.len());
+            let (alias, after) = match CassandraParser::unparsed_keyword(after, "AS") {
+                Some(after) => {
+                    let (alias, after) = BorrowedParser::unparsed_identifier(after)?;
+                    (Some(alias), after)
+                }
+                None => (None, after),
+            };
+            let span = Span::new(start, end_byte - after.len());
+            result.push(BorrowedParser::select_element(
+                selector,
+                Some(selector_span),
+                alias,
+                Some(span),
+            ));
+            match symbol(after, ',') {
+                Some(after) => rest = after,
+                None if after.trim().is_empty() => return Some(result),
+                None => return None,
+            }
+        }
+    }
+
+    /// split the selector from the start of `text`, which ends at `end_byte` in the query text.
+    fn parse_unparsed_selector(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::Selector<'_>, &str)> {
+        BorrowedParser::parse_unparsed_arithmetic(text, end_byte, 1)
+    }
+
+    /// split the arithmetic operations with operators of at least `precedence` from the start of
+    /// `text`.  Addition and subtraction have a precedence of 1, multiplication, division and
+    /// modulo have a precedence of 2.
+    fn parse_unparsed_arithmetic(
+        text: &str,
+        end_byte: usize,
+        precedence: u8,
+    ) -> Option<(borrowed::Selector<'_>, &str)> {
+        let operand = |text| {
+            if precedence < 2 {
+                BorrowedParser::parse_unparsed_arithmetic(text, end_byte, precedence + 1)
+            } else {
+                BorrowedParser::parse_unparsed_selector_term(text, end_byte)
+            }
+        };
+        let (mut selector, mut rest) = operand(text)?;
+        loop {
+            let trimmed = rest.trim_start();
+            let operator = match (precedence, trimmed.chars().next()) {
+                (1, Some('+')) => ArithmeticOperator::Add,
+                (1, Some('-')) => ArithmeticOperator::Subtract,
+                (2, Some('*')) => ArithmeticOperator::Multiply,
+                (2, Some('/')) => ArithmeticOperator::Divide,
+                (2, Some('%')) => ArithmeticOperator::Modulo,
+                _ => return Some((selector, rest)),
+            };
+            let (right, after) = operand(&trimmed[1..])?;
+            selector =
+                borrowed::Selector::Arithmetic(Box::new(selector), operator, Box::new(right));
+            rest = after;
+        }
+    }
+
+    /// split a possibly negated selector with the fields, elements and slices taken from it
+    /// from the start of `text`.
+    fn parse_unparsed_selector_term(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::Selector<'_>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        let text = text.trim_start();
+        // a negative number is a literal.
+        if let Some(negated) = text.strip_prefix('-') {
+            if !negated.starts_with(|x: char| x.is_ascii_digit()) {
+                let (selector, rest) =
+                    BorrowedParser::parse_unparsed_selector_term(negated, end_byte)?;
+                return Some((borrowed::Selector::Negate(Box::new(selector)), rest));
             }
-            ("function_call", Some(alias)) => SelectElement::Function(Named::new(
-                NodeFuncs::as_str(&type_, source),
-                NodeFuncs::as_str(&alias, source),
-            )),
-            ("function_call", None) => {
-                SelectElement::Function(Named::simple(NodeFuncs::as_str(&type_, source)))
+        }
+        let (mut selector, mut rest) =
+            BorrowedParser::parse_unparsed_selector_primary(text, end_byte)?;
+        loop {
+            let trimmed = rest.trim_start();
+            if trimmed.starts_with('.') && !trimmed.starts_with("..") {
+                let (field, after) = BorrowedParser::unparsed_identifier(&trimmed[1..])?;
+                selector = borrowed::Selector::Field(Box::new(selector), field);
+                rest = after;
+            } else if let Some(index) = symbol(trimmed, '[') {
+                let (start, after) = if index.trim_start().starts_with("..") {
+                    (None, index)
+                } else {
+                    let (start, after) = BorrowedParser::parse_unparsed_selector(index, end_byte)?;
+                    (Some(Box::new(start)), after)
+                };
+                let after = after.trim_start();
+                if let Some(after) = after.strip_prefix("..") {
+                    let (end, after) = match symbol(after, ']') {
+                        Some(after) => (None, after),
+                        None => {
+                            let (end, after) =
+                                BorrowedParser::parse_unparsed_selector(after, end_byte)?;
+                            (Some(Box::new(end)), symbol(after, ']')?)
+                        }
+                    };
+                    selector = borrowed::Selector::Slice(Box::new(selector), start, end);
+                    rest = after;
+                } else {
+                    selector = borrowed::Selector::Element(Box::new(selector), start?);
+                    rest = symbol(after, ']')?;
+                }
+            } else {
+                return Some((selector, rest));
             }
-            _ => unreachable!(),
         }
     }
 
-    /// parse the standard drop specification.
-    pub fn parse_standard_drop(node: &Node, source: &str) -> CommonDrop {
-        let mut cursor = node.walk();
-        let mut if_exists = false;
-        cursor.goto_first_child();
-        // consume 'DROP'
-        cursor.goto_next_sibling();
-        // consume type
-        if cursor.node().kind().eq("MATERIALIZED") {
-            cursor.goto_next_sibling();
+    /// split a column, term, function call or parenthesized selector from the start of `text`.
+    fn parse_unparsed_selector_primary(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(borrowed::Selector<'_>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        let text = text.trim_start();
+        let first = text.chars().next()?;
+        match first {
+            '(' => {
+                let (selector, rest) =
+                    BorrowedParser::parse_unparsed_selector(&text[1..], end_byte)?;
+                return Some((selector, symbol(rest, ')')?));
+            }
+            '\'' | '$' | '?' | ':' | '[' | '{' => {
+                let (term, rest) = BorrowedParser::parse_unparsed_operand(text, end_byte)?;
+                return Some((borrowed::Selector::Term(term), rest));
+            }
+            _ => {}
         }
-        cursor.goto_next_sibling();
-        if cursor.node().kind().eq("IF") {
-            if_exists = true;
-            // consume 'IF'
-            cursor.goto_next_sibling();
-            // consume 'EXISTS'
-            cursor.goto_next_sibling();
+        if first.is_ascii_digit() || first == '-' {
+            // numbers, UUIDs and durations.  A `.` is part of the number when a digit follows
+            // it so that slices (`1..3`) are not read as numbers.
+            let uuid = text
+                .get(..36)
+                .and_then(borrowed::Literal::parse)
+                .map_or(false, |x| matches!(x, borrowed::Literal::Uuid(_)));
+            let mut len = if uuid { 36 } else { 0 };
+            let mut previous = ' ';
+            let mut chars = text[len..].chars().peekable();
+            while let Some(c) = chars.next() {
+                let digit_follows = chars.peek().map_or(false, |x| x.is_ascii_digit());
+                let accepted = c.is_alphanumeric()
+                    || c == '_'
+                    || (c == '-' && len == 0)
+                    || (c == '.' && digit_follows)
+                    || (matches!(c, '+' | '-') && matches!(previous, 'e' | 'E') && digit_follows);
+                if !accepted {
+                    break;
+                }
+                len += c.len_utf8();
+                previous = c;
+            }
+            let (word, rest) = text.split_at(len);
+            return Some((
+                borrowed::Selector::Term(BorrowedParser::parse_constant(word)),
+                rest,
+            ));
         }
-        CommonDrop {
-            name: CassandraParser::parse_table_name(&cursor.node(), source),
-            if_exists,
+        let (name, rest) = BorrowedParser::unparsed_identifier(text)?;
+        let name_end = end_byte - rest.len();
+        let unquoted = match &name {
+            borrowed::Identifier::Unquoted(name) => Some(name.to_ascii_uppercase()),
+            _ => None,
+        };
+        let args = match symbol(rest, '(') {
+            Some(args) => args,
+            None => {
+                // a keyspace qualified function name
+                let function = rest
+                    .strip_prefix('.')
+                    .and_then(BorrowedParser::unparsed_identifier)
+                    .and_then(|(function, rest)| Some((function, rest, symbol(rest, '(')?)));
+                if let Some((function, rest, args)) = function {
+                    let name = borrowed::FQName {
+                        keyspace: Some(name),
+                        name: function,
+                        span: Some(Span::new(end_byte - text.len(), end_byte - rest.len())),
+                    };
+                    let (args, rest) =
+                        BorrowedParser::parse_unparsed_selector_args(args, end_byte)?;
+                    return Some((BorrowedParser::function_selector(name, args), rest));
+                }
+                let term = match unquoted.as_deref() {
+                    Some("NULL") => Some(borrowed::Operand::Null),
+                    Some(_) => match borrowed::Literal::parse(&text[..text.len() - rest.len()]) {
+                        Some(
+                            literal @ (borrowed::Literal::Boolean(_)
+                            | borrowed::Literal::NaN(_)
+                            | borrowed::Literal::Infinity(_)),
+                        ) => Some(borrowed::Operand::Literal(literal)),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                return Some(match term {
+                    Some(term) => (borrowed::Selector::Term(term), rest),
+                    None => (borrowed::Selector::Column(name), rest),
+                });
+            }
+        };
+        if unquoted.as_deref() == Some("CAST") {
+            let (selector, rest) = BorrowedParser::parse_unparsed_selector(args, end_byte)?;
+            let rest = CassandraParser::unparsed_keyword(rest, "AS")?;
+            let (data_type, rest) = DataType::parse_prefix(rest)?;
+            return Some((
+                borrowed::Selector::Cast(Box::new(selector), data_type),
+                symbol(rest, ')')?,
+            ));
         }
+        let name = borrowed::FQName {
+            keyspace: None,
+            name,
+            span: Some(Span::new(end_byte - text.len(), name_end)),
+        };
+        let (args, rest) = BorrowedParser::parse_unparsed_selector_args(args, end_byte)?;
+        Some((BorrowedParser::function_selector(name, args), rest))
     }
 
-    /// parse a drop trigger statement.
-    pub fn parse_drop_trigger(node: &Node, source: &str) -> DropTrigger {
-        let mut cursor = node.walk();
-        cursor.goto_first_child();
-        DropTrigger {
-            if_exists: CassandraParser::consume_2_keywords_and_check_exists(&mut cursor),
-            name: { CassandraParser::parse_table_name(&cursor.node(), source) },
-            table: {
-                cursor.goto_next_sibling();
-                // consume 'ON'
-                cursor.goto_next_sibling();
-                CassandraParser::parse_table_name(&cursor.node(), source)
-            },
+    /// split the arguments of a function call and the closing `)` from the start of `text`.
+    fn parse_unparsed_selector_args(
+        text: &str,
+        end_byte: usize,
+    ) -> Option<(Vec<borrowed::Selector<'_>>, &str)> {
+        let symbol = CassandraParser::unparsed_symbol;
+        if let Some(rest) = symbol(text, ')') {
+            return Some((vec![], rest));
+        }
+        if let Some(rest) = symbol(text, '*') {
+            return Some((
+                vec![borrowed::Selector::Term(borrowed::Operand::Const(
+                    Cow::Borrowed("*"),
+                ))],
+                symbol(rest, ')')?,
+            ));
+        }
+        let mut args = vec![];
+        let mut rest = text;
+        loop {
+            let (arg, after) = BorrowedParser::parse_unparsed_selector(rest, end_byte)?;
+            args.push(arg);
+            match symbol(after, ',') {
+                Some(after) => rest = after,
+                None => return Some((args, symbol(after, ')')?)),
+            }
         }
     }
 }
@@ -1998,62 +4989,579 @@ pub struct ParsedStatement {
     pub has_error: bool,
     /// the parsed statement.
     pub statement: CassandraStatement,
+    /// the comments on the lines before the statement.
+    pub leading_comments: Vec<Comment>,
+    /// the comments that follow the statement on the line it ends on.
+    pub trailing_comments: Vec<Comment>,
     /// the beginning byte of the text for the parsed statement within
     /// the original statement.
-    start_byte: usize,
+    pub(crate) start_byte: usize,
     /// the ending byte of the text for the parsed statement within
     /// the original statement.
-    end_byte: usize,
+    pub(crate) end_byte: usize,
+}
+
+impl MoveSpans for ParsedStatement {
+    fn move_spans(&mut self, offset: isize) {
+        self.statement.move_spans(offset);
+        self.leading_comments.move_spans(offset);
+        self.trailing_comments.move_spans(offset);
+        self.start_byte = move_byte(self.start_byte, offset);
+        self.end_byte = move_byte(self.end_byte, offset);
+    }
 }
 
 impl ParsedStatement {
+    /// create the parsed statement for a statement node.
+    /// Nodes that can not be interpreted produce `CassandraStatement::Unknown` and are marked
+    /// as having an error.
     pub fn new(node: Node, source: &str) -> ParsedStatement {
+        ParsedStatement::from_nodes(&[node], source, &mut vec![])
+    }
+
+    /// create the parsed statement for a statement node.
+    /// A node that contains syntax errors and can not be interpreted produces
+    /// `CassandraStatement::Unknown` as the syntax errors are reported by `CassandraAST::errors`.
+    /// Returns an error if a node without syntax errors can not be interpreted.
+    pub fn try_new(node: Node, source: &str) -> Result<ParsedStatement, ParseError> {
+        ParsedStatement::try_from_nodes(&[node], source, &mut vec![])
+    }
+
+    /// create the parsed statement for a group of statement nodes, either a single statement or
+    /// a batch (see `CassandraStatement::statement_groups`).
+    /// Nodes that can not be interpreted produce `CassandraStatement::Unknown` and are marked
+    /// as having an error.
+    pub(crate) fn from_nodes(
+        nodes: &[Node],
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> ParsedStatement {
+        ParsedStatement::try_from_nodes(nodes, source, recovered)
+            .unwrap_or_else(|_| ParsedStatement::unknown(nodes, source))
+    }
+
+    /// create the parsed statement for a group of statement nodes, see `try_new`.
+    /// A batch that holds statements other than `INSERT`, `UPDATE` and `DELETE` is marked as
+    /// having an error.  A select statement whose clauses continue in the error that follows
+    /// it is not.  The parts of the text read for the statement are only added to `recovered`
+    /// if it is not unknown.
+    pub(crate) fn try_from_nodes(
+        nodes: &[Node],
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<ParsedStatement, ParseError> {
+        let mut read = vec![];
+        let (has_error, result) = match nodes {
+            [node] => (
+                node.is_error(),
+                CassandraStatement::try_from_node(node, source, &mut read),
+            ),
+            [node, _] if node.kind().eq("create_index") => (
+                false,
+                CassandraStatement::try_from_node(node, source, &mut read),
+            ),
+            [node, error] if BorrowedParser::continues_insert(node, error, source) => (
+                false,
+                BorrowedParser::parse_continued_insert(node, error, source, &mut read)
+                    .map(|insert| CassandraStatement::Insert(insert.into_owned())),
+            ),
+            [node, error] if !CassandraStatement::is_batch(nodes) => (
+                false,
+                BorrowedParser::parse_continued_select(node, error, source, &mut read)
+                    .map(|select| CassandraStatement::Select(select.into_owned())),
+            ),
+            _ => (
+                // the last node is `APPLY BATCH`.
+                nodes[..nodes.len() - 1]
+                    .iter()
+                    .any(|x| x.is_error() || !CassandraStatement::allowed_in_batch(x)),
+                CassandraParser::parse_batch(nodes, source, &mut read),
+            ),
+        };
+        match result {
+            Ok(CassandraStatement::Unknown(_)) => Ok(ParsedStatement::unknown(nodes, source)),
+            Ok(statement) => {
+                recovered.append(&mut read);
+                Ok(ParsedStatement::from_statement(nodes, statement, has_error))
+            }
+            Err(_) if nodes.iter().any(|x| x.has_error()) => {
+                Ok(ParsedStatement::unknown(nodes, source))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// the location of the statement in the query text.
+    pub fn span(&self) -> Span {
+        Span::new(self.start_byte, self.end_byte)
+    }
+
+    fn unknown(nodes: &[Node], source: &str) -> ParsedStatement {
+        ParsedStatement::from_statement(
+            nodes,
+            CassandraStatement::Unknown(source.to_string()),
+            true,
+        )
+    }
+
+    fn from_statement(
+        nodes: &[Node],
+        statement: CassandraStatement,
+        has_error: bool,
+    ) -> ParsedStatement {
         ParsedStatement {
-            has_error: node.is_error(),
-            statement: CassandraStatement::from_node(&node, source),
-            start_byte: node.start_byte(),
-            end_byte: node.end_byte(),
+            has_error,
+            statement,
+            leading_comments: vec![],
+            trailing_comments: vec![],
+            start_byte: nodes[0].start_byte(),
+            end_byte: nodes[nodes.len() - 1].end_byte(),
+        }
+    }
+}
+
+/// Displays the statement with its comments.  A line comment is followed by a line break so
+/// text written after the statement is not part of the comment.
+impl Display for ParsedStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for comment in &self.leading_comments {
+            writeln!(f, "{}", comment)?;
+        }
+        write!(f, "{}", self.statement)?;
+        for comment in &self.trailing_comments {
+            write!(f, " {}", comment)?;
+            if comment.is_line_comment() {
+                writeln!(f)?;
+            }
         }
+        Ok(())
     }
 }
 
 pub struct CassandraAST {
     /// The query string
-    text: String,
-    /// the tree-sitter tree
-    pub(crate) tree: Tree,
+    pub(crate) text: String,
+    /// the tree-sitter tree, `None` if tree-sitter could not produce one.
+    pub(crate) tree: Option<Tree>,
+    /// the parts of the text holding syntax errors that were read by the parser, see
+    /// `Recovered`.
+    pub(crate) recovered: Vec<Recovered>,
     /// the statement type of the query
     pub statements: Vec<ParsedStatement>,
+    /// the problems found while parsing the query.  Empty if the query parsed cleanly.
+    pub errors: Vec<ParseError>,
+    /// the comments in the query.
+    pub comments: Vec<Comment>,
 }
 
 impl CassandraAST {
-    /// create an AST from the query string
+    /// create an AST from the query string using the thread's default `CqlParser`.
+    /// If tree-sitter could not produce a tree, because the grammar is not compatible with the
+    /// tree-sitter library or the parse was aborted by a timeout or cancellation set on the
+    /// default parser, the query is a single `CassandraStatement::Unknown` and the problem is
+    /// the only error.  Use `try_new` to receive these problems as an error.
     pub fn new(cassandra_statement: &str) -> CassandraAST {
-        let language = tree_sitter_cql::language();
-        let mut parser = tree_sitter::Parser::new();
-        if parser.set_language(&language).is_err() {
-            panic!("language version mismatch");
+        match CqlParser::with_default(|parser| {
+            parser
+                .parse_tree(cassandra_statement, None)
+                .map(|tree| (tree, parser.dialect()))
+        })
+        .and_then(|tree| tree)
+        {
+            Ok((tree, dialect)) => CassandraAST::from_tree(cassandra_statement, tree, dialect),
+            Err(error) => CassandraAST::without_tree(cassandra_statement, error),
         }
+    }
 
-        // this code enables debug logging
-        /*
-        fn log( _x : LogType, message : &str) {
-            println!("{}", message );
+    /// create an AST for a query that tree-sitter could not produce a tree for.
+    fn without_tree(cassandra_statement: &str, error: ParseError) -> CassandraAST {
+        CassandraAST {
+            statements: vec![ParsedStatement {
+                has_error: true,
+                statement: CassandraStatement::Unknown(cassandra_statement.to_string()),
+                leading_comments: vec![],
+                trailing_comments: vec![],
+                start_byte: 0,
+                end_byte: cassandra_statement.len(),
+            }],
+            errors: vec![error],
+            comments: Comment::find(cassandra_statement),
+            text: cassandra_statement.to_string(),
+            tree: None,
+            recovered: vec![],
         }
-        parser.set_logger( Some( Box::new( log)) );
-        */
+    }
 
-        let tree = parser.parse(cassandra_statement, None).unwrap();
+    /// create an AST from the query string using the thread's default `CqlParser`.
+    /// Syntax errors in the query do not cause a failure, they are reported in `errors`.
+    /// Returns an error if tree-sitter could not produce a tree or if a statement without syntax
+    /// errors does not have the shape the parser expects.
+    pub fn try_new(cassandra_statement: &str) -> Result<CassandraAST, ParseError> {
+        CqlParser::with_default(|parser| parser.parse(cassandra_statement))?
+    }
+
+    /// create an AST from a tree produced by parsing the query string.
+    /// A statement without syntax errors that does not have the shape the parser expects is
+    /// `CassandraStatement::Unknown` and the problem is added to the errors.
+    fn from_tree(cassandra_statement: &str, tree: Tree, dialect: Dialect) -> CassandraAST {
+        let comments = Comment::find(cassandra_statement);
+        let mut failures = vec![];
+        let mut recovered = vec![];
+        let groups = CassandraStatement::statement_groups(&tree, cassandra_statement);
+        let mut statements = groups
+            .iter()
+            .map(|nodes| {
+                ParsedStatement::try_from_nodes(nodes, cassandra_statement, &mut recovered)
+                    .unwrap_or_else(|e| {
+                        failures.push(e);
+                        ParsedStatement::unknown(nodes, cassandra_statement)
+                    })
+            })
+            .collect::<Vec<_>>();
+        CassandraAST::attach_comments(&mut statements, &comments, cassandra_statement);
+        let mut errors = CassandraAST::collect_errors(
+            dialect,
+            &tree,
+            cassandra_statement,
+            &groups,
+            &recovered,
+            &mut statements,
+        );
+        errors.extend(failures);
+        errors.sort_by_key(|x| x.start_byte);
         CassandraAST {
-            statements: CassandraStatement::from_tree(&tree, cassandra_statement),
+            statements,
+            errors,
+            comments,
+            text: cassandra_statement.to_string(),
+            tree: Some(tree),
+            recovered,
+        }
+    }
+
+    /// create an AST from a tree produced by parsing the query string.
+    /// Returns an error if a statement without syntax errors does not have the shape the parser
+    /// expects.
+    pub(crate) fn try_from_tree(
+        cassandra_statement: &str,
+        tree: Tree,
+        dialect: Dialect,
+    ) -> Result<CassandraAST, ParseError> {
+        let comments = Comment::find(cassandra_statement);
+        let mut recovered = vec![];
+        let groups = CassandraStatement::statement_groups(&tree, cassandra_statement);
+        let mut statements = groups
+            .iter()
+            .map(|nodes| {
+                ParsedStatement::try_from_nodes(nodes, cassandra_statement, &mut recovered)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        CassandraAST::attach_comments(&mut statements, &comments, cassandra_statement);
+        let errors = CassandraAST::collect_errors(
+            dialect,
+            &tree,
+            cassandra_statement,
+            &groups,
+            &recovered,
+            &mut statements,
+        );
+        Ok(CassandraAST {
+            statements,
+            errors,
+            comments,
             text: cassandra_statement.to_string(),
-            tree,
+            tree: Some(tree),
+            recovered,
+        })
+    }
+
+    /// the problems found in the tree, see `find_errors`.  The statements that are unknown or
+    /// hold one of the problems are marked as having an error, the others are not.
+    pub(crate) fn collect_errors(
+        dialect: Dialect,
+        tree: &Tree,
+        text: &str,
+        groups: &[Vec<Node>],
+        recovered: &[Recovered],
+        statements: &mut [ParsedStatement],
+    ) -> Vec<ParseError> {
+        let errors = CassandraAST::find_errors(dialect, tree, text, groups, recovered);
+        for statement in statements.iter_mut() {
+            statement.has_error = matches!(statement.statement, CassandraStatement::Unknown(_))
+                || errors.iter().any(|x| x.is_within(statement.span()));
+        }
+        errors
+    }
+
+    /// the problems found in the tree: the syntax errors, the problems with the batches (see
+    /// `CassandraStatement::batch_errors`) and the syntax the dialect does not support (see
+    /// `Dialect::check`).  `groups` are the statement groups of the tree, see
+    /// `CassandraStatement::statement_groups`.  The syntax errors in the parts of the text that
+    /// were read by the parser (`recovered`) are not included.
+    pub(crate) fn find_errors(
+        dialect: Dialect,
+        tree: &Tree,
+        text: &str,
+        groups: &[Vec<Node>],
+        recovered: &[Recovered],
+    ) -> Vec<ParseError> {
+        let mut errors = ParseError::collect(&tree.root_node(), text);
+        let unsupported = dialect.check(&tree.root_node(), text, &mut errors);
+        errors.extend(unsupported);
+        errors.retain(|x| !recovered.iter().any(|read| read.covers(x)));
+        errors.extend(CassandraStatement::batch_errors(groups, text));
+        errors.sort_by_key(|x| x.start_byte);
+        errors
+    }
+
+    /// give the statements the comments that are before them, or after them on the same line.
+    /// Comments within a `CREATE TABLE` statement that follow a column definition on the same
+    /// line are given to the column.  Other comments within statements are only found in
+    /// `CassandraAST::comments`.
+    pub(crate) fn attach_comments(
+        statements: &mut [ParsedStatement],
+        comments: &[Comment],
+        text: &str,
+    ) {
+        for statement in statements.iter_mut() {
+            statement.leading_comments.clear();
+            statement.trailing_comments.clear();
+            if let CassandraStatement::CreateTable(table) = &mut statement.statement {
+                table
+                    .columns
+                    .iter_mut()
+                    .for_each(|column| column.comment = None);
+            }
+        }
+        for comment in comments {
+            let start = match comment.span {
+                Some(span) => span.start_byte,
+                None => continue,
+            };
+            if let Some(statement) = statements
+                .iter_mut()
+                .find(|x| x.start_byte <= start && start < x.end_byte)
+            {
+                if let CassandraStatement::CreateTable(table) = &mut statement.statement {
+                    let column = table.columns.iter_mut().find(|column| {
+                        column
+                            .span
+                            .and_then(|span| text.get(span.end_byte..start))
+                            .map_or(false, |between| {
+                                !between.contains('\n') && matches!(between.trim(), "" | ",")
+                            })
+                    });
+                    if let Some(column) = column {
+                        column.comment = Some(comment.clone());
+                    }
+                }
+                continue;
+            }
+            let previous = statements.iter().rposition(|x| x.end_byte <= start);
+            let next = statements.iter().position(|x| start < x.start_byte);
+            let same_line = previous.map_or(false, |idx| {
+                !text[statements[idx].end_byte..start].contains('\n')
+            });
+            match (previous, next) {
+                (Some(idx), _) if same_line => {
+                    statements[idx].trailing_comments.push(comment.clone())
+                }
+                (_, Some(idx)) => statements[idx].leading_comments.push(comment.clone()),
+                (Some(idx), None) => statements[idx].trailing_comments.push(comment.clone()),
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// replace the text between the `start` and `old_end` byte offsets with `new_text` and
+    /// reparse the query using the thread's default `CqlParser`.  See `CqlParser::edit`.
+    pub fn edit(&mut self, start: usize, old_end: usize, new_text: &str) -> Result<(), ParseError> {
+        CqlParser::with_default(|parser| parser.edit(self, start, old_end, new_text))?
+    }
+
+    /// apply an edit to the text and reparse it with `parser`.
+    pub(crate) fn apply_edit(
+        &mut self,
+        parser: &mut CqlParser,
+        start: usize,
+        old_end: usize,
+        new_text: &str,
+    ) -> Result<(), ParseError> {
+        if start > old_end
+            || !self.text.is_char_boundary(start)
+            || !self.text.is_char_boundary(old_end)
+        {
+            return Err(ParseError::without_location(ParseErrorKind::InvalidEdit));
+        }
+        let text = format!(
+            "{}{}{}",
+            &self.text[..start],
+            new_text,
+            &self.text[old_end..]
+        );
+        let new_end = start + new_text.len();
+        // adding or removing comment markers changes which text is blanked out of the parsed text
+        // so the edit covers every change to the parsed text.
+        let old_blanked = Comment::blank(&self.text, &self.comments);
+        let comments = Comment::find(&text);
+        let new_blanked = Comment::blank(&text, &comments);
+        let (start, old_end, new_end) =
+            CassandraAST::blanked_edit(&old_blanked, &new_blanked, start, old_end, new_end);
+        let edit = InputEdit {
+            start_byte: start,
+            old_end_byte: old_end,
+            new_end_byte: new_end,
+            start_position: CassandraAST::point_at(&self.text, start),
+            old_end_position: CassandraAST::point_at(&self.text, old_end),
+            new_end_position: CassandraAST::point_at(&text, new_end),
+        };
+        let old_tree = self.tree.clone().map(|mut old_tree| {
+            old_tree.edit(&edit);
+            old_tree
+        });
+        let tree = parser.parse_tree(&text, old_tree.as_ref())?;
+
+        // reuse the statements the edit did not change: the statements that end before the edit
+        // and the statements that begin after it, moved by the change in the length of the text.
+        // Without a previous tree every statement is rebuilt.
+        let changed = old_tree
+            .as_ref()
+            .map(|old_tree| old_tree.changed_ranges(&tree).collect::<Vec<_>>());
+        let groups = CassandraStatement::statement_groups(&tree, &text);
+        let offset = new_end as isize - old_end as isize;
+        let moved = |statement: &ParsedStatement| {
+            if statement.end_byte <= start {
+                Some(0)
+            } else if old_end <= statement.start_byte {
+                Some(offset)
+            } else {
+                None
+            }
+        };
+        let mut old = self
+            .statements
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, statement)| moved(statement).map(|offset| (idx, offset)))
+            .peekable();
+        let mut reused = vec![];
+        let mut rebuilt = vec![];
+        for nodes in &groups {
+            let (start_byte, end_byte) = (nodes[0].start_byte(), nodes[nodes.len() - 1].end_byte());
+            while old
+                .next_if(|(idx, offset)| {
+                    move_byte(self.statements[*idx].start_byte, *offset) < start_byte
+                })
+                .is_some()
+            {}
+            let kept = old.next_if(|(idx, offset)| {
+                let statement = &self.statements[*idx];
+                move_byte(statement.start_byte, *offset) == start_byte
+                    && move_byte(statement.end_byte, *offset) == end_byte
+                    // unknown statements hold the text of the whole query.
+                    && !matches!(statement.statement, CassandraStatement::Unknown(_))
+                    && changed.as_ref().map_or(false, |changed| {
+                        !changed.iter().any(|range| {
+                            range.start_byte <= end_byte && start_byte <= range.end_byte
+                        })
+                    })
+            });
+            if kept.is_none() {
+                let mut recovered = vec![];
+                let statement = ParsedStatement::try_from_nodes(nodes, &text, &mut recovered)?;
+                rebuilt.push((statement, recovered));
+            }
+            reused.push(kept);
+        }
+
+        let old_recovered = std::mem::take(&mut self.recovered);
+        let mut old_statements = std::mem::take(&mut self.statements).into_iter().enumerate();
+        let mut rebuilt = rebuilt.into_iter();
+        for kept in reused {
+            match kept {
+                Some((idx, offset)) => {
+                    if let Some((_, mut statement)) = old_statements.find(|(x, _)| *x == idx) {
+                        // the parts of the text read for the statement move with it.
+                        self.recovered.extend(
+                            old_recovered
+                                .iter()
+                                .filter(|x| {
+                                    statement.start_byte <= x.start_byte
+                                        && x.end_byte <= statement.end_byte
+                                })
+                                .map(|x| {
+                                    let mut x = x.clone();
+                                    x.move_spans(offset);
+                                    x
+                                }),
+                        );
+                        statement.move_spans(offset);
+                        self.statements.push(statement);
+                    }
+                }
+                None => {
+                    if let Some((statement, mut recovered)) = rebuilt.next() {
+                        self.recovered.append(&mut recovered);
+                        self.statements.push(statement);
+                    }
+                }
+            }
+        }
+        CassandraAST::attach_comments(&mut self.statements, &comments, &text);
+        self.errors = CassandraAST::collect_errors(
+            parser.dialect(),
+            &tree,
+            &text,
+            &groups,
+            &self.recovered,
+            &mut self.statements,
+        );
+        self.comments = comments;
+        self.text = text;
+        self.tree = Some(tree);
+        Ok(())
+    }
+
+    /// the `start`, `old_end` and `new_end` byte offsets of an edit extended to cover every
+    /// difference between the old and new blanked texts.
+    fn blanked_edit(
+        old: &str,
+        new: &str,
+        start: usize,
+        old_end: usize,
+        new_end: usize,
+    ) -> (usize, usize, usize) {
+        let prefix = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut start = start.min(prefix);
+        while !old.is_char_boundary(start) {
+            start -= 1;
         }
+        let suffix = old
+            .bytes()
+            .rev()
+            .zip(new.bytes().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut extended_end = old_end.max(old.len().saturating_sub(suffix));
+        while !old.is_char_boundary(extended_end) {
+            extended_end += 1;
+        }
+        (start, extended_end, new_end + (extended_end - old_end))
+    }
+
+    /// the tree-sitter point (row and byte column) of a byte offset in the text.
+    fn point_at(text: &str, byte: usize) -> Point {
+        let line_start = text[..byte].rfind('\n').map_or(0, |newline| newline + 1);
+        Point::new(text[..byte].matches('\n').count(), byte - line_start)
     }
 
-    /// returns true if the parsing exposed an error in the query
+    /// returns true if the parsing exposed an error in the query, see `errors`.
     pub fn has_error(&self) -> bool {
-        self.tree.root_node().has_error()
+        !self.errors.is_empty()
     }
 
     /// retrieves the query value for the node (word or phrase enclosed by the node)
@@ -2069,8 +5577,18 @@ impl CassandraAST {
 
 #[cfg(test)]
 mod tests {
-    use crate::cassandra_ast::{CassandraAST, ParsedStatement};
+    use crate::cassandra_ast::{CassandraAST, Construct, ParsedStatement};
     use crate::cassandra_statement::CassandraStatement;
+    use crate::comment::Comment;
+    use crate::common::{FQName, Identifier, Literal, Operand, RelationElement, Span, WithItem};
+    use crate::cql_parser::CqlParser;
+    use crate::dialect::Dialect;
+    use crate::parse_error::{ParseErrorKind, Position};
+    use crate::select::SelectElement;
+    use crate::statement_summary::classify;
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::panic;
 
     #[test]
     fn test_invalid_statement() {
@@ -2078,6 +5596,8 @@ mod tests {
         let result = ParsedStatement {
             has_error: true,
             statement: CassandraStatement::Unknown(statement.to_string()),
+            leading_comments: vec![],
+            trailing_comments: vec![],
             start_byte: 0,
             end_byte: 28,
         };
@@ -2096,12 +5616,16 @@ mod tests {
             ParsedStatement {
                 has_error: false,
                 statement: select.clone(),
+                leading_comments: vec![],
+                trailing_comments: vec![],
                 start_byte: 0,
                 end_byte: 17,
             },
             ParsedStatement {
                 has_error: true,
                 statement: CassandraStatement::Unknown(statement.to_string()),
+                leading_comments: vec![],
+                trailing_comments: vec![],
                 start_byte: 18,
                 end_byte: 41,
             },
@@ -2122,12 +5646,16 @@ mod tests {
             ParsedStatement {
                 has_error: false,
                 statement: select1.clone(),
+                leading_comments: vec![],
+                trailing_comments: vec![],
                 start_byte: 0,
                 end_byte: 17,
             },
             ParsedStatement {
                 has_error: false,
                 statement: select2.clone(),
+                leading_comments: vec![],
+                trailing_comments: vec![],
                 start_byte: 19,
                 end_byte: 36,
             },
@@ -2150,4 +5678,817 @@ mod tests {
         assert_eq!(36, result.end_byte);
         assert_eq!(stmt.to_string(), result.statement.to_string());
     }
+
+    #[test]
+    fn test_no_errors() {
+        let ast = CassandraAST::new("SELECT * FROM foo WHERE bar = 5");
+        assert!(ast.errors.is_empty());
+    }
+
+    #[test]
+    fn test_trailing_error() {
+        let ast = CassandraAST::new("SELECT * FROM foo WHERE some invalid part");
+        assert_eq!(1, ast.errors.len());
+        let error = &ast.errors[0];
+        assert_eq!(ParseErrorKind::Error, error.kind);
+        assert_eq!(18, error.start_byte);
+        assert_eq!(41, error.end_byte);
+        assert_eq!(
+            Position {
+                line: 1,
+                column: 19
+            },
+            error.start
+        );
+        assert_eq!(
+            Position {
+                line: 1,
+                column: 42
+            },
+            error.end
+        );
+        assert_eq!("WHERE some invalid part", error.text);
+        assert_eq!(Some("select_statement"), error.statement);
+        assert_eq!(
+            "syntax error at line 1, column 19 near 'WHERE some invalid part' in select_statement",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let ast = CassandraAST::new("SELECT * FROM foo;\n  \u{1F44D} SELECT * FROM bar;");
+        assert_eq!(1, ast.errors.len());
+        let error = &ast.errors[0];
+        assert_eq!(Position { line: 2, column: 3 }, error.start);
+        assert_eq!("\u{1F44D}", error.text);
+        assert_eq!(None, error.statement);
+    }
+
+    #[test]
+    fn test_missing_token() {
+        let ast = CassandraAST::new("INSERT INTO t (a) VALUES (1");
+        assert!(ast.has_error());
+        assert!(ast
+            .errors
+            .iter()
+            .any(|e| matches!(e.kind, ParseErrorKind::Missing(_))));
+        assert!(ast
+            .errors
+            .iter()
+            .all(|e| e.statement == Some("insert_statement")));
+    }
+
+    #[test]
+    fn test_unparsed_identifiers() {
+        // the owned and borrowed text readers accept the same identifiers.
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::Cassandra5_0);
+        for (query, error) in [
+            ("ALTER TABLE t ALTER x MASKED WITH mask_null()", false),
+            ("ALTER TABLE t ALTER _x MASKED WITH mask_null()", true),
+            ("ALTER TABLE t ALTER \"_x\" MASKED WITH mask_null()", false),
+            ("SELECT a FROM t GROUP BY a", false),
+            ("SELECT a FROM t GROUP BY _a", true),
+            ("SELECT maxwritetime(a) AS _b FROM t", true),
+        ] {
+            let ast = parser.parse(query).unwrap();
+            let borrowed = parser.parse_borrowed(query).unwrap();
+            assert_eq!(error, ast.has_error(), "{}", query);
+            assert_eq!(ast.errors, borrowed.errors, "{}", query);
+            assert_eq!(
+                ast.statements
+                    .iter()
+                    .map(|x| x.statement.to_string())
+                    .collect::<Vec<String>>(),
+                borrowed
+                    .statements
+                    .iter()
+                    .map(|x| x.statement.clone().into_owned().to_string())
+                    .collect::<Vec<String>>(),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_recovered_constructs() {
+        for (query, constructs) in [
+            ("SELECT a FROM t", vec![]),
+            ("SELECT a FROM t GROUP BY a", vec![Construct::SelectClauses]),
+            (
+                "UPDATE t USING TTL ? SET a = 1 WHERE b = 2",
+                vec![Construct::Using],
+            ),
+            (
+                "CREATE INDEX ON t (VALUES(a))",
+                vec![Construct::IndexValues, Construct::IndexClauses],
+            ),
+            (
+                "CREATE TABLE t (a vector<float, 3> PRIMARY KEY)",
+                vec![Construct::DataType],
+            ),
+            (
+                "SELECT a FROM t; SELECT a FROM t PER PARTITION LIMIT ? LIMIT ?",
+                vec![Construct::SelectClauses],
+            ),
+            // the text read for a statement that is not parsed is not kept.
+            ("SELECT a FROM t GROUP a", vec![]),
+        ] {
+            let ast = CassandraAST::new(query);
+            let recovered: Vec<Construct> = ast.recovered.iter().map(|x| x.construct).collect();
+            assert_eq!(constructs, recovered, "{}", query);
+            for read in &ast.recovered {
+                assert!(read.start_byte < read.end_byte, "{}", query);
+                assert!(read.end_byte <= query.len(), "{}", query);
+            }
+            let borrowed = CqlParser::with_default(|x| x.parse_borrowed(query))
+                .unwrap()
+                .unwrap();
+            assert_eq!(ast.recovered, borrowed.into_owned().recovered, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_recovered_errors() {
+        // the text the parser read from the errors is not reported, the rest is.
+        for (query, error) in [
+            ("SELECT a FROM t GROUP BY a", false),
+            ("SELECT a FROM t LIMIT ?", false),
+            ("SELECT a FROM t PER PARTITION LIMIT ? LIMIT :n", false),
+            ("UPDATE t USING TTL ? SET a = 1 WHERE b = 2", false),
+            ("CREATE INDEX ON t (VALUES(a))", false),
+            ("SELECT a FROM t GROUP BY a zq", true),
+            ("SELECT a FROM t WHERE b = 1 zq LIMIT ?", true),
+            ("UPDATE t USING TTL ? zq SET a = 1 WHERE b = 2", true),
+            ("SELECT a FROM t; SELECT a FROM t LIMIT ? zq", true),
+            ("CREATE TABLE t (a int PRIMARY KEY, b IN text)", true),
+            ("CREATE TYPE type (col1 IN UUID)", true),
+            ("ALTER TABLE ks.t ADD c IN int", true),
+            ("SELECT ? * FROM foo.table", true),
+            (
+                "SELECT a FROM t GROUP BY a junk PER PARTITION LIMIT 3",
+                true,
+            ),
+            ("SELECT a FROM t PER PARTITION zzz LIMIT 3", true),
+            ("SELECT a FROM t GROUP BY 3 PER PARTITION LIMIT 3", true),
+            ("SELECT a FROM t GROUP a", true),
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(error, ast.has_error(), "{}", query);
+            assert_eq!(
+                error,
+                ast.statements.iter().any(|x| x.has_error),
+                "{}",
+                query
+            );
+            for statement in &ast.statements {
+                assert_eq!(
+                    ast.errors.iter().any(|x| x.is_within(statement.span())),
+                    statement.has_error,
+                    "{}",
+                    query
+                );
+            }
+            let borrowed = CqlParser::with_default(|x| x.parse_borrowed(query))
+                .unwrap()
+                .unwrap();
+            assert_eq!(ast.errors, borrowed.errors, "{}", query);
+            let flags: Vec<bool> = ast.statements.iter().map(|x| x.has_error).collect();
+            assert_eq!(
+                flags,
+                borrowed
+                    .statements
+                    .iter()
+                    .map(|x| x.has_error)
+                    .collect::<Vec<bool>>(),
+                "{}",
+                query
+            );
+            // the summaries do not read the errors, so recovered errors are not filtered out.
+            let summaries = classify(query).unwrap();
+            assert_eq!(flags.len(), summaries.len(), "{}", query);
+            for (flag, summary) in flags.iter().zip(&summaries) {
+                assert!(!flag || summary.has_error, "{}", query);
+            }
+        }
+
+        // the statements rebuilt after an edit are checked the same way.
+        let mut ast = CassandraAST::new("SELECT a FROM t LIMIT ?; SELECT b FROM t");
+        let end = ast.text.len();
+        ast.edit(end, end, " GROUP BY b").unwrap();
+        assert!(!ast.has_error());
+        ast.edit(end, end, " zq").unwrap();
+        assert!(ast.has_error());
+        assert!(!ast.statements[0].has_error);
+        let parsed = CassandraAST::new(&ast.text);
+        assert_eq!(parsed.errors, ast.errors);
+        assert_eq!(
+            parsed
+                .statements
+                .iter()
+                .map(|x| x.has_error)
+                .collect::<Vec<bool>>(),
+            ast.statements
+                .iter()
+                .map(|x| x.has_error)
+                .collect::<Vec<bool>>()
+        );
+    }
+
+    #[test]
+    fn test_select_spans() {
+        let text = "SELECT a, b AS c FROM ks.tbl WHERE a = 5 ORDER BY b DESC";
+        let ast = CassandraAST::new(text);
+        let select = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select,
+            _ => panic!("not a select"),
+        };
+        assert_eq!(0..text.len(), select.span.unwrap().range());
+        let columns: Vec<&str> = select
+            .columns
+            .iter()
+            .map(|column| match column {
+                SelectElement::Column(named) => &text[named.span.unwrap().range()],
+                _ => panic!("not a column"),
+            })
+            .collect();
+        assert_eq!(vec!["a", "b AS c"], columns);
+        match &select.columns[1] {
+            SelectElement::Column(named) => {
+                assert_eq!("b", &text[named.name_span.unwrap().range()])
+            }
+            _ => panic!("not a column"),
+        }
+        assert_eq!("ks.tbl", &text[select.table_name.span.unwrap().range()]);
+        let relation = &select.where_relations().unwrap()[0];
+        assert_eq!("a = 5", &text[relation.span.unwrap().range()]);
+        assert_eq!("a", &text[relation.obj_span.unwrap().range()]);
+        assert_eq!("5", &text[relation.value_span.unwrap().range()]);
+        assert_eq!("b DESC", &text[select.order[0].span.unwrap().range()]);
+        assert_eq!("b", &text[select.order[0].name_span.unwrap().range()]);
+
+        // the relations the grammar does not accept are read from the text.
+        let text = "SELECT a FROM t WHERE (a, b) IN ((1, 2)) AND ts > now() - 1d";
+        let ast = CassandraAST::new(text);
+        assert!(!ast.has_error());
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                let relations = select.where_relations().unwrap();
+                let obj: Vec<&str> = relations
+                    .iter()
+                    .map(|x| &text[x.obj_span.unwrap().range()])
+                    .collect();
+                let values: Vec<&str> = relations
+                    .iter()
+                    .map(|x| &text[x.value_span.unwrap().range()])
+                    .collect();
+                assert_eq!(vec!["(a, b)", "ts"], obj);
+                assert_eq!(vec!["((1, 2))", "now() - 1d"], values);
+            }
+            _ => panic!("not a select"),
+        }
+    }
+
+    #[test]
+    fn test_multi_column_order_spans() {
+        // the grammar accepts one column, the others are read from the error that follows it.
+        let text = "SELECT a FROM t ORDER BY b, c DESC , d LIMIT 1";
+        let ast = CassandraAST::new(text);
+        assert_eq!(1, ast.statements.len());
+        assert_eq!(
+            "SELECT a FROM t ORDER BY b ASC, c DESC, d ASC LIMIT 1",
+            ast.statements[0].statement.to_string()
+        );
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                let order: Vec<&str> = select
+                    .order
+                    .iter()
+                    .map(|x| &text[x.span.unwrap().range()])
+                    .collect();
+                assert_eq!(vec!["b", "c DESC", "d"], order);
+            }
+            _ => panic!("not a select"),
+        }
+
+        let text = "CREATE TABLE t (a int, b int, c int, PRIMARY KEY (a, b, c)) WITH CLUSTERING ORDER BY (b, c DESC)";
+        let ast = CassandraAST::new(text);
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(create) => match &create.with_clause[0] {
+                WithItem::ClusterOrder(order) => {
+                    assert_eq!(
+                        vec![false, true],
+                        order.iter().map(|x| x.desc).collect::<Vec<_>>()
+                    );
+                    assert_eq!("c DESC", &text[order[1].span.unwrap().range()]);
+                }
+                _ => panic!("not a cluster order"),
+            },
+            _ => panic!("not a create table"),
+        }
+
+        // the order of the first column is read when other columns follow, so the statement
+        // is parsed again from its display.
+        let displayed = ast.statements[0].statement.to_string();
+        assert!(displayed.ends_with("CLUSTERING ORDER BY (b ASC, c DESC)"));
+        let reparsed = CassandraAST::new(&displayed);
+        assert!(!reparsed.has_error(), "{}", displayed);
+        assert_eq!(
+            ast.statements[0].statement,
+            reparsed.statements[0].statement
+        );
+        match &reparsed.statements[0].statement {
+            CassandraStatement::CreateTable(create) => match &create.with_clause[0] {
+                WithItem::ClusterOrder(order) => {
+                    assert_eq!("b ASC", &displayed[order[0].span.unwrap().range()]);
+                    assert_eq!("c DESC", &displayed[order[1].span.unwrap().range()]);
+                }
+                _ => panic!("not a cluster order"),
+            },
+            _ => panic!("not a create table"),
+        }
+        for text in [
+            "CREATE TABLE t (a int, b int, c int, PRIMARY KEY (a, b, c)) WITH CLUSTERING ORDER BY (b DESC, c ASC)",
+            "CREATE MATERIALIZED VIEW v AS SELECT a, b, c FROM t WHERE b IS NOT NULL AND c IS NOT NULL PRIMARY KEY (a, b, c) WITH CLUSTERING ORDER BY (b DESC, c ASC)",
+        ] {
+            let ast = CassandraAST::new(text);
+            assert!(!ast.has_error(), "{}", text);
+            assert_eq!(text.replace(" int", " INT"), ast.statements[0].statement.to_string());
+        }
+        let ast = CassandraAST::new("CREATE TABLE t (a int, b int, c int, PRIMARY KEY (a, b, c)) WITH CLUSTERING ORDER BY (b ASC DESC, c)");
+        assert!(ast.has_error());
+    }
+
+    #[test]
+    fn test_modification_spans() {
+        let text = "INSERT INTO t (a) VALUES (1); UPDATE t SET m[1] = 2 WHERE a = 1; DELETE m[1] FROM t WHERE a = 1";
+        let ast = CassandraAST::new(text);
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => {
+                assert_eq!(
+                    "INSERT INTO t (a) VALUES (1)",
+                    &text[insert.span.unwrap().range()]
+                );
+                assert_eq!("t", &text[insert.table_name.span.unwrap().range()]);
+            }
+            _ => panic!("not an insert"),
+        }
+        match &ast.statements[1].statement {
+            CassandraStatement::Update(update) => {
+                assert_eq!(
+                    "UPDATE t SET m[1] = 2 WHERE a = 1",
+                    &text[update.span.unwrap().range()]
+                );
+                assert_eq!(
+                    "m[1] = 2",
+                    &text[update.assignments[0].span.unwrap().range()]
+                );
+                assert_eq!(
+                    "m[1]",
+                    &text[update.assignments[0].name.span.unwrap().range()]
+                );
+                assert_eq!(
+                    "2",
+                    &text[update.assignments[0].value_span.unwrap().range()]
+                );
+            }
+            _ => panic!("not an update"),
+        }
+        match &ast.statements[2].statement {
+            CassandraStatement::Delete(delete) => {
+                assert_eq!("m[1]", &text[delete.columns[0].span.unwrap().range()]);
+                assert_eq!("m", &text[delete.columns[0].column_span.unwrap().range()]);
+                assert_eq!("a = 1", &text[delete.where_clause[0].span.unwrap().range()]);
+                assert_eq!(
+                    "1",
+                    &text[delete.where_clause[0].value_span.unwrap().range()]
+                );
+            }
+            _ => panic!("not a delete"),
+        }
+
+        // an update the grammar does not accept is read from the text.
+        let text = "UPDATE t SET a = fn(b), m[1] = 2 WHERE c = 1";
+        match &CassandraAST::new(text).statements[0].statement {
+            CassandraStatement::Update(update) => {
+                let values: Vec<&str> = update
+                    .assignments
+                    .iter()
+                    .map(|x| &text[x.value_span.unwrap().range()])
+                    .collect();
+                assert_eq!(vec!["fn(b)", "2"], values);
+                assert_eq!(
+                    "m",
+                    &text[update.assignments[1].name.column_span.unwrap().range()]
+                );
+            }
+            statement => panic!("not an update: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_create_table_spans() {
+        let text =
+            "CREATE TABLE t (a int, b int, PRIMARY KEY (a, b)) WITH CLUSTERING ORDER BY (b ASC)";
+        let ast = CassandraAST::new(text);
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(create) => {
+                assert_eq!("a int", &text[create.columns[0].span.unwrap().range()]);
+                assert_eq!("a", &text[create.columns[0].name_span.unwrap().range()]);
+                match &create.with_clause[0] {
+                    WithItem::ClusterOrder(order) => {
+                        assert_eq!("b ASC", &text[order[0].span.unwrap().range()]);
+                        assert_eq!("b", &text[order[0].name_span.unwrap().range()]);
+                    }
+                    _ => panic!("not a cluster order"),
+                }
+            }
+            _ => panic!("not a create table"),
+        }
+    }
+
+    #[test]
+    fn test_spans_ignored_in_equality() {
+        let ast = CassandraAST::new("SELECT a FROM t WHERE a = 5");
+        let parsed = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select.where_relations().unwrap().remove(0),
+            _ => panic!("not a select"),
+        };
+        let mut moved = parsed.clone();
+        moved.span = Some(Span::new(100, 105));
+        assert_eq!(parsed, moved);
+        assert_eq!(
+            Operand::Literal(Literal::Integer("5".to_string())),
+            parsed.value
+        );
+        // an element built by hand has no spans.
+        let built = RelationElement {
+            span: None,
+            obj_span: None,
+            value_span: None,
+            ..parsed.clone()
+        };
+        assert_eq!(parsed, built);
+        assert_eq!(Ordering::Equal, parsed.cmp(&built));
+
+        let names: HashSet<FQName> = ast
+            .statements
+            .iter()
+            .filter_map(|x| match &x.statement {
+                CassandraStatement::Select(select) => Some(select.table_name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(names.contains(&FQName::simple("t")));
+    }
+
+    #[test]
+    fn test_try_new() {
+        let ast = CassandraAST::try_new("SELECT * FROM foo WHERE some invalid part").unwrap();
+        assert_eq!(2, ast.statements.len());
+        assert_eq!(1, ast.errors.len());
+    }
+
+    #[test]
+    fn test_try_new_invalid_value() {
+        let statement = "SELECT * FROM foo LIMIT 99999999999";
+        let error = CassandraAST::try_new(statement).err().unwrap();
+        assert_eq!(ParseErrorKind::InvalidValue, error.kind);
+        assert_eq!("99999999999", error.text);
+        assert_eq!(Some("select_statement"), error.statement);
+        assert_eq!(
+            "invalid value '99999999999' at line 1, column 25 in select_statement",
+            error.to_string()
+        );
+
+        let ast = CassandraAST::new(statement);
+        assert!(ast.statements[0].has_error);
+        assert_eq!(
+            CassandraStatement::Unknown(statement.to_string()),
+            ast.statements[0].statement
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_tuple() {
+        let statement =
+            "SELECT column FROM table WHERE (( col2) >= (col1, 5, 'stuff'), (6, 'other'))";
+        let ast = CassandraAST::try_new(statement).unwrap();
+        assert!(ast.has_error());
+        assert_eq!(
+            CassandraStatement::Unknown(statement.to_string()),
+            ast.statements[0].statement
+        );
+    }
+
+    #[test]
+    fn test_no_panic_on_damaged_statements() {
+        let statements = [
+            "SELECT column FROM table WHERE (col1, col2) >= ((5, 'stuff'), (6, 'other'))",
+            "SELECT DISTINCT JSON a, b AS c FROM ks.tbl WHERE a IN (1, 2) ORDER BY b DESC LIMIT 5 ALLOW FILTERING",
+            "INSERT INTO t (a, b) VALUES (1, {'k':'v'}) IF NOT EXISTS USING TTL 5",
+            "UPDATE t USING TIMESTAMP 3 SET m[1] = 2, c = c + 1 WHERE a = 1 IF b = 3",
+            "DELETE m[1] FROM t USING TIMESTAMP 5 WHERE a = 1 IF EXISTS",
+            "CREATE TABLE t (a int, b int, PRIMARY KEY ((a), b)) WITH CLUSTERING ORDER BY (b ASC)",
+            "CREATE OR REPLACE FUNCTION f (a int) RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE java AS 'x'",
+            "GRANT SELECT ON ALL KEYSPACES TO role",
+            "CREATE ROLE r WITH PASSWORD = 'p' AND LOGIN = true AND OPTIONS = {'a':'b'}",
+            "TRUNCATE TABLE ks.t",
+            "SELECT a FROM t WHERE b NOT CONTAINS KEY 'k' AND c = 0xCAFE LIMIT 3",
+            "INSERT INTO t (a, b, c) VALUES (1h30m, -NaN, 3.14e2)",
+        ];
+        for statement in statements {
+            let mut damaged = vec![];
+            let boundaries = statement
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain([statement.len()])
+                .collect::<Vec<usize>>();
+            for (start, end) in boundaries.iter().zip(boundaries.iter().skip(1)) {
+                damaged.push(statement[..*start].to_string());
+                damaged.push(format!("{}{}", &statement[..*start], &statement[*end..]));
+                // multi-byte characters within and in place of tokens.
+                damaged.push(format!("{}é{}", &statement[..*start], &statement[*start..]));
+                damaged.push(format!("{}é{}", &statement[..*start], &statement[*end..]));
+            }
+            let words = statement.split(' ').collect::<Vec<&str>>();
+            for i in 0..words.len() {
+                let mut without = words.clone();
+                without.remove(i);
+                damaged.push(without.join(" "));
+                for j in i + 1..words.len() {
+                    let mut swapped = words.clone();
+                    swapped.swap(i, j);
+                    damaged.push(swapped.join(" "));
+                }
+            }
+            for text in damaged {
+                let result = panic::catch_unwind(|| {
+                    if let Ok(ast) = CassandraAST::try_new(&text) {
+                        ast.statements.iter().for_each(|s| {
+                            s.statement.to_string();
+                        });
+                    }
+                    CassandraAST::new(&text).statements.iter().for_each(|s| {
+                        s.statement.to_string();
+                    });
+                });
+                assert!(result.is_ok(), "panic parsing: {}", text);
+            }
+        }
+    }
+
+    fn assert_edit(text: &str, start: usize, old_end: usize, new_text: &str, expected: &str) {
+        let mut ast = CassandraAST::new(text);
+        ast.edit(start, old_end, new_text).unwrap();
+        let reparsed = CassandraAST::new(expected);
+        assert_eq!(expected, ast.text);
+        assert_eq!(reparsed.statements, ast.statements);
+        // the debug output includes the spans that equality ignores.
+        assert_eq!(
+            format!("{:?}", reparsed.statements),
+            format!("{:?}", ast.statements)
+        );
+        assert_eq!(reparsed.recovered, ast.recovered);
+        assert_eq!(reparsed.errors, ast.errors);
+        assert_eq!(reparsed.comments, ast.comments);
+        for (edited, statement) in ast.statements.iter().zip(reparsed.statements.iter()) {
+            assert_eq!(reparsed.extract_text(statement), ast.extract_text(edited));
+        }
+        assert_eq!(
+            reparsed.tree.as_ref().unwrap().root_node().to_sexp(),
+            ast.tree.as_ref().unwrap().root_node().to_sexp()
+        );
+    }
+
+    #[test]
+    fn test_edit() {
+        let text =
+            "SELECT * FROM foo;\nSELECT a FROM bar WHERE a = 5;\nDELETE FROM baz WHERE b = 1";
+        // replace the table name in the second statement.
+        assert_edit(
+            text,
+            33,
+            36,
+            "other",
+            "SELECT * FROM foo;\nSELECT a FROM other WHERE a = 5;\nDELETE FROM baz WHERE b = 1",
+        );
+        // insert a statement.
+        assert_edit(
+            text,
+            19,
+            19,
+            "TRUNCATE t;\n",
+            "SELECT * FROM foo;\nTRUNCATE t;\nSELECT a FROM bar WHERE a = 5;\nDELETE FROM baz WHERE b = 1",
+        );
+        // extend the first statement.
+        assert_edit(
+            text,
+            17,
+            17,
+            " WHERE x = 1",
+            "SELECT * FROM foo WHERE x = 1;\nSELECT a FROM bar WHERE a = 5;\nDELETE FROM baz WHERE b = 1",
+        );
+        // remove the last statement.
+        assert_edit(
+            text,
+            49,
+            text.len(),
+            "",
+            "SELECT * FROM foo;\nSELECT a FROM bar WHERE a = 5;",
+        );
+    }
+
+    #[test]
+    fn test_edit_reuses_later_statements() {
+        let text =
+            "SELECT * FROM foo;\nSELECT a FROM bar WHERE a = 5;\nDELETE FROM baz WHERE b = 1";
+        let mut ast = CassandraAST::new(text);
+        // mark the statements so the reused ones can be told apart from rebuilt ones.
+        for statement in ast.statements.iter_mut() {
+            statement.has_error = true;
+        }
+        ast.statements[2].statement = CassandraStatement::Use(Identifier::parse("reused"));
+        ast.edit(14, 17, "other_table").unwrap();
+        let expected =
+            "SELECT * FROM other_table;\nSELECT a FROM bar WHERE a = 5;\nDELETE FROM baz WHERE b = 1";
+        let reparsed = CassandraAST::new(expected);
+        assert_eq!(expected, ast.text);
+        assert_eq!(reparsed.statements[0], ast.statements[0]);
+        assert_eq!(reparsed.statements[1], ast.statements[1]);
+        assert_eq!(
+            format!("{:?}", reparsed.statements[1]),
+            format!("{:?}", ast.statements[1])
+        );
+        assert_eq!(
+            CassandraStatement::Use(Identifier::parse("reused")),
+            ast.statements[2].statement
+        );
+        assert_eq!(reparsed.statements[2].span(), ast.statements[2].span());
+        assert!(ast.errors.is_empty());
+        assert!(!ast.has_error());
+    }
+
+    #[test]
+    fn test_edit_errors() {
+        // introduce an error and then fix it.
+        let mut ast = CassandraAST::new("SELECT * FROM foo WHERE a = 5");
+        ast.edit(24, 25, "").unwrap();
+        assert!(ast.has_error());
+        assert_eq!(
+            CassandraAST::new("SELECT * FROM foo WHERE  = 5").errors,
+            ast.errors
+        );
+        ast.edit(24, 24, "b").unwrap();
+        assert!(!ast.has_error());
+        assert!(ast.errors.is_empty());
+        assert_eq!(
+            "SELECT * FROM foo WHERE b = 5",
+            ast.statements[0].statement.to_string()
+        );
+    }
+
+    #[test]
+    fn test_edit_multi_byte() {
+        assert_edit(
+            "SELECT * FROM foo WHERE bar = '\u{1F44D}';\nSELECT * FROM x",
+            52,
+            53,
+            "y",
+            "SELECT * FROM foo WHERE bar = '\u{1F44D}';\nSELECT * FROM y",
+        );
+    }
+
+    #[test]
+    fn test_invalid_edit() {
+        let text = "SELECT * FROM foo WHERE bar = '\u{1F44D}'";
+        let mut ast = CassandraAST::new(text);
+        for (start, old_end) in [(5, 4), (0, 100), (32, 33)] {
+            let error = ast.edit(start, old_end, "x").err().unwrap();
+            assert_eq!(ParseErrorKind::InvalidEdit, error.kind);
+        }
+        assert_eq!(text, ast.text);
+        assert_eq!(CassandraAST::new(text).statements, ast.statements);
+    }
+
+    #[test]
+    fn test_comments() {
+        let text = "-- create the table\n/* version 2 */\nCREATE TABLE foo (a int PRIMARY KEY); // done\n\nSELECT * FROM foo; -- read it\n-- end of script\n";
+        let ast = CassandraAST::try_new(text).unwrap();
+        assert!(!ast.has_error());
+        assert!(ast.errors.is_empty());
+        assert_eq!(5, ast.comments.len());
+        assert_eq!(2, ast.statements.len());
+        fn texts(comments: &[Comment]) -> Vec<&str> {
+            comments.iter().map(|x| x.text.as_str()).collect()
+        }
+        assert_eq!(
+            vec!["-- create the table", "/* version 2 */"],
+            texts(&ast.statements[0].leading_comments)
+        );
+        assert_eq!(vec!["// done"], texts(&ast.statements[0].trailing_comments));
+        assert!(ast.statements[1].leading_comments.is_empty());
+        assert_eq!(
+            vec!["-- read it", "-- end of script"],
+            texts(&ast.statements[1].trailing_comments)
+        );
+        assert_eq!(
+            "-- create the table\n/* version 2 */\nCREATE TABLE foo (a INT PRIMARY KEY) // done\n",
+            ast.statements[0].to_string()
+        );
+        assert_eq!(
+            "SELECT * FROM foo -- read it\n -- end of script\n",
+            ast.statements[1].to_string()
+        );
+        assert_eq!(
+            "CREATE TABLE foo (a int PRIMARY KEY)",
+            ast.extract_text(&ast.statements[0])
+        );
+    }
+
+    #[test]
+    fn test_comments_in_strings() {
+        let text =
+            "SELECT \"b//c\" FROM foo WHERE a = '-- not a comment' AND d = $$ /* nor this */ $$";
+        let ast = CassandraAST::try_new(text).unwrap();
+        assert!(ast.comments.is_empty());
+        assert_eq!(text, ast.statements[0].statement.to_string());
+    }
+
+    #[test]
+    fn test_column_comments() {
+        let text = "CREATE TABLE foo (\n  a int PRIMARY KEY, -- the key\n  /* the value */ b text, c text /* unused */\n)";
+        let ast = CassandraAST::try_new(text).unwrap();
+        assert!(!ast.has_error());
+        let columns = match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(table) => &table.columns,
+            statement => panic!("not a table {:?}", statement),
+        };
+        assert_eq!(
+            vec![Some("-- the key"), None, Some("/* unused */")],
+            columns
+                .iter()
+                .map(|x| x.comment.as_ref().map(|x| x.text.as_str()))
+                .collect::<Vec<_>>()
+        );
+        let display = ast.statements[0].to_string();
+        assert_eq!(
+            "CREATE TABLE foo (a INT PRIMARY KEY, -- the key\nb TEXT, c TEXT /* unused */)",
+            display
+        );
+        // the displayed statement keeps the column comments.
+        let reparsed = CassandraAST::try_new(&display).unwrap();
+        assert_eq!(
+            ast.statements[0].statement,
+            reparsed.statements[0].statement
+        );
+    }
+
+    #[test]
+    fn test_edit_batch() {
+        let text = "USE ks; BEGIN BATCH INSERT INTO t (a) VALUES (1); DELETE FROM t WHERE a = 2;";
+        // applying the batch groups its statements.
+        let applied = format!("{} APPLY BATCH", text);
+        assert_edit(text, text.len(), text.len(), " APPLY BATCH", &applied);
+        // changing a statement within the batch rebuilds the batch.
+        let changed = applied.replace("(1)", "(5)");
+        assert_edit(&applied, 46, 47, "5", &changed);
+    }
+
+    #[test]
+    fn test_edit_comments() {
+        let text = "SELECT * FROM foo; SELECT * FROM bar; SELECT * FROM baz";
+        // open a block comment that hides the rest of the text.
+        assert_edit(
+            text,
+            19,
+            19,
+            "/* ",
+            "SELECT * FROM foo; /* SELECT * FROM bar; SELECT * FROM baz",
+        );
+        // close it again.
+        assert_edit(
+            "SELECT * FROM foo; /* SELECT * FROM bar; */ SELECT * FROM baz",
+            41,
+            43,
+            "",
+            "SELECT * FROM foo; /* SELECT * FROM bar;  SELECT * FROM baz",
+        );
+        assert_edit(
+            text,
+            18,
+            18,
+            " -- first",
+            "SELECT * FROM foo; -- first SELECT * FROM bar; SELECT * FROM baz",
+        );
+        assert_edit(
+            "SELECT * FROM foo; -- first\nSELECT * FROM bar",
+            19,
+            20,
+            "",
+            "SELECT * FROM foo; - first\nSELECT * FROM bar",
+        );
+    }
 }
@@ -2,8 +2,9 @@ use crate::aggregate::Aggregate;
 use crate::alter_materialized_view::AlterMaterializedView;
 use crate::alter_table::AlterTable;
 use crate::alter_type::AlterType;
-use crate::cassandra_ast::{CassandraParser, ParsedStatement};
-use crate::common::{FQName, Identifier, Privilege};
+use crate::begin_batch::{BatchType, BeginBatch};
+use crate::cassandra_ast::{BorrowedParser, CassandraParser, ParsedStatement, Recovered};
+use crate::common::{FQName, Identifier, MoveSpans, Privilege, TtlTimestamp};
 use crate::common_drop::CommonDrop;
 use crate::create_function::CreateFunction;
 use crate::create_index::CreateIndex;
@@ -17,6 +18,7 @@ use crate::delete::Delete;
 use crate::drop_trigger::DropTrigger;
 use crate::insert::Insert;
 use crate::list_role::ListRole;
+use crate::parse_error::ParseError;
 use crate::role_common::RoleCommon;
 use crate::select::Select;
 use crate::update::Update;
@@ -35,6 +37,14 @@ pub enum CassandraStatement {
     AlterType(AlterType),
     AlterUser(CreateUser),
     ApplyBatch,
+    /// the statements from `BEGIN BATCH` to `APPLY BATCH`.  Only `INSERT`, `UPDATE` and `DELETE`
+    /// statements are allowed in a batch.  The batch only accepts `USING TIMESTAMP`, a TTL in
+    /// `using` is not displayed.
+    Batch {
+        ty: BatchType,
+        using: TtlTimestamp,
+        statements: Vec<CassandraStatement>,
+    },
     CreateAggregate(Aggregate),
     CreateFunction(CreateFunction),
     CreateIndex(CreateIndex),
@@ -72,11 +82,31 @@ impl CassandraStatement {
     /// extract the cassandra statement from an AST tree.
     /// the boolean return value is `true` if there is a parsing error in the statement tree.
     pub fn from_tree(tree: &Tree, source: &str) -> Vec<ParsedStatement> {
+        let mut recovered = vec![];
+        CassandraStatement::statement_groups(tree, source)
+            .iter()
+            .map(|nodes| ParsedStatement::from_nodes(nodes, source, &mut recovered))
+            .collect()
+    }
+
+    /// extract the cassandra statements from an AST tree.
+    /// Returns an error if a statement without syntax errors does not have the shape the parser
+    /// expects.
+    pub fn try_from_tree(tree: &Tree, source: &str) -> Result<Vec<ParsedStatement>, ParseError> {
+        let mut recovered = vec![];
+        CassandraStatement::statement_groups(tree, source)
+            .iter()
+            .map(|nodes| ParsedStatement::try_from_nodes(nodes, source, &mut recovered))
+            .collect()
+    }
+
+    /// the top level nodes of the tree, one for each statement.
+    pub(crate) fn statement_nodes(tree: &Tree) -> Vec<Node> {
         let mut result = vec![];
         let mut cursor = tree.root_node().walk();
         let mut process = cursor.goto_first_child();
         while process {
-            result.push(ParsedStatement::new(cursor.node(), source));
+            result.push(cursor.node());
             process = cursor.goto_next_sibling();
             while process && cursor.node().kind().eq(";") {
                 process = cursor.goto_next_sibling();
@@ -85,112 +115,230 @@ impl CassandraStatement {
         result
     }
 
+    /// the top level nodes of the tree grouped by statement.  The nodes of a batch, from the
+    /// statement that starts with `BEGIN BATCH` to `APPLY BATCH`, are one group.  A select
+    /// or create index statement and the error that holds the rest of its clauses, see
+    /// `BorrowedParser::continues_select` and `CassandraParser::continues_index`, are one
+    /// group.  Every other node, including the statements of a batch that is not applied, is a
+    /// group on its own.
+    pub(crate) fn statement_groups<'t>(tree: &'t Tree, source: &str) -> Vec<Vec<Node<'t>>> {
+        let nodes = CassandraStatement::statement_nodes(tree);
+        let mut result = vec![];
+        let mut pos = 0;
+        while pos < nodes.len() {
+            let mut end = pos + 1;
+            let apply = if CassandraStatement::begins_batch(&nodes[pos], source) {
+                nodes[pos + 1..]
+                    .iter()
+                    .position(|x| {
+                        x.kind().eq("apply_batch") || CassandraStatement::begins_batch(x, source)
+                    })
+                    .filter(|x| nodes[pos + 1 + x].kind().eq("apply_batch"))
+            } else {
+                None
+            };
+            if let Some(apply) = apply {
+                end = pos + apply + 2;
+            } else if nodes.get(pos + 1).map_or(false, |x| {
+                BorrowedParser::continues_select(&nodes[pos], x, source)
+                    || BorrowedParser::continues_insert(&nodes[pos], x, source)
+                    || CassandraParser::continues_index(&nodes[pos], x, source)
+            }) {
+                end = pos + 2;
+            }
+            result.push(nodes[pos..end].to_vec());
+            pos = end;
+        }
+        result
+    }
+
+    /// true if the group of statement nodes is a batch, see `statement_groups`.
+    pub(crate) fn is_batch(nodes: &[Node]) -> bool {
+        nodes.len() > 1 && nodes[nodes.len() - 1].kind().eq("apply_batch")
+    }
+
+    /// true if the node is a statement that starts with `BEGIN BATCH`, or the error that holds
+    /// the `BEGIN BATCH` clause before a statement that is not allowed in a batch.
+    fn begins_batch(node: &Node, source: &str) -> bool {
+        match node.kind() {
+            "insert_statement" | "update" | "delete_statement" => {
+                node.child(0).map_or(false, |x| x.kind().eq("begin_batch"))
+            }
+            _ => CassandraParser::unparsed_begin_batch(node, source).is_some(),
+        }
+    }
+
+    /// the errors for the batches in the statement groups of a tree (see `statement_groups`):
+    /// the statements that are not allowed in a batch and the `APPLY BATCH` that is missing
+    /// after the statements of a batch that is not applied.
+    pub(crate) fn batch_errors(groups: &[Vec<Node>], source: &str) -> Vec<ParseError> {
+        let mut result = vec![];
+        for (pos, nodes) in groups.iter().enumerate() {
+            if CassandraStatement::is_batch(nodes) {
+                result.extend(
+                    nodes[..nodes.len() - 1]
+                        .iter()
+                        .filter(|node| !CassandraStatement::allowed_in_batch(node))
+                        .map(|node| ParseError::unexpected(node, source)),
+                );
+            } else if CassandraStatement::begins_batch(&nodes[0], source) {
+                // the batch ends with the last statement that may be part of it.
+                let last = groups[pos + 1..]
+                    .iter()
+                    .take_while(|x| {
+                        !x[0].is_error()
+                            && CassandraStatement::allowed_in_batch(&x[0])
+                            && !CassandraStatement::begins_batch(&x[0], source)
+                    })
+                    .last()
+                    .unwrap_or(nodes);
+                result.push(ParseError::missing_after(
+                    &last[last.len() - 1],
+                    source,
+                    "APPLY BATCH",
+                ));
+            }
+        }
+        result
+    }
+
+    /// true if the statement node may be part of a batch.  Nodes with syntax errors are
+    /// reported as syntax errors.
+    pub(crate) fn allowed_in_batch(node: &Node) -> bool {
+        node.is_error()
+            || matches!(
+                node.kind(),
+                "insert_statement" | "update" | "delete_statement"
+            )
+    }
+
     /// extract the cassandra statement from an AST node.
+    /// Nodes that can not be interpreted produce `CassandraStatement::Unknown`.
     pub fn from_node(node: &Node, source: &str) -> CassandraStatement {
-        match node.kind() {
+        CassandraStatement::try_from_node(node, source, &mut vec![])
+            .unwrap_or_else(|_| CassandraStatement::Unknown(source.to_string()))
+    }
+
+    /// extract the cassandra statement from an AST node.
+    /// Returns an error if the node does not have the shape the parser expects.  The parts of
+    /// the text that the grammar did not accept but were read are added to `recovered`.
+    pub fn try_from_node(
+        node: &Node,
+        source: &str,
+        recovered: &mut Vec<Recovered>,
+    ) -> Result<CassandraStatement, ParseError> {
+        Ok(match node.kind() {
             "alter_keyspace" => CassandraStatement::AlterKeyspace(
-                CassandraParser::parse_keyspace_data(node, source),
+                CassandraParser::parse_keyspace_data(node, source)?,
             ),
             "alter_materialized_view" => CassandraStatement::AlterMaterializedView(
-                CassandraParser::parse_alter_materialized_view(node, source),
+                CassandraParser::parse_alter_materialized_view(node, source, recovered)?,
             ),
             "alter_role" => {
-                CassandraStatement::AlterRole(CassandraParser::parse_create_role(node, source))
-            }
-            "alter_table" => {
-                CassandraStatement::AlterTable(CassandraParser::parse_alter_table(node, source))
-            }
-            "alter_type" => {
-                CassandraStatement::AlterType(CassandraParser::parse_alter_type(node, source))
+                CassandraStatement::AlterRole(CassandraParser::parse_create_role(node, source)?)
             }
+            "alter_table" => CassandraStatement::AlterTable(CassandraParser::parse_alter_table(
+                node, source, recovered,
+            )?),
+            "alter_type" => CassandraStatement::AlterType(CassandraParser::parse_alter_type(
+                node, source, recovered,
+            )?),
             "alter_user" => {
-                CassandraStatement::AlterUser(CassandraParser::parse_create_user(node, source))
+                CassandraStatement::AlterUser(CassandraParser::parse_create_user(node, source)?)
             }
             "apply_batch" => CassandraStatement::ApplyBatch,
             "create_aggregate" => CassandraStatement::CreateAggregate(
-                CassandraParser::parse_create_aggregate(node, source),
+                CassandraParser::parse_create_aggregate(node, source, recovered)?,
             ),
             "create_function" => CassandraStatement::CreateFunction(
-                CassandraParser::parse_function_data(node, source),
+                CassandraParser::parse_function_data(node, source, recovered)?,
             ),
-            "create_index" => {
-                CassandraStatement::CreateIndex(CassandraParser::parse_index(node, source))
-            }
+            "create_index" => CassandraStatement::CreateIndex(CassandraParser::parse_index(
+                node, source, recovered,
+            )?),
             "create_keyspace" => CassandraStatement::CreateKeyspace(
-                CassandraParser::parse_keyspace_data(node, source),
+                CassandraParser::parse_keyspace_data(node, source)?,
             ),
             "create_materialized_view" => CassandraStatement::CreateMaterializedView(
-                CassandraParser::parse_create_materialized_vew(node, source),
+                CassandraParser::parse_create_materialized_vew(node, source, recovered)?,
             ),
             "create_role" => {
-                CassandraStatement::CreateRole(CassandraParser::parse_create_role(node, source))
-            }
-            "create_table" => {
-                CassandraStatement::CreateTable(CassandraParser::parse_create_table(node, source))
+                CassandraStatement::CreateRole(CassandraParser::parse_create_role(node, source)?)
             }
+            "create_table" => CassandraStatement::CreateTable(CassandraParser::parse_create_table(
+                node, source, recovered,
+            )?),
             "create_trigger" => CassandraStatement::CreateTrigger(
-                CassandraParser::parse_create_trigger(node, source),
+                CassandraParser::parse_create_trigger(node, source)?,
             ),
-            "create_type" => {
-                CassandraStatement::CreateType(CassandraParser::parse_create_type(node, source))
-            }
+            "create_type" => CassandraStatement::CreateType(CassandraParser::parse_create_type(
+                node, source, recovered,
+            )?),
             "create_user" => {
-                CassandraStatement::CreateUser(CassandraParser::parse_create_user(node, source))
-            }
-            "delete_statement" => {
-                CassandraStatement::Delete(CassandraParser::parse_delete_statement(node, source))
+                CassandraStatement::CreateUser(CassandraParser::parse_create_user(node, source)?)
             }
+            "delete_statement" => CassandraStatement::Delete(
+                CassandraParser::parse_delete_statement(node, source, recovered)?,
+            ),
             "drop_aggregate" => CassandraStatement::DropAggregate(
-                CassandraParser::parse_standard_drop(node, source),
+                CassandraParser::parse_standard_drop(node, source)?,
+            ),
+            "drop_function" => CassandraStatement::DropFunction(
+                CassandraParser::parse_standard_drop(node, source)?,
             ),
-            "drop_function" => {
-                CassandraStatement::DropFunction(CassandraParser::parse_standard_drop(node, source))
-            }
             "drop_index" => {
-                CassandraStatement::DropIndex(CassandraParser::parse_standard_drop(node, source))
-            }
-            "drop_keyspace" => {
-                CassandraStatement::DropKeyspace(CassandraParser::parse_standard_drop(node, source))
+                CassandraStatement::DropIndex(CassandraParser::parse_standard_drop(node, source)?)
             }
+            "drop_keyspace" => CassandraStatement::DropKeyspace(
+                CassandraParser::parse_standard_drop(node, source)?,
+            ),
             "drop_materialized_view" => CassandraStatement::DropMaterializedView(
-                CassandraParser::parse_standard_drop(node, source),
+                CassandraParser::parse_standard_drop(node, source)?,
             ),
             "drop_role" => {
-                CassandraStatement::DropRole(CassandraParser::parse_standard_drop(node, source))
+                CassandraStatement::DropRole(CassandraParser::parse_standard_drop(node, source)?)
             }
             "drop_table" => {
-                CassandraStatement::DropTable(CassandraParser::parse_standard_drop(node, source))
+                CassandraStatement::DropTable(CassandraParser::parse_standard_drop(node, source)?)
             }
             "drop_trigger" => {
-                CassandraStatement::DropTrigger(CassandraParser::parse_drop_trigger(node, source))
+                CassandraStatement::DropTrigger(CassandraParser::parse_drop_trigger(node, source)?)
             }
             "drop_type" => {
-                CassandraStatement::DropType(CassandraParser::parse_standard_drop(node, source))
+                CassandraStatement::DropType(CassandraParser::parse_standard_drop(node, source)?)
             }
             "drop_user" => {
-                CassandraStatement::DropUser(CassandraParser::parse_standard_drop(node, source))
+                CassandraStatement::DropUser(CassandraParser::parse_standard_drop(node, source)?)
             }
-            "grant" => CassandraStatement::Grant(CassandraParser::parse_privilege(node, source)),
+            "grant" => CassandraStatement::Grant(CassandraParser::parse_privilege(
+                node, source, recovered,
+            )?),
             "insert_statement" => {
-                CassandraStatement::Insert(CassandraParser::parse_insert(node, source))
-            }
-            "list_permissions" => {
-                CassandraStatement::ListPermissions(CassandraParser::parse_privilege(node, source))
+                CassandraStatement::Insert(CassandraParser::parse_insert(node, source, recovered)?)
             }
+            "list_permissions" => CassandraStatement::ListPermissions(
+                CassandraParser::parse_privilege(node, source, recovered)?,
+            ),
             "list_roles" => {
-                CassandraStatement::ListRoles(CassandraParser::parse_list_role_data(node, source))
+                CassandraStatement::ListRoles(CassandraParser::parse_list_role_data(node, source)?)
             }
-            "revoke" => CassandraStatement::Revoke(CassandraParser::parse_privilege(node, source)),
+            "revoke" => CassandraStatement::Revoke(CassandraParser::parse_privilege(
+                node, source, recovered,
+            )?),
             "select_statement" => {
-                CassandraStatement::Select(CassandraParser::parse_select(node, source))
+                CassandraStatement::Select(CassandraParser::parse_select(node, source, recovered)?)
             }
             "truncate" => {
-                CassandraStatement::Truncate(CassandraParser::parse_truncate(node, source))
+                CassandraStatement::Truncate(CassandraParser::parse_truncate(node, source)?)
             }
-            "update" => CassandraStatement::Update(CassandraParser::parse_update(node, source)),
-            "use" => CassandraStatement::Use(CassandraParser::parse_use(node, source)),
+            "update" => {
+                CassandraStatement::Update(CassandraParser::parse_update(node, source, recovered)?)
+            }
+            "use" => CassandraStatement::Use(CassandraParser::parse_use(node, source)?),
+            "ERROR" => CassandraParser::parse_unparsed_statement(node, source, recovered)
+                .unwrap_or_else(|| CassandraStatement::Unknown(source.to_string())),
             _ => CassandraStatement::Unknown(source.to_string()),
-        }
+        })
     }
 
     pub fn get_keyspace<'a>(&'a self, default: &'a Identifier) -> &'a Identifier {
@@ -204,6 +352,7 @@ impl CassandraStatement {
             CassandraStatement::AlterType(x) => x.name.keyspace.as_ref().unwrap_or(default),
             CassandraStatement::AlterUser(_) => default,
             CassandraStatement::ApplyBatch => default,
+            CassandraStatement::Batch { .. } => default,
             CassandraStatement::CreateAggregate(x) => x.name.keyspace.as_ref().unwrap_or(default),
             CassandraStatement::CreateFunction(x) => x.name.keyspace.as_ref().unwrap_or(default),
             CassandraStatement::CreateIndex(x) => x.table.keyspace.as_ref().unwrap_or(default),
@@ -251,6 +400,7 @@ impl CassandraStatement {
             CassandraStatement::AlterType(_) => "ALTER TYPE",
             CassandraStatement::AlterUser(_) => "ALTER USER",
             CassandraStatement::ApplyBatch => "APPLY BATCH",
+            CassandraStatement::Batch { .. } => "BATCH",
             CassandraStatement::CreateAggregate(_) => "CREATE AGGREGATE",
             CassandraStatement::CreateFunction(_) => "CREATE FUNCTION",
             CassandraStatement::CreateIndex(_) => "CREATE INDEX",
@@ -304,6 +454,52 @@ impl CassandraStatement {
     }
 }
 
+impl MoveSpans for CassandraStatement {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            CassandraStatement::AlterMaterializedView(x) => x.move_spans(offset),
+            CassandraStatement::AlterTable(x) => x.move_spans(offset),
+            CassandraStatement::AlterType(x) => x.move_spans(offset),
+            CassandraStatement::Batch { statements, .. } => statements.move_spans(offset),
+            CassandraStatement::CreateAggregate(x) => x.move_spans(offset),
+            CassandraStatement::CreateFunction(x) => x.move_spans(offset),
+            CassandraStatement::CreateIndex(x) => x.move_spans(offset),
+            CassandraStatement::CreateMaterializedView(x) => x.move_spans(offset),
+            CassandraStatement::CreateTable(x) => x.move_spans(offset),
+            CassandraStatement::CreateTrigger(x) => x.move_spans(offset),
+            CassandraStatement::CreateType(x) => x.move_spans(offset),
+            CassandraStatement::Delete(x) => x.move_spans(offset),
+            CassandraStatement::DropAggregate(x)
+            | CassandraStatement::DropFunction(x)
+            | CassandraStatement::DropIndex(x)
+            | CassandraStatement::DropKeyspace(x)
+            | CassandraStatement::DropMaterializedView(x)
+            | CassandraStatement::DropRole(x)
+            | CassandraStatement::DropTable(x)
+            | CassandraStatement::DropType(x)
+            | CassandraStatement::DropUser(x) => x.move_spans(offset),
+            CassandraStatement::DropTrigger(x) => x.move_spans(offset),
+            CassandraStatement::Grant(x)
+            | CassandraStatement::ListPermissions(x)
+            | CassandraStatement::Revoke(x) => x.move_spans(offset),
+            CassandraStatement::Insert(x) => x.move_spans(offset),
+            CassandraStatement::Select(x) => x.move_spans(offset),
+            CassandraStatement::Truncate(x) => x.move_spans(offset),
+            CassandraStatement::Update(x) => x.move_spans(offset),
+            CassandraStatement::AlterKeyspace(_)
+            | CassandraStatement::AlterRole(_)
+            | CassandraStatement::AlterUser(_)
+            | CassandraStatement::ApplyBatch
+            | CassandraStatement::CreateKeyspace(_)
+            | CassandraStatement::CreateRole(_)
+            | CassandraStatement::CreateUser(_)
+            | CassandraStatement::ListRoles(_)
+            | CassandraStatement::Use(_)
+            | CassandraStatement::Unknown(_) => {}
+        }
+    }
+}
+
 impl Display for CassandraStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -315,13 +511,34 @@ impl Display for CassandraStatement {
             CassandraStatement::AlterTable(table_data) => {
                 write!(
                     f,
-                    "ALTER TABLE {} {}",
-                    table_data.name, table_data.operation
+                    "ALTER TABLE {}{} {}",
+                    if table_data.if_exists {
+                        "IF EXISTS "
+                    } else {
+                        ""
+                    },
+                    table_data.name,
+                    table_data.operation
                 )
             }
             CassandraStatement::AlterType(alter_type_data) => write!(f, "{}", alter_type_data),
             CassandraStatement::AlterUser(user_data) => write!(f, "ALTER {}", user_data),
             CassandraStatement::ApplyBatch => write!(f, "APPLY BATCH"),
+            CassandraStatement::Batch {
+                ty,
+                using,
+                statements,
+            } => {
+                let begin = BeginBatch {
+                    ty: ty.clone(),
+                    timestamp: using.timestamp.clone(),
+                };
+                write!(f, "{}", begin)?;
+                for statement in statements {
+                    write!(f, "{}; ", statement)?;
+                }
+                write!(f, "APPLY BATCH")
+            }
             CassandraStatement::CreateAggregate(aggregate_data) => write!(f, "{}", aggregate_data),
             CassandraStatement::CreateFunction(function_data) => write!(f, "{}", function_data),
             CassandraStatement::CreateIndex(index_data) => write!(f, "{}", index_data),
@@ -400,25 +617,45 @@ impl Display for CassandraStatement {
 #[cfg(test)]
 mod tests {
     use crate::{
+        alter_table::AlterTableOperation,
+        begin_batch::BatchType,
         cassandra_ast::CassandraAST,
         cassandra_statement::CassandraStatement,
-        common::{FQName, Identifier},
+        common::{
+            Bindable, DataType, DataTypeName, FQName, FunctionCall, Identifier, Literal, Operand,
+            PrivilegeType, RelationElement, RelationOperator, RelationTarget, Resource, Span,
+            WhereExpression,
+        },
         common_drop::CommonDrop,
-        select::{Select, SelectElement},
+        cql_parser::CqlParser,
+        create_index::IndexColumnType,
+        dialect::Dialect,
+        insert::InsertValues,
+        parse_error::{ParseErrorKind, Position},
+        select::{ArithmeticOperator, Named, NamedSelector, Select, SelectElement, Selector},
     };
 
-    // only tests single results
+    // only tests single results.  A statement that starts a batch is only missing the
+    // `APPLY BATCH`.
     fn test_parsing(expected: &[&str], statements: &[&str]) {
         for (statement, expected) in statements.iter().zip(expected) {
             let ast = CassandraAST::new(statement);
-            assert!(
-                !ast.has_error(),
+            let upper = statement.to_uppercase();
+            let unapplied = upper.starts_with("BEGIN ") && !upper.contains("APPLY BATCH");
+            let errors: Vec<&ParseErrorKind> = ast.errors.iter().map(|x| &x.kind).collect();
+            let expected_errors = match unapplied {
+                true => vec![&ParseErrorKind::Missing("APPLY BATCH")],
+                false => vec![],
+            };
+            assert_eq!(
+                expected_errors,
+                errors,
                 "AST has error\n{}\n{} ",
                 statement,
-                ast.tree.root_node().to_sexp()
+                ast.tree.as_ref().unwrap().root_node().to_sexp()
             );
             let stmt = &ast.statements[0];
-            assert!(!stmt.has_error);
+            assert_eq!(unapplied, stmt.has_error);
             let stmt_str = stmt.statement.to_string();
             assert_eq!(*expected, stmt_str);
         }
@@ -430,7 +667,7 @@ mod tests {
             !ast.has_error(),
             "AST has error\n{}\n{} ",
             query,
-            ast.tree.root_node().to_sexp()
+            ast.tree.as_ref().unwrap().root_node().to_sexp()
         );
         assert_eq!(&ast.statements[0].statement, expected)
     }
@@ -445,12 +682,18 @@ mod tests {
                 table_name: FQName {
                     keyspace: Some(Identifier::Unquoted("foo".into())),
                     name: Identifier::Unquoted("table".into()),
+                    span: None,
                 },
                 columns: vec![SelectElement::Star],
-                where_clause: vec![],
-                order: None,
+                where_clause: None,
+                group_by: vec![],
+                order: vec![],
+                per_partition_limit: None,
                 limit: None,
                 filtering: false,
+                bypass_cache: false,
+                timeout: None,
+                span: None,
             }),
         );
         assert_ast(
@@ -459,6 +702,7 @@ mod tests {
                 name: FQName {
                     keyspace: Some(Identifier::Unquoted("keyspace".into())),
                     name: Identifier::Unquoted("table".into()),
+                    span: None,
                 },
                 if_exists: false,
             }),
@@ -1082,6 +1326,299 @@ mod tests {
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_select_group_by() {
+        // the grammar does not accept GROUP BY or PER PARTITION LIMIT, they are read from the
+        // errors within the select statement.
+        let query = "SELECT a, count(*) FROM t WHERE a = 1 GROUP BY a, b PER PARTITION LIMIT 2 LIMIT 3 ALLOW FILTERING";
+        let ast = CassandraAST::new(query);
+        assert_eq!(1, ast.statements.len());
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        if let CassandraStatement::Select(select) = &ast.statements[0].statement {
+            assert_eq!(
+                vec![
+                    Identifier::Unquoted("a".into()),
+                    Identifier::Unquoted("b".into())
+                ],
+                select.group_by
+            );
+            assert_eq!(Some(Bindable::Literal(2)), select.per_partition_limit);
+            assert_eq!(Some(Bindable::Literal(3)), select.limit);
+        } else {
+            panic!("not a select");
+        }
+        assert!(ast.errors.is_empty());
+        assert!(!ast.has_error());
+        for query in [
+            "SELECT a FROM t GROUP BY a",
+            "SELECT a FROM t GROUP BY a ORDER BY a ASC",
+            "SELECT a FROM t PER PARTITION LIMIT 2",
+            "SELECT a FROM t PER PARTITION LIMIT 2 ALLOW FILTERING",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            // the clauses were read so they are not reported.
+            assert!(ast.errors.is_empty(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+        }
+        // a clause that could not be read is still reported.
+        let ast = CassandraAST::new("SELECT a FROM t GROUP BY 3");
+        assert!(ast.has_error());
+        assert!(ast.statements.last().unwrap().has_error);
+    }
+
+    #[test]
+    fn test_using_bind_markers() {
+        // the grammar does not accept bind markers in USING, they are read from the errors
+        // within the statement.
+        for query in [
+            "UPDATE t USING TTL ? AND TIMESTAMP :ts SET a = 1 WHERE b = 2",
+            "DELETE FROM t USING TIMESTAMP ? WHERE a = 1",
+            "INSERT INTO t (a) VALUES (1) USING TTL ?",
+            "INSERT INTO t (a) VALUES (1) USING TIMESTAMP :ts",
+            "INSERT INTO t (a) VALUES (1) IF NOT EXISTS USING TTL ? AND TIMESTAMP :ts",
+            "INSERT INTO t (a) VALUES (1) USING TTL ? AND TIMESTAMP 5",
+            "UPDATE t USING TTL ? AND TIMESTAMP 5 SET a = 1 WHERE b = 2",
+            "UPDATE t USING TTL 5 AND TIMESTAMP ? SET a = 1 WHERE b = 2",
+            "BEGIN BATCH INSERT INTO t (a) VALUES (1) USING TTL ?; UPDATE t SET a = 1 WHERE b = 1; APPLY BATCH",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            assert_eq!(0..query.len(), ast.statements[0].span().range());
+            assert!(!ast.has_error(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+            let borrowed = crate::borrowed::CassandraAST::try_new(query).unwrap();
+            assert_eq!(1, borrowed.statements.len(), "{}", query);
+            assert_eq!(
+                ast.statements[0].statement,
+                borrowed.statements[0].statement.clone().into_owned()
+            );
+            assert_eq!(1, crate::statement_summary::classify(query).unwrap().len());
+        }
+        let ast = CassandraAST::new("INSERT INTO t (a) VALUES (1) USING TTL ? AND TIMESTAMP :ts");
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => {
+                let using = insert.using_ttl.as_ref().unwrap();
+                assert_eq!(Some(Bindable::Param("?".to_string())), using.ttl);
+                assert_eq!(Some(Bindable::Param(":ts".to_string())), using.timestamp);
+                assert_eq!(0..58, insert.span.unwrap().range());
+            }
+            statement => panic!("not an insert: {}", statement),
+        }
+        // the grammar ends the clause before a bind marker that follows a literal.
+        let ast = CassandraAST::new("INSERT INTO t (a) VALUES (1) USING TIMESTAMP 5 AND TTL ?");
+        assert_eq!(1, ast.statements.len());
+        assert!(!ast.has_error());
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => {
+                let using = insert.using_ttl.as_ref().unwrap();
+                assert_eq!(Some(Bindable::Param("?".to_string())), using.ttl);
+                assert_eq!(Some(Bindable::Literal(5)), using.timestamp);
+            }
+            statement => panic!("not an insert: {}", statement),
+        }
+        // a clause that can not be read is still an error.
+        for query in [
+            "INSERT INTO t (a) VALUES (1) USING TTL ? zq",
+            "INSERT INTO t (a) VALUES (1) USING TIMESTAMP ? TIMESTAMP ?",
+            "UPDATE t USING TIMESTAMP ? TIMESTAMP ? SET a = 1 WHERE b = 2",
+            "UPDATE t USING TTL ? AND TTL ? SET a = 1 WHERE b = 2",
+            "UPDATE t USING TIMESTAMP : SET a = 1 WHERE b = 2",
+            "DELETE FROM t USING TIMESTAMP : WHERE a = 1",
+            "UPDATE t USING AND TTL ? SET a = 1 WHERE b = 2",
+            "DELETE FROM t USING TTL ? WHERE a = 1",
+            "BEGIN BATCH USING TIMESTAMP ? TTL ? INSERT INTO t (a) VALUES (1) APPLY BATCH",
+            "BEGIN BATCH USING TIMESTAMP ? AND TTL ? INSERT INTO t (a) VALUES (1) APPLY BATCH",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert!(ast.has_error(), "{}", query);
+            assert!(ast.statements.last().unwrap().has_error, "{}", query);
+        }
+        let ast = CassandraAST::new(
+            "BEGIN BATCH USING TIMESTAMP :ts INSERT INTO t (a) VALUES (1) APPLY BATCH",
+        );
+        match &ast.statements[0].statement {
+            CassandraStatement::Batch { using, .. } => {
+                assert_eq!(Some(Bindable::Param(":ts".to_string())), using.timestamp);
+            }
+            statement => panic!("not a batch: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_batch() {
+        let stmts = [
+            "BEGIN BATCH INSERT INTO t (a) VALUES (1); UPDATE t SET a = 2 WHERE b = 1; DELETE FROM t WHERE b = 2; APPLY BATCH;",
+            "begin unlogged batch using timestamp 5 insert into ks.t (a) values (1) apply batch",
+        ];
+        let expected = [
+            "BEGIN BATCH INSERT INTO t (a) VALUES (1); UPDATE t SET a = 2 WHERE b = 1; DELETE FROM t WHERE b = 2; APPLY BATCH",
+            "BEGIN UNLOGGED BATCH USING TIMESTAMP 5 INSERT INTO ks.t (a) VALUES (1); APPLY BATCH",
+        ];
+        test_parsing(&expected, &stmts);
+        for statement in expected {
+            test_parsing(&[statement], &[statement]);
+        }
+
+        let ast = CassandraAST::new(stmts[1]);
+        assert_eq!(1, ast.statements.len());
+        match &ast.statements[0].statement {
+            CassandraStatement::Batch {
+                ty,
+                using,
+                statements,
+            } => {
+                assert_eq!(&BatchType::Unlogged, ty);
+                assert_eq!(Some(Bindable::Literal(5)), using.timestamp);
+                match &statements[0] {
+                    CassandraStatement::Insert(insert) => assert!(insert.begin_batch.is_none()),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        // the spans of the statements do not include the `BEGIN BATCH` clause.
+        for (query, expected) in [
+            (
+                stmts[0],
+                vec![
+                    "INSERT INTO t (a) VALUES (1)",
+                    "UPDATE t SET a = 2 WHERE b = 1",
+                    "DELETE FROM t WHERE b = 2",
+                ],
+            ),
+            (
+                "BEGIN BATCH USING TIMESTAMP 5 INSERT INTO t (a) VALUES (1) USING TTL ?; APPLY BATCH",
+                vec!["INSERT INTO t (a) VALUES (1) USING TTL ?"],
+            ),
+            (
+                "BEGIN COUNTER BATCH UPDATE t SET a = a + 1 WHERE b = 1 APPLY BATCH",
+                vec!["UPDATE t SET a = a + 1 WHERE b = 1"],
+            ),
+        ] {
+            let ast = CassandraAST::new(query);
+            assert!(!ast.has_error(), "{}", query);
+            match &ast.statements[0].statement {
+                CassandraStatement::Batch { statements, .. } => assert_eq!(
+                    expected,
+                    statements
+                        .iter()
+                        .map(|x| {
+                            let span = match x {
+                                CassandraStatement::Insert(insert) => insert.span,
+                                CassandraStatement::Update(update) => update.span,
+                                CassandraStatement::Delete(delete) => delete.span,
+                                _ => unreachable!(),
+                            };
+                            &query[span.unwrap().range()]
+                        })
+                        .collect::<Vec<&str>>(),
+                    "{}",
+                    query
+                ),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_only_dml() {
+        let query =
+            "USE ks; BEGIN BATCH INSERT INTO t (a) VALUES (1); SELECT * FROM t; APPLY BATCH";
+        let ast = CassandraAST::new(query);
+        assert_eq!(2, ast.statements.len());
+        assert!(ast.has_error());
+        assert!(ast.statements[1].has_error);
+        assert_eq!(
+            ParseErrorKind::Unexpected("select_statement"),
+            ast.errors[0].kind
+        );
+        assert_eq!("SELECT * FROM t", ast.errors[0].text);
+
+        // the grammar does not accept `BEGIN BATCH` before the other statements, the statement
+        // is reported the same way.
+        for (query, kind, text) in [
+            (
+                "BEGIN BATCH SELECT * FROM t; APPLY BATCH",
+                "select_statement",
+                "SELECT * FROM t",
+            ),
+            (
+                "BEGIN UNLOGGED BATCH USING TIMESTAMP 5 TRUNCATE t; DELETE FROM t WHERE a = 1; APPLY BATCH",
+                "truncate",
+                "TRUNCATE t",
+            ),
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert!(ast.statements[0].has_error, "{}", query);
+            assert_eq!(1, ast.errors.len(), "{}", query);
+            assert_eq!(ParseErrorKind::Unexpected(kind), ast.errors[0].kind);
+            assert_eq!(text, ast.errors[0].text);
+            assert!(matches!(
+                ast.statements[0].statement,
+                CassandraStatement::Batch { .. }
+            ));
+        }
+        let ast = CassandraAST::new(
+            "BEGIN UNLOGGED BATCH USING TIMESTAMP 5 TRUNCATE t; DELETE FROM t WHERE a = 1; APPLY BATCH",
+        );
+        match &ast.statements[0].statement {
+            CassandraStatement::Batch {
+                ty,
+                using,
+                statements,
+            } => {
+                assert_eq!(&BatchType::Unlogged, ty);
+                assert_eq!(Some(Bindable::Literal(5)), using.timestamp);
+                assert_eq!(2, statements.len());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_batch_not_applied() {
+        let ast = CassandraAST::new("BEGIN BATCH INSERT INTO t (a) VALUES (1); USE ks");
+        assert!(ast.has_error());
+        assert_eq!(1, ast.errors.len());
+        let error = &ast.errors[0];
+        assert_eq!(ParseErrorKind::Missing("APPLY BATCH"), error.kind);
+        assert_eq!("", error.text);
+        assert_eq!(40, error.start_byte);
+        assert_eq!(
+            Position {
+                line: 1,
+                column: 41
+            },
+            error.start
+        );
+        assert_eq!(2, ast.statements.len());
+        assert!(ast.statements[0].has_error);
+        assert!(!ast.statements[1].has_error);
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => assert!(insert.begin_batch.is_some()),
+            _ => unreachable!(),
+        }
+
+        // the error follows the last statement that may be part of the batch.
+        let query = "BEGIN BATCH INSERT INTO t (a) VALUES (1);\nDELETE FROM t WHERE a = 1;";
+        let ast = CassandraAST::new(query);
+        assert_eq!(1, ast.errors.len());
+        assert_eq!(ParseErrorKind::Missing("APPLY BATCH"), ast.errors[0].kind);
+        assert_eq!(query.len() - 1, ast.errors[0].start_byte);
+        assert_eq!(
+            Position {
+                line: 2,
+                column: 26
+            },
+            ast.errors[0].start
+        );
+        assert!(ast.statements[1].has_error);
+    }
+
     #[test]
     fn test_apply_batch() {
         let stmts = ["Apply Batch;"];
@@ -1110,6 +1647,749 @@ mod tests {
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_create_custom_index() {
+        // the grammar does not accept these clauses, they are read from the errors within and
+        // directly after the statement.  Storage-attached indexes are Cassandra 5.0 syntax.
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::Cassandra5_0);
+        let query = "CREATE CUSTOM INDEX IF NOT EXISTS i ON ks.t (VALUES(m)) USING 'StorageAttachedIndex' WITH OPTIONS = {'case_sensitive': 'false', 'normalize' : 'true'}";
+        let ast = parser.parse(query).unwrap();
+        assert_eq!(1, ast.statements.len());
+        assert!(!ast.has_error());
+        assert!(!ast.statements[0].has_error);
+        assert_eq!(query.len(), ast.statements[0].span().end_byte);
+        assert_eq!(1, crate::statement_summary::classify(query).unwrap().len());
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateIndex(index) => {
+                assert!(index.custom);
+                assert!(index.if_not_exists);
+                assert_eq!(
+                    IndexColumnType::Values(Identifier::Unquoted("m".to_string())),
+                    index.column
+                );
+                assert_eq!(Some("StorageAttachedIndex".to_string()), index.using);
+                assert_eq!(
+                    vec![
+                        ("case_sensitive".to_string(), "false".to_string()),
+                        ("normalize".to_string(), "true".to_string()),
+                    ],
+                    index.options
+                );
+                assert_eq!(
+                    "CREATE CUSTOM INDEX IF NOT EXISTS i ON ks.t( VALUES( m ) ) USING 'StorageAttachedIndex' WITH OPTIONS = {'case_sensitive':'false', 'normalize':'true'}",
+                    index.to_string()
+                );
+            }
+            statement => panic!("not a create index: {}", statement),
+        }
+        let ast = parser.parse("CREATE INDEX i ON t (v) USING 'sai'").unwrap();
+        assert!(!ast.has_error());
+        assert_eq!(
+            "CREATE INDEX i ON t( v ) USING 'sai'",
+            ast.statements[0].statement.to_string()
+        );
+        assert!(CassandraAST::new(query).statements[0].has_error);
+    }
+
+    #[test]
+    fn test_data_masking() {
+        // the grammar does not accept masking, the statements are read from the errors.
+        let query = "CREATE TABLE t (a int, b text MASKED WITH mask_inner(1, null), c int MASKED WITH mask_default(), PRIMARY KEY (a, c))";
+        let ast = CassandraAST::new(query);
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(table) => {
+                assert_eq!(None, table.columns[0].mask);
+                assert_eq!(
+                    Some(FunctionCall::new(
+                        "mask_inner",
+                        vec![
+                            Operand::Literal(Literal::Integer("1".to_string())),
+                            Operand::Null
+                        ]
+                    )),
+                    table.columns[1].mask
+                );
+                assert_eq!(
+                    Some(FunctionCall::new("mask_default", vec![])),
+                    table.columns[2].mask
+                );
+                assert_eq!(
+                    "c int MASKED WITH mask_default()",
+                    &query[table.columns[2].span.unwrap().range()]
+                );
+            }
+            statement => panic!("not a create table: {}", statement),
+        }
+        assert_eq!(
+            "CREATE TABLE t (a INT, b TEXT MASKED WITH mask_inner(1, NULL), c INT MASKED WITH mask_default(), PRIMARY KEY (a, c))",
+            ast.statements[0].statement.to_string()
+        );
+        let ast =
+            CassandraAST::new("CREATE TABLE t (a int MASKED WITH mask_null() PRIMARY KEY, b text)");
+        assert_eq!(
+            "CREATE TABLE t (a INT MASKED WITH mask_null() PRIMARY KEY, b TEXT)",
+            ast.statements[0].statement.to_string()
+        );
+
+        let stmts = [
+            "ALTER TABLE ks.t ALTER b MASKED WITH mask_replace('redacted')",
+            "ALTER TABLE t ALTER b DROP MASKED",
+            "GRANT UNMASK ON TABLE ks.t TO auditor",
+            "REVOKE UNMASK ON KEYSPACE ks FROM auditor",
+            "GRANT SELECT_MASKED ON TABLE ks.t TO auditor",
+        ];
+        for query in stmts {
+            let ast = CassandraAST::new(query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+        }
+        match &CassandraAST::new(stmts[0]).statements[0].statement {
+            CassandraStatement::AlterTable(alter) => assert_eq!(
+                AlterTableOperation::Mask((
+                    Identifier::Unquoted("b".to_string()),
+                    FunctionCall::new("mask_replace", vec![Operand::from("redacted")])
+                )),
+                alter.operation
+            ),
+            statement => panic!("not an alter table: {}", statement),
+        }
+        match &CassandraAST::new(stmts[2]).statements[0].statement {
+            CassandraStatement::Grant(grant) => {
+                assert_eq!(PrivilegeType::Unmask, grant.privilege);
+                assert_eq!(
+                    Some(Resource::Table(FQName::new("ks", "t"))),
+                    grant.resource
+                );
+            }
+            statement => panic!("not a grant: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let query = "SELECT token(a, b), toTimestamp(now()) AS ts, ks.f(1, 'x'), count(*) FROM t WHERE token(a) > token(1)";
+        let ast = CassandraAST::new(query);
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        let token = |args: Vec<Operand>| Operand::Func(FunctionCall::new("token", args));
+        let a = Operand::Column(Identifier::parse("a"));
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                match &select.columns[0] {
+                    SelectElement::Function(named) => {
+                        assert_eq!(
+                            token(vec![a.clone(), Operand::Column(Identifier::parse("b"))]),
+                            Operand::Func(named.function.clone())
+                        );
+                        assert_eq!("token(a, b)", &query[named.span.unwrap().range()]);
+                    }
+                    element => panic!("not a function: {}", element),
+                }
+                match &select.columns[2] {
+                    SelectElement::Function(named) => {
+                        assert_eq!(FQName::new("ks", "f"), named.function.name);
+                        assert_eq!("ks.f", &query[named.function.name.span.unwrap().range()]);
+                    }
+                    element => panic!("not a function: {}", element),
+                }
+                let relations = select.where_relations().unwrap();
+                assert_eq!(
+                    RelationTarget::Token(vec![Identifier::parse("a")]),
+                    relations[0].obj
+                );
+                assert_eq!(
+                    token(vec![Operand::Literal(Literal::Integer("1".to_string()))]),
+                    relations[0].value
+                );
+            }
+            statement => panic!("not a select: {}", statement),
+        }
+
+        // the grammar does not accept function calls as values, they are read from the errors.
+        for query in [
+            "INSERT INTO t (a, b, c) VALUES (1, now(), f(2, c, g(?))) USING TTL 5",
+            "UPDATE t SET a = 1, b = toTimestamp(now()), c = c + {1, 2}, m['k'] = :p WHERE d = 1",
+            // a function call in the first assignment makes the whole statement an error.
+            "UPDATE t SET a = fn(b) WHERE c = 1",
+            "UPDATE ks.t USING TTL ? SET a = ks.f(b, 2), c = c + [1] WHERE d = 1 AND e IN (1, 2) IF f = 3",
+            "UPDATE t SET a = now() WHERE c = 1 IF EXISTS",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            assert!(!ast.has_error(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+            let borrowed = crate::borrowed::CassandraAST::try_new(query).unwrap();
+            assert_eq!(
+                ast.statements[0].statement,
+                borrowed.statements[0].statement.clone().into_owned()
+            );
+            let summary = &crate::statement_summary::classify(query).unwrap()[0];
+            assert_eq!(ast.statements[0].statement.short_name(), summary.kind);
+            // the summary is read from the tree, which holds the syntax errors.
+            assert!(summary.has_error);
+        }
+        let ast = CassandraAST::new("UPDATE t SET a = fn(b) WHERE c = 1");
+        match &ast.statements[0].statement {
+            CassandraStatement::Update(update) => {
+                assert_eq!(
+                    Operand::Func(FunctionCall::new(
+                        "fn",
+                        vec![Operand::Column(Identifier::parse("b"))]
+                    )),
+                    update.assignments[0].value
+                );
+                assert_eq!(0..34, update.span.unwrap().range());
+            }
+            statement => panic!("not an update: {}", statement),
+        }
+        // text the parser can not read is still an error.
+        let ast = CassandraAST::new("UPDATE t SET a = fn(b) WHERE c = 1 zq");
+        assert!(ast.has_error());
+        assert!(matches!(
+            ast.statements[0].statement,
+            CassandraStatement::Unknown(_)
+        ));
+        let ast = CassandraAST::new("INSERT INTO t (a, b) VALUES (1, now())");
+        assert!(!ast.has_error());
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => assert_eq!(
+                InsertValues::Values(vec![
+                    Operand::Literal(Literal::Integer("1".to_string())),
+                    Operand::Func(FunctionCall::new("now", vec![]))
+                ]),
+                insert.values
+            ),
+            statement => panic!("not an insert: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_duration_values() {
+        // the grammar does not accept ISO 8601 durations or arithmetic, they are read from the
+        // errors.
+        for query in [
+            "SELECT a FROM t WHERE ts > now() - 1d",
+            "SELECT a FROM t WHERE ts > now() - 1d * 2 AND b = (1 + 2) * 3 - 4",
+            "INSERT INTO t (a) VALUES (P1DT2H)",
+            "INSERT INTO t (a) VALUES (P0001-02-03T04:05:06)",
+            "INSERT INTO ks.t (a, b) VALUES (P1D, ?) IF NOT EXISTS USING TTL ?",
+            "UPDATE t SET a = P1DT2H WHERE b = 1",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            assert!(!ast.has_error(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+            let borrowed = crate::borrowed::CassandraAST::try_new(query).unwrap();
+            assert_eq!(
+                ast.statements[0].statement,
+                borrowed.statements[0].statement.clone().into_owned()
+            );
+            let summary = &crate::statement_summary::classify(query).unwrap()[0];
+            assert_eq!(ast.statements[0].statement.short_name(), summary.kind);
+        }
+
+        let duration = |text: &str| Operand::Literal(Literal::Duration(text.to_string()));
+        let ast = CassandraAST::new("SELECT a FROM t WHERE ts > now() - 1d");
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => assert_eq!(
+                Operand::Arithmetic(
+                    Box::new(Operand::Func(FunctionCall::new("now", vec![]))),
+                    ArithmeticOperator::Subtract,
+                    Box::new(duration("1d"))
+                ),
+                select.where_relations().unwrap()[0].value
+            ),
+            statement => panic!("not a select: {}", statement),
+        }
+        for (query, value) in [
+            ("INSERT INTO t (a) VALUES (P1DT2H)", "P1DT2H"),
+            (
+                "INSERT INTO t (a) VALUES (P0001-02-03T04:05:06)",
+                "P0001-02-03T04:05:06",
+            ),
+        ] {
+            match &CassandraAST::new(query).statements[0].statement {
+                CassandraStatement::Insert(insert) => {
+                    assert_eq!(InsertValues::Values(vec![duration(value)]), insert.values)
+                }
+                statement => panic!("not an insert: {}", statement),
+            }
+        }
+    }
+
+    #[test]
+    fn test_selectors() {
+        // the grammar only accepts columns and function calls, other selectors are read from the
+        // errors.
+        let query = "SELECT a, CAST(b AS text), c + d * 2 AS x, m['k'], s[1..3], u.field, WRITETIME(e), 'lit' FROM ks.t WHERE k = 1";
+        let ast = CassandraAST::new(query);
+        assert_eq!(
+            query.replace("text", "TEXT"),
+            ast.statements[0].statement.to_string()
+        );
+        let column = |name: &str| Box::new(Selector::Column(Identifier::parse(name)));
+        let term = |operand| Box::new(Selector::Term(operand));
+        let select = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select,
+            statement => panic!("not a select: {}", statement),
+        };
+        assert_eq!(FQName::new("ks", "t"), select.table_name);
+        assert_eq!(SelectElement::Column(Named::simple("a")), select.columns[0]);
+        let selectors: Vec<Selector> = select.columns[1..]
+            .iter()
+            .map(|element| match element {
+                SelectElement::Selector(named) => named.selector.clone(),
+                element => panic!("not a selector: {}", element),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                Selector::Cast(column("b"), DataType::Native(DataTypeName::Text)),
+                Selector::Arithmetic(
+                    column("c"),
+                    ArithmeticOperator::Add,
+                    Box::new(Selector::Arithmetic(
+                        column("d"),
+                        ArithmeticOperator::Multiply,
+                        term(Operand::from(&2))
+                    ))
+                ),
+                Selector::Element(column("m"), term(Operand::from("k"))),
+                Selector::Slice(
+                    column("s"),
+                    Some(term(Operand::from(&1))),
+                    Some(term(Operand::from(&3)))
+                ),
+                Selector::Field(column("u"), Identifier::parse("field")),
+                Selector::WriteTime(column("e")),
+                *term(Operand::from("lit")),
+            ],
+            selectors
+        );
+        match &select.columns[2] {
+            SelectElement::Selector(named) => {
+                assert_eq!(Some(Identifier::parse("x")), named.alias);
+                assert_eq!("c + d * 2 AS x", &query[named.span.unwrap().range()]);
+            }
+            element => panic!("not a selector: {}", element),
+        }
+
+        // WRITETIME and TTL are accepted by the grammar as function calls.
+        let ast = CassandraAST::new("SELECT TTL(a), count(*) FROM t");
+        assert!(!ast.has_error());
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                assert_eq!(
+                    SelectElement::Selector(NamedSelector::new(Selector::Ttl(column("a")), None)),
+                    select.columns[0]
+                );
+                assert!(matches!(select.columns[1], SelectElement::Function(_)));
+            }
+            statement => panic!("not a select: {}", statement),
+        }
+
+        // the selectors must be read up to the FROM clause.
+        for query in ["SELECT ? * FROM foo.table", "SELECT a, ? b FROM t"] {
+            let ast = CassandraAST::new(query);
+            assert!(ast.has_error(), "{}", query);
+            assert!(ast.statements[0].has_error, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_where_expressions() {
+        let relation = |column: &str, oper, value| {
+            WhereExpression::Relation(RelationElement {
+                obj: RelationTarget::Column(Identifier::parse(column)),
+                oper,
+                value,
+                span: None,
+                obj_span: None,
+                value_span: None,
+            })
+        };
+        // `OR` and the `NOT` operators are Cassandra 5.0 syntax.
+        let select = |query| {
+            let mut parser = CqlParser::new().unwrap();
+            parser.set_dialect(Dialect::Cassandra5_0);
+            let ast = parser.parse(query).unwrap();
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert!(!ast.has_error(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            match &ast.statements[0].statement {
+                CassandraStatement::Select(select) => select.clone(),
+                statement => panic!("not a select: {}", statement),
+            }
+        };
+
+        // the grammar ends the statement before the parenthesis, the rest of the statement is
+        // read from the error that follows it.
+        let parsed = select("SELECT * FROM t WHERE (a = 1 OR b = 2) AND c = 3 ALLOW FILTERING");
+        assert_eq!(
+            Some(WhereExpression::And(vec![
+                WhereExpression::Or(vec![
+                    relation("a", RelationOperator::Equal, Operand::from(&1)),
+                    relation("b", RelationOperator::Equal, Operand::from(&2)),
+                ]),
+                relation("c", RelationOperator::Equal, Operand::from(&3)),
+            ])),
+            parsed.where_clause
+        );
+        assert!(parsed.filtering);
+        assert_eq!(None, parsed.where_relations());
+
+        let parsed = select("SELECT * FROM t WHERE a = 1 OR b = 2 LIMIT 5");
+        assert_eq!(
+            Some(WhereExpression::Or(vec![
+                relation("a", RelationOperator::Equal, Operand::from(&1)),
+                relation("b", RelationOperator::Equal, Operand::from(&2)),
+            ])),
+            parsed.where_clause
+        );
+        assert_eq!(Some(Bindable::Literal(5)), parsed.limit);
+
+        // no dialect accepts `NOT` before a condition, and an `OR` must be followed by one.
+        for dialect in [Dialect::Cassandra3_11, Dialect::Cassandra5_0] {
+            let mut parser = CqlParser::new().unwrap();
+            parser.set_dialect(dialect);
+            for query in [
+                "SELECT * FROM t WHERE NOT a = 1",
+                "SELECT * FROM t WHERE NOT (a = 1 OR b = 2)",
+                "SELECT * FROM t WHERE a = 1 OR NOT b = 2",
+                "SELECT * FROM t WHERE a = 1 AND b = 2 OR",
+            ] {
+                let ast = parser.parse(query).unwrap();
+                assert!(ast.has_error(), "{}", query);
+                assert!(ast.statements.iter().any(|x| x.has_error), "{}", query);
+                assert!(
+                    ast.errors
+                        .iter()
+                        .all(|x| !matches!(x.kind, ParseErrorKind::Unsupported(_, _))),
+                    "{} {:?}",
+                    query,
+                    ast.errors
+                );
+            }
+        }
+
+        let parsed = select("SELECT * FROM t WHERE a NOT IN (1, 2) AND s NOT CONTAINS 1 AND m NOT CONTAINS KEY 'k' AND n LIKE 'x%' LIMIT ?");
+        let operators: Vec<RelationOperator> = parsed
+            .where_relations()
+            .unwrap()
+            .into_iter()
+            .map(|x| x.oper)
+            .collect();
+        assert_eq!(
+            vec![
+                RelationOperator::NotIn,
+                RelationOperator::NotContains,
+                RelationOperator::NotContainsKey,
+                RelationOperator::Like
+            ],
+            operators
+        );
+        assert_eq!(Some(Bindable::Param("?".to_string())), parsed.limit);
+
+        // a parenthesis may also start the columns of a multi-column relation.
+        let parsed = select("SELECT * FROM t WHERE (c1, c2) > (1, 2) AND (c3 = 1 OR c3 = 2)");
+        let relations = parsed.where_clause.as_ref().unwrap().relations();
+        assert_eq!(3, relations.len());
+        assert!(matches!(relations[0].obj, RelationTarget::Columns(_)));
+
+        // text that does not continue the where clause is still a statement of its own.
+        let ast = CassandraAST::new("SELECT * FROM t WHERE (a = 1 OR b) AND c = 3");
+        assert_eq!(2, ast.statements.len());
+        assert!(ast.statements[1].has_error);
+    }
+
+    #[test]
+    fn test_relation_targets() {
+        let query = "SELECT * FROM t WHERE token(pk1, pk2) > token(?, ?) AND (c1, c2) IN ((1, 2)) AND (c1, c2) >= (?, ?) AND a IN ? AND b IN (?) AND c = 1";
+        let ast = CassandraAST::new(query);
+        assert_eq!(1, ast.statements.len());
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        let relations = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select.where_relations().unwrap(),
+            statement => panic!("not a select: {}", statement),
+        };
+        let columns = |names: &[&str]| names.iter().map(|x| Identifier::parse(x)).collect();
+        assert_eq!(
+            vec![
+                RelationTarget::Token(columns(&["pk1", "pk2"])),
+                RelationTarget::Columns(columns(&["c1", "c2"])),
+                RelationTarget::Columns(columns(&["c1", "c2"])),
+                RelationTarget::Column(Identifier::parse("a")),
+                RelationTarget::Column(Identifier::parse("b")),
+                RelationTarget::Column(Identifier::parse("c")),
+            ],
+            relations.iter().map(|x| x.obj.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            &[Identifier::parse("pk1"), Identifier::parse("pk2")],
+            relations[0].obj.columns()
+        );
+
+        // a list of one tuple is not the tuple itself.
+        let tuple = Operand::Tuple(vec![Operand::from(&1), Operand::from(&2)]);
+        assert_eq!(Some(&[tuple][..]), relations[1].in_values());
+        // `IN ?` binds the whole list where `IN (?)` lists one bind marker.
+        let marker = Operand::Param("?".to_string());
+        assert_eq!(marker, relations[3].value);
+        assert_eq!(None, relations[3].in_values());
+        assert_eq!(Some(&[marker][..]), relations[4].in_values());
+        assert_eq!(None, relations[5].in_values());
+
+        // other functions are not token relations.
+        let ast = CassandraAST::new("SELECT * FROM t WHERE token(a, 1) > 0");
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => assert!(matches!(
+                select.where_relations().unwrap()[0].obj,
+                RelationTarget::Other(Operand::Func(_))
+            )),
+            statement => panic!("not a select: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_collection_operands() {
+        let query = "UPDATE t SET m = {'a': ?, 'b': 'c'}, l = [1, :p] WHERE k = 1";
+        let ast = CassandraAST::new(query);
+        assert!(!ast.has_error());
+        let constant = |txt: &str| Operand::Literal(Literal::parse(txt).unwrap());
+        match &ast.statements[0].statement {
+            CassandraStatement::Update(update) => {
+                assert_eq!(
+                    Operand::Map(vec![
+                        (constant("'a'"), Operand::Param("?".to_string())),
+                        (constant("'b'"), constant("'c'"))
+                    ]),
+                    update.assignments[0].value
+                );
+                assert_eq!(
+                    Operand::List(vec![constant("1"), Operand::Param(":p".to_string())]),
+                    update.assignments[1].value
+                );
+            }
+            statement => panic!("not an update: {}", statement),
+        }
+
+        // the grammar does not accept nested collections or user defined type literals, they
+        // are read from the errors.
+        let query = "INSERT INTO t (a, b) VALUES ({'k': [1, 2]}, {street: '1 Main', zip: {3}})";
+        let ast = CassandraAST::new(query);
+        assert_eq!(1, ast.statements.len());
+        assert_eq!(
+            "INSERT INTO t (a, b) VALUES ({'k':[1, 2]}, {street:'1 Main', zip:{3}})",
+            ast.statements[0].statement.to_string()
+        );
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => assert_eq!(
+                InsertValues::Values(vec![
+                    Operand::Map(vec![(
+                        constant("'k'"),
+                        Operand::List(vec![constant("1"), constant("2")])
+                    )]),
+                    Operand::Udt(vec![
+                        (Identifier::parse("street"), constant("'1 Main'")),
+                        (Identifier::parse("zip"), Operand::Set(vec![constant("3")]))
+                    ])
+                ]),
+                insert.values
+            ),
+            statement => panic!("not an insert: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_tokens() {
+        // the grammar only accepts ASCII identifiers, the statements hold errors but parsing
+        // them must not panic.
+        for query in [
+            "SELECT aé FROM t",
+            "SELECT \"aé\" FROM t",
+            "SELECT a FROM t WHERE zé NOT CONTAINS KEY 'k'",
+            "SELECT a FROM t LIMIT 3é",
+            "SELECT a FROM t WHERE a = 0xé",
+            "INSERT INTO t (é) VALUES (0é)",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert!(!ast.statements.is_empty(), "{}", query);
+            for statement in &ast.statements {
+                statement.statement.to_string();
+            }
+        }
+        let query = "SELECT a FROM t WHERE b = 'ü'";
+        let ast = CassandraAST::new(query);
+        assert!(!ast.has_error());
+        assert_eq!(query, ast.statements[0].statement.to_string());
+    }
+
+    #[test]
+    fn test_literals() {
+        let query = "INSERT INTO t (a, b, c, d, e, f, g) VALUES ('x', 42, 1.5, true, 123e4567-e89b-12d3-a456-426614174000, 0xCAFE, $$s$$)";
+        let ast = CassandraAST::new(query);
+        assert!(!ast.has_error());
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => assert_eq!(
+                InsertValues::Values(vec![
+                    Operand::Literal(Literal::String("'x'".to_string())),
+                    Operand::Literal(Literal::Integer("42".to_string())),
+                    Operand::Literal(Literal::Float("1.5".to_string())),
+                    Operand::Literal(Literal::Boolean("true".to_string())),
+                    Operand::Literal(Literal::Uuid(
+                        "123e4567-e89b-12d3-a456-426614174000".to_string()
+                    )),
+                    Operand::Literal(Literal::Hex("0xCAFE".to_string())),
+                    Operand::Literal(Literal::String("$$s$$".to_string())),
+                ]),
+                insert.values
+            ),
+            statement => panic!("not an insert: {}", statement),
+        }
+
+        // the grammar does not accept exponents, NaN, Infinity or durations, they are read from
+        // the text when the values hold errors.
+        let query = "INSERT INTO t (a, b, c, d) VALUES (3.14e2, NaN, -Infinity, 1h30m)";
+        let ast = CassandraAST::new(query);
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        match &ast.statements[0].statement {
+            CassandraStatement::Insert(insert) => assert_eq!(
+                InsertValues::Values(vec![
+                    Operand::Literal(Literal::Float("3.14e2".to_string())),
+                    Operand::Literal(Literal::NaN("NaN".to_string())),
+                    Operand::Literal(Literal::Infinity("-Infinity".to_string())),
+                    Operand::Literal(Literal::Duration("1h30m".to_string())),
+                ]),
+                insert.values
+            ),
+            statement => panic!("not an insert: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_nested_data_types() {
+        let query = "CREATE TABLE t (a map<text, frozen<list<int>>> PRIMARY KEY, b tuple<int, frozen<ks.udt>>, c ks.other)";
+        let ast = CassandraAST::new(query);
+        assert_eq!(1, ast.statements.len());
+        assert_eq!(
+            "CREATE TABLE t (a MAP<TEXT, FROZEN<LIST<INT>>> PRIMARY KEY, b TUPLE<INT, FROZEN<ks.udt>>, c ks.other)",
+            ast.statements[0].statement.to_string()
+        );
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(create) => {
+                assert_eq!(
+                    DataType::Map(
+                        Box::new(DataType::Native(DataTypeName::Text)),
+                        Box::new(DataType::Frozen(Box::new(DataType::List(Box::new(
+                            DataType::Native(DataTypeName::Int)
+                        )))))
+                    ),
+                    create.columns[0].data_type
+                );
+                assert_eq!(
+                    DataType::Tuple(vec![
+                        DataType::Native(DataTypeName::Int),
+                        DataType::Frozen(Box::new(DataType::Udt(FQName::new("ks", "udt"))))
+                    ]),
+                    create.columns[1].data_type
+                );
+                assert_eq!(
+                    DataType::Udt(FQName::new("ks", "other")),
+                    create.columns[2].data_type
+                );
+                assert!(create.columns[0].primary_key);
+                assert!(!create.columns[1].primary_key);
+                assert!(!create.columns[2].primary_key);
+            }
+            _ => panic!("not a create table"),
+        }
+
+        // the names of user defined types have spans at any depth.
+        let query = "CREATE TABLE t (a udt PRIMARY KEY, b list<frozen<ks.udt>>, c tuple<int, frozen<\"Ks\".u>>, d map<text, frozen<v>>)";
+        let ast = CassandraAST::new(query);
+        assert!(!ast.has_error());
+        let udt = |data_type: &DataType| -> Option<Span> {
+            let mut data_type = data_type;
+            loop {
+                data_type = match data_type {
+                    DataType::Udt(name) => return name.span,
+                    DataType::List(x) | DataType::Frozen(x) | DataType::Map(_, x) => x,
+                    DataType::Tuple(x) => &x[1],
+                    _ => return None,
+                }
+            }
+        };
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(create) => assert_eq!(
+                vec!["udt", "ks.udt", "\"Ks\".u", "v"],
+                create
+                    .columns
+                    .iter()
+                    .map(|x| &query[udt(&x.data_type).unwrap().range()])
+                    .collect::<Vec<&str>>()
+            ),
+            _ => panic!("not a create table"),
+        }
+
+        // a word before the data type is not read as the type.
+        for query in [
+            "CREATE TABLE t (a int PRIMARY KEY, b IN text)",
+            "CREATE TYPE type (col1 IN UUID)",
+            "ALTER TABLE ks.t ADD c IN int",
+            "CREATE TABLE t (a list<frozen<udt>> junk PRIMARY KEY)",
+        ] {
+            let ast = CassandraAST::new(query);
+            assert!(ast.has_error(), "{}", query);
+            assert!(ast.statements[0].has_error, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_vector() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::Cassandra5_0);
+        let ast = parser
+            .parse("CREATE TABLE t (a int PRIMARY KEY, v vector<float, 3>)")
+            .unwrap();
+        match &ast.statements[0].statement {
+            CassandraStatement::CreateTable(create) => assert_eq!(
+                DataType::Vector(Box::new(DataType::Native(DataTypeName::Float)), 3),
+                create.columns[1].data_type
+            ),
+            _ => panic!("not a create table"),
+        }
+        let query = "INSERT INTO t (a, v) VALUES (1, [0.1, 0.2, 0.3])";
+        let ast = parser.parse(query).unwrap();
+        assert!(!ast.has_error());
+        assert_eq!(query, ast.statements[0].statement.to_string());
+
+        // the grammar does not accept ANN OF, it is read from the error that follows the column.
+        let query = "SELECT a FROM t ORDER BY v ANN OF [0.1, 0.2, 0.3] LIMIT 3";
+        let ast = parser.parse(query).unwrap();
+        assert_eq!(query, ast.statements[0].statement.to_string());
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                assert_eq!(
+                    Some(Operand::List(vec![
+                        Operand::Literal(Literal::Float("0.1".to_string())),
+                        Operand::Literal(Literal::Float("0.2".to_string())),
+                        Operand::Literal(Literal::Float("0.3".to_string()))
+                    ])),
+                    select.order[0].ann_of
+                );
+                assert_eq!(
+                    "v ANN OF [0.1, 0.2, 0.3]",
+                    &query[select.order[0].span.unwrap().range()]
+                );
+            }
+            _ => panic!("not a select"),
+        }
+    }
+
     #[test]
     fn test_create_table() {
         let stmts = [
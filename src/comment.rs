@@ -0,0 +1,138 @@
+use crate::common::{eq_without_spans, move_spans, Span};
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+/// A comment in the query text.  The grammar does not accept comments so they are found before
+/// the query is parsed and replaced by spaces, see `Comment::blank`.
+#[derive(Debug, Clone)]
+pub struct Comment {
+    /// the text of the comment including the comment markers (`--`, `//` or `/* */`).
+    pub text: String,
+    /// the location of the comment in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(Comment { text; span });
+
+move_spans!(Comment { span; text });
+
+impl Comment {
+    /// create a comment from the text including the comment markers.
+    pub fn new(text: &str) -> Comment {
+        Comment {
+            text: text.to_string(),
+            span: None,
+        }
+    }
+
+    /// true if the comment extends to the end of the line (`--` and `//` comments).
+    pub fn is_line_comment(&self) -> bool {
+        !self.text.starts_with("/*")
+    }
+
+    /// find the comments in the text.  Comment markers within string constants, quoted
+    /// identifiers and `$$` blocks are ignored.
+    pub fn find(text: &str) -> Vec<Comment> {
+        let bytes = text.as_bytes();
+        let mut result = vec![];
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let next = bytes.get(pos + 1).copied();
+            pos = match (bytes[pos], next) {
+                (b'\'', _) => Comment::skip_past(text, pos + 1, "'"),
+                (b'"', _) => Comment::skip_past(text, pos + 1, "\""),
+                (b'$', Some(b'$')) => Comment::skip_past(text, pos + 2, "$$"),
+                (b'-', Some(b'-')) | (b'/', Some(b'/')) => {
+                    let end = text[pos..].find('\n').map_or(text.len(), |x| pos + x);
+                    result.push(Comment::at(text, pos, end));
+                    end
+                }
+                (b'/', Some(b'*')) => {
+                    let end = Comment::skip_past(text, pos + 2, "*/");
+                    result.push(Comment::at(text, pos, end));
+                    end
+                }
+                _ => pos + 1,
+            };
+        }
+        result
+    }
+
+    /// the text with the comments replaced by spaces.  Line breaks within block comments are
+    /// kept so the byte, line and column of every other character in the text are unchanged.
+    pub fn blank<'a>(text: &'a str, comments: &[Comment]) -> Cow<'a, str> {
+        if comments.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let mut result = String::with_capacity(text.len());
+        let mut pos = 0;
+        for span in comments.iter().filter_map(|x| x.span) {
+            result.push_str(&text[pos..span.start_byte]);
+            for byte in text[span.range()].bytes() {
+                // replacing every byte keeps multi-byte characters the same length.
+                result.push(if byte == b'\n' { '\n' } else { ' ' });
+            }
+            pos = span.end_byte;
+        }
+        result.push_str(&text[pos..]);
+        Cow::Owned(result)
+    }
+
+    /// the comment between the `start` and `end` byte offsets of the text.
+    fn at(text: &str, start: usize, end: usize) -> Comment {
+        Comment {
+            text: text[start..end].to_string(),
+            span: Some(Span::new(start, end)),
+        }
+    }
+
+    /// the offset after the first `terminator` at or after `pos`, or the end of the text if
+    /// there is none.  Quotes in strings and identifiers are escaped by doubling them, this
+    /// reads as two adjacent strings which does not change where the comments are.
+    fn skip_past(text: &str, pos: usize, terminator: &str) -> usize {
+        text.get(pos..)
+            .and_then(|rest| rest.find(terminator))
+            .map_or(text.len(), |x| pos + x + terminator.len())
+    }
+}
+
+impl Display for Comment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comment::Comment;
+
+    fn texts(query: &str) -> Vec<String> {
+        Comment::find(query).into_iter().map(|x| x.text).collect()
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            vec!["-- one", "// two", "/* three\n */"],
+            texts("-- one\nSELECT * FROM foo; // two\n/* three\n */ SELECT * FROM bar")
+        );
+        assert_eq!(vec!["/* open"], texts("SELECT * FROM foo /* open"));
+        assert!(Comment::find("SELECT * FROM foo").is_empty());
+    }
+
+    #[test]
+    fn test_find_ignores_quoted_markers() {
+        assert_eq!(
+            vec!["-- real"],
+            texts("SELECT 'it''s -- not' AS \"a--b\" FROM foo WHERE c = $$ /* no */ $$ -- real")
+        );
+    }
+
+    #[test]
+    fn test_blank() {
+        let query = "SELECT * /* é\n */ FROM foo -- x";
+        let blanked = Comment::blank(query, &Comment::find(query));
+        assert_eq!(query.len(), blanked.len());
+        assert_eq!("SELECT *      \n    FROM foo     ", blanked);
+    }
+}
@@ -1,19 +1,176 @@
+use crate::borrowed;
+use crate::comment::Comment;
+use crate::select::ArithmeticOperator;
 use bigdecimal::BigDecimal;
 use bytes::Bytes;
 use hex;
 use itertools::Itertools;
 use num_bigint::BigInt;
 use serde::Deserialize;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::net::IpAddr;
+use std::ops::Range;
 use uuid::Uuid;
 
+/// The location of a parsed element within the query text.
+/// The offsets are bytes within the text handed to the parser.
+///
+/// Elements hold an `Option<Span>` that is `None` when they were not produced by the parser,
+/// e.g. when built by hand.
+///
+/// `PartialEq`, `Ord` and `Hash` ignore the offsets: any two spans are equal, and the elements
+/// that hold spans are compared without them.  So a parsed element is equal to the same element
+/// built by hand, and the same element parsed at another place in the text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Span {
+    /// the first byte of the element.
+    pub start_byte: usize,
+    /// the byte after the last byte of the element.
+    pub end_byte: usize,
+}
+
+impl Span {
+    pub fn new(start_byte: usize, end_byte: usize) -> Span {
+        Span {
+            start_byte,
+            end_byte,
+        }
+    }
+
+    /// the byte range of the element.
+    pub fn range(&self) -> Range<usize> {
+        self.start_byte..self.end_byte
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Span {}
+
+impl PartialOrd for Span {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Span {
+    fn cmp(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+/// implements `PartialEq` for a struct that holds spans by comparing the fields listed before
+/// the `;`, so that a parsed element is equal to the same element built by hand without spans.
+/// The spans are listed after the `;`, every field must be listed so none is missed.
+/// `Ord` and `Hash` may follow to implement them the same way.
+macro_rules! eq_without_spans {
+    ($name:ident$(<$lt:lifetime>)? { $($field:ident),+ ; $($span:ident),+ }) => {
+        impl$(<$lt>)? PartialEq for $name$(<$lt>)? {
+            fn eq(&self, other: &Self) -> bool {
+                let $name { $($field,)+ $($span: _,)+ } = self;
+                $(*$field == other.$field)&&+
+            }
+        }
+    };
+    ($name:ident { $($field:ident),+ ; $($span:ident),+ }, Ord) => {
+        eq_without_spans!($name { $($field),+ ; $($span),+ });
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                ($(&self.$field,)+).cmp(&($(&other.$field,)+))
+            }
+        }
+    };
+    ($name:ident { $($field:ident),+ ; $($span:ident),+ }, Ord, Hash) => {
+        eq_without_spans!($name { $($field),+ ; $($span),+ }, Ord);
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$field.hash(state);)+
+            }
+        }
+    };
+}
+
+pub(crate) use eq_without_spans;
+
+/// Moves the spans of an element within the text.  Used when the element was parsed from a part
+/// of the text, so that the spans are offsets within the whole text, and when the text before
+/// the element was edited.
+pub(crate) trait MoveSpans {
+    /// add `offset` to every span within the element.
+    fn move_spans(&mut self, offset: isize);
+}
+
+/// the byte offset moved by `offset`.
+pub(crate) fn move_byte(byte: usize, offset: isize) -> usize {
+    (byte as isize + offset) as usize
+}
+
+impl MoveSpans for Span {
+    fn move_spans(&mut self, offset: isize) {
+        self.start_byte = move_byte(self.start_byte, offset);
+        self.end_byte = move_byte(self.end_byte, offset);
+    }
+}
+
+impl<T: MoveSpans> MoveSpans for Option<T> {
+    fn move_spans(&mut self, offset: isize) {
+        if let Some(x) = self {
+            x.move_spans(offset);
+        }
+    }
+}
+
+impl<T: MoveSpans> MoveSpans for Box<T> {
+    fn move_spans(&mut self, offset: isize) {
+        (**self).move_spans(offset);
+    }
+}
+
+impl<T: MoveSpans> MoveSpans for Vec<T> {
+    fn move_spans(&mut self, offset: isize) {
+        self.iter_mut().for_each(|x| x.move_spans(offset));
+    }
+}
+
+/// implements `MoveSpans` for a struct by moving the spans within the fields listed before the
+/// `;`.  The fields that can not hold spans are listed after the `;`, every field must be
+/// listed so none is missed.
+macro_rules! move_spans {
+    ($name:ident { $($field:ident),+ $(; $($other:ident),+)? }) => {
+        impl crate::common::MoveSpans for $name {
+            fn move_spans(&mut self, offset: isize) {
+                let $name { $($field,)+ $($($other: _,)+)? } = self;
+                $(crate::common::MoveSpans::move_spans($field, offset);)+
+            }
+        }
+    };
+}
+
+pub(crate) use move_spans;
+
 /// A column definition.
 /// This is used in many places, however the primary_key value should only be used in
 /// the `create table` calls.  In all other cases it will yield an invalid statement.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct ColumnDefinition {
     /// the name of the column
     pub name: Identifier,
@@ -21,36 +178,198 @@ pub struct ColumnDefinition {
     pub data_type: DataType,
     /// if set this column is the primary key.
     pub primary_key: bool,
+    /// the function that masks the column values, `MASKED WITH mask_default()` (Cassandra 5.0).
+    pub mask: Option<FunctionCall>,
+    /// the comment that follows the definition on the same line.
+    pub comment: Option<Comment>,
+    /// the location of the definition in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub name_span: Option<Span>,
 }
 
+eq_without_spans!(ColumnDefinition {
+    name, data_type, primary_key, mask, comment;
+    span, name_span
+});
+
+move_spans!(ColumnDefinition { data_type, mask, comment, span, name_span; name, primary_key });
+
 impl Display for ColumnDefinition {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} {}{}",
+            "{} {}{}{}",
             self.name,
             self.data_type,
+            self.mask
+                .as_ref()
+                .map_or("".to_string(), |x| format!(" MASKED WITH {}", x)),
             if self.primary_key { " PRIMARY KEY" } else { "" }
         )
     }
 }
 
-/// the definition of a data type
+/// the definition of a data type.  Collection, tuple and frozen types hold the data types they
+/// comprise, e.g. `MAP<TEXT, FROZEN<LIST<INT>>>`.
 #[derive(PartialEq, Debug, Clone)]
-pub struct DataType {
-    /// the name of the data type.
-    pub name: DataTypeName,
-    /// the definition of the data type.  Normally this is empty but may contain data types that
-    /// comprise the named type. (e.g. `FROZEN<foo>` will have foo in the definition)
-    pub definition: Vec<DataTypeName>,
+pub enum DataType {
+    /// a native type such as `INT` or `TEXT`.  A collection name without the data types it
+    /// comprises (e.g. `LIST`) is also held as a native type.
+    Native(DataTypeName),
+    /// `LIST<element>`
+    List(Box<DataType>),
+    /// `SET<element>`
+    Set(Box<DataType>),
+    /// `MAP<key, value>`
+    Map(Box<DataType>, Box<DataType>),
+    /// `FROZEN<type>`
+    Frozen(Box<DataType>),
+    /// `TUPLE<type, type, ...>`
+    Tuple(Vec<DataType>),
+    /// `VECTOR<element, dimension>`, a fixed length array of `dimension` elements
+    /// (Cassandra 5.0).  Vector literals are written as lists, see `Operand::List`.
+    Vector(Box<DataType>, usize),
+    /// a user defined type.
+    Udt(FQName),
+    /// a type that is not known to the parser with the data types it comprises, displayed as
+    /// `name<type, type, ...>`.
+    Custom(String, Vec<DataType>),
+}
+
+impl DataType {
+    /// the data type with the name and the data types it comprises, e.g. `MAP` and
+    /// `[TEXT, INT]`.  A name that is not a native type without data types is a user defined
+    /// type.  Returns `None` if the number of data types does not fit the named type.
+    pub fn new(name: &str, mut types: Vec<DataType>) -> Option<DataType> {
+        let boxed = |types: &mut Vec<DataType>| Box::new(types.remove(0));
+        Some(match (DataTypeName::from(name), types.len()) {
+            (DataTypeName::Custom(_), 0) => DataType::Udt(FQName::parse(name)),
+            (DataTypeName::Custom(name), _) => DataType::Custom(name, types),
+            (native, 0) => DataType::Native(native),
+            (DataTypeName::List, 1) => DataType::List(boxed(&mut types)),
+            (DataTypeName::Set, 1) => DataType::Set(boxed(&mut types)),
+            (DataTypeName::Frozen, 1) => DataType::Frozen(boxed(&mut types)),
+            (DataTypeName::Map, 2) => DataType::Map(boxed(&mut types), boxed(&mut types)),
+            (DataTypeName::Tuple, _) => DataType::Tuple(types),
+            _ => return None,
+        })
+    }
+
+    /// parse the data type at the start of the text.  Returns the data type and the rest of
+    /// the text, or `None` if the text does not start with a data type.  The spans of the names
+    /// of user defined types are offsets within the text.
+    pub(crate) fn parse_prefix(input: &str) -> Option<(DataType, &str)> {
+        let text = input.trim_start();
+        let start = input.len() - text.len();
+        let name_len = DataType::name_len(text)?;
+        let (name, mut rest) = text.split_at(name_len);
+        let mut types = vec![];
+        if let Some(args) = rest.trim_start().strip_prefix('<') {
+            rest = args;
+            loop {
+                if DataTypeName::from(name) == DataTypeName::Vector && types.len() == 1 {
+                    let rest_trimmed = rest.trim_start();
+                    let digits = rest_trimmed
+                        .find(|x: char| !x.is_ascii_digit())
+                        .unwrap_or(rest_trimmed.len());
+                    let dimension = rest_trimmed[..digits].parse().ok()?;
+                    let rest = rest_trimmed[digits..].trim_start().strip_prefix('>')?;
+                    return Some((DataType::Vector(Box::new(types.remove(0)), dimension), rest));
+                }
+                let (mut data_type, after) = DataType::parse_prefix(rest)?;
+                data_type.move_spans((input.len() - rest.len()) as isize);
+                types.push(data_type);
+                let after = after.trim_start();
+                if let Some(after) = after.strip_prefix(',') {
+                    rest = after;
+                } else {
+                    rest = after.strip_prefix('>')?;
+                    break;
+                }
+            }
+        }
+        let data_type = DataType::new(name, types)?;
+        Some((
+            data_type.with_name_span(Span::new(start, start + name_len)),
+            rest,
+        ))
+    }
+
+    /// the data type with the span of its name, if it is a user defined type.
+    pub(crate) fn with_name_span(mut self, span: Span) -> DataType {
+        if let DataType::Udt(name) = &mut self {
+            name.span = Some(span);
+        }
+        self
+    }
+
+    /// the length of the possibly keyspace qualified name at the start of the text.
+    fn name_len(text: &str) -> Option<usize> {
+        let identifier_len = |text: &str| -> Option<usize> {
+            if let Some(quoted) = text.strip_prefix('"') {
+                // a quote within a quoted identifier is doubled.
+                let mut chars = quoted.char_indices().peekable();
+                while let Some((pos, c)) = chars.next() {
+                    if c == '"' {
+                        if chars.peek().map_or(false, |(_, c)| *c == '"') {
+                            chars.next();
+                        } else {
+                            return Some(pos + 2);
+                        }
+                    }
+                }
+                None
+            } else {
+                let len = text
+                    .find(|x: char| !(x.is_ascii_alphanumeric() || x == '_'))
+                    .unwrap_or(text.len());
+                if len > 0 && !text.starts_with(|x: char| x.is_ascii_digit()) {
+                    Some(len)
+                } else {
+                    None
+                }
+            }
+        };
+        let len = identifier_len(text)?;
+        match text[len..].strip_prefix('.') {
+            Some(name) => Some(len + 1 + identifier_len(name)?),
+            None => Some(len),
+        }
+    }
+}
+
+impl MoveSpans for DataType {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            DataType::List(x) | DataType::Set(x) | DataType::Frozen(x) | DataType::Vector(x, _) => {
+                x.move_spans(offset)
+            }
+            DataType::Map(key, value) => {
+                key.move_spans(offset);
+                value.move_spans(offset);
+            }
+            DataType::Tuple(x) | DataType::Custom(_, x) => x.move_spans(offset),
+            DataType::Udt(name) => name.move_spans(offset),
+            DataType::Native(_) => {}
+        }
+    }
 }
 
 impl Display for DataType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.definition.is_empty() {
-            write!(f, "{}", self.name)
-        } else {
-            write!(f, "{}<{}>", self.name, self.definition.iter().join(", "))
+        match self {
+            DataType::Native(name) => write!(f, "{}", name),
+            DataType::List(element) => write!(f, "LIST<{}>", element),
+            DataType::Set(element) => write!(f, "SET<{}>", element),
+            DataType::Map(key, value) => write!(f, "MAP<{}, {}>", key, value),
+            DataType::Frozen(data_type) => write!(f, "FROZEN<{}>", data_type),
+            DataType::Tuple(types) => write!(f, "TUPLE<{}>", types.iter().join(", ")),
+            DataType::Vector(element, dimension) => {
+                write!(f, "VECTOR<{}, {}>", element, dimension)
+            }
+            DataType::Udt(name) => write!(f, "{}", name),
+            DataType::Custom(name, types) => write!(f, "{}<{}>", name, types.iter().join(", ")),
         }
     }
 }
@@ -68,6 +387,7 @@ pub enum DataTypeName {
     Date,
     Decimal,
     Double,
+    Duration,
     Float,
     Frozen,
     Inet,
@@ -83,6 +403,7 @@ pub enum DataTypeName {
     VarChar,
     VarInt,
     Uuid,
+    Vector,
     /// defines a custom type.  Where the name is the name of the type.
     Custom(String),
 }
@@ -100,6 +421,7 @@ impl Display for DataTypeName {
             DataTypeName::Date => write!(f, "DATE"),
             DataTypeName::Decimal => write!(f, "DECIMAL"),
             DataTypeName::Double => write!(f, "DOUBLE"),
+            DataTypeName::Duration => write!(f, "DURATION"),
             DataTypeName::Float => write!(f, "FLOAT"),
             DataTypeName::Frozen => write!(f, "FROZEN"),
             DataTypeName::Inet => write!(f, "INET"),
@@ -115,6 +437,7 @@ impl Display for DataTypeName {
             DataTypeName::VarChar => write!(f, "VARCHAR"),
             DataTypeName::VarInt => write!(f, "VARINT"),
             DataTypeName::Uuid => write!(f, "UUID"),
+            DataTypeName::Vector => write!(f, "VECTOR"),
             DataTypeName::Custom(name) => write!(f, "{}", name),
         }
     }
@@ -131,6 +454,7 @@ impl DataTypeName {
             "DATE" => DataTypeName::Date,
             "DECIMAL" => DataTypeName::Decimal,
             "DOUBLE" => DataTypeName::Double,
+            "DURATION" => DataTypeName::Duration,
             "FLOAT" => DataTypeName::Float,
             "FROZEN" => DataTypeName::Frozen,
             "INET" => DataTypeName::Inet,
@@ -146,6 +470,7 @@ impl DataTypeName {
             "TINYINT" => DataTypeName::TinyInt,
             "TUPLE" => DataTypeName::Tuple,
             "UUID" => DataTypeName::Uuid,
+            "VECTOR" => DataTypeName::Vector,
             "VARCHAR" => DataTypeName::VarChar,
             "VARINT" => DataTypeName::VarInt,
             _ => DataTypeName::Custom(name.to_string()),
@@ -156,31 +481,38 @@ impl DataTypeName {
 /// An object that can be on either side of an `Operator`
 #[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
 pub enum Operand {
-    /// A constant
+    /// A constant that is not a literal, such as the `*` of `count(*)`
     Const(String),
-    /// a map displays as `{ String:String, String:String, ... }`
-    Map(Vec<(String, String)>),
-    /// a set of values.  Displays as `( String, String, ...)`
-    Set(Vec<String>),
-    /// a list of values.  Displays as `[String, String, ...]`
-    List(Vec<String>),
+    /// A literal value e.g. `'abc'`, `42` or `0xCAFE`
+    Literal(Literal),
+    /// a map displays as `{ Operand:Operand, Operand:Operand, ... }`
+    Map(Vec<(Operand, Operand)>),
+    /// a set of values.  Displays as `{ Operand, Operand, ...}`
+    Set(Vec<Operand>),
+    /// a list of values.  Displays as `[Operand, Operand, ...]`.  This is also a vector literal
+    /// as vectors are written the same way.
+    List(Vec<Operand>),
+    /// a user defined type literal.  Displays as `{ field:Operand, field:Operand, ... }`
+    Udt(Vec<(Identifier, Operand)>),
     /// a tuple of values.  Displays as `{ Operand, Operand, ... }`
     Tuple(Vec<Operand>),
     /// A column name
     Column(Identifier),
     /// A function call e.g. foo(bar)
-    Func(String),
+    Func(FunctionCall),
     /// A parameter.  The string will either be '?' or ':name'
     Param(String),
     /// the `NULL` value.
     Null,
+    /// an arithmetic operation e.g. `now() - 1d`
+    Arithmetic(Box<Operand>, ArithmeticOperator, Box<Operand>),
 }
 
 /// this is _NOT_ the same as `Operand::Const(string)`  This conversion encloses the value in
 /// single quotes.
 impl From<&str> for Operand {
     fn from(txt: &str) -> Self {
-        Operand::Const(format!("'{}'", txt))
+        Operand::Literal(Literal::String(format!("'{}'", txt)))
     }
 }
 
@@ -192,90 +524,90 @@ impl From<&Bytes> for Operand {
 
 impl From<&bool> for Operand {
     fn from(b: &bool) -> Self {
-        Operand::Const(if *b {
+        Operand::Literal(Literal::Boolean(if *b {
             "TRUE".to_string()
         } else {
             "FALSE".to_string()
-        })
+        }))
     }
 }
 
 impl From<&u128> for Operand {
     fn from(i: &u128) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 impl From<&u64> for Operand {
     fn from(i: &u64) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 impl From<&u32> for Operand {
     fn from(i: &u32) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&u16> for Operand {
     fn from(i: &u16) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&u8> for Operand {
     fn from(i: &u8) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 impl From<&i128> for Operand {
     fn from(i: &i128) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&i64> for Operand {
     fn from(i: &i64) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 impl From<&i32> for Operand {
     fn from(i: &i32) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&i16> for Operand {
     fn from(i: &i16) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&i8> for Operand {
     fn from(i: &i8) -> Self {
-        Operand::Const(i.to_string())
+        Operand::Literal(Literal::Integer(i.to_string()))
     }
 }
 
 impl From<&f64> for Operand {
     fn from(i: &f64) -> Self {
-        Operand::Const(i.to_string())
+        Operand::from_non_finite(*i).unwrap_or_else(|| Operand::from_text(i.to_string()))
     }
 }
 impl From<&f32> for Operand {
     fn from(i: &f32) -> Self {
-        Operand::Const(i.to_string())
+        Operand::from_non_finite(*i as f64).unwrap_or_else(|| Operand::from_text(i.to_string()))
     }
 }
 
 impl From<&BigInt> for Operand {
     fn from(b: &BigInt) -> Self {
-        Operand::Const(b.to_string())
+        Operand::Literal(Literal::Integer(b.to_string()))
     }
 }
 
 impl From<&BigDecimal> for Operand {
     fn from(b: &BigDecimal) -> Self {
-        Operand::Const(b.to_string())
+        Operand::from_text(b.to_string())
     }
 }
 
@@ -292,9 +624,43 @@ impl From<&Uuid> for Operand {
 }
 
 impl Operand {
-    /// creates creates a properly formatted Operand::Const for a hex string.
+    /// creates creates a properly formatted Operand::Literal for a hex string.
     fn from_hex(hex_str: &str) -> Operand {
-        Operand::Const(format!("0x{}", hex_str))
+        Operand::Literal(Literal::Hex(format!("0x{}", hex_str)))
+    }
+
+    /// the operand as the operand of an operator with the given precedence.  An operation that
+    /// binds less tightly is enclosed in parentheses.
+    fn operand(&self, precedence: u8) -> String {
+        match self {
+            Operand::Arithmetic(_, operator, _) if operator.precedence() < precedence => {
+                format!("({})", self)
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// creates an Operand::Literal from the text of a number.  Text that is not a literal is held
+    /// as an Operand::Const.
+    fn from_text(text: String) -> Operand {
+        match Literal::parse(&text) {
+            Some(literal) => Operand::Literal(literal),
+            None => Operand::Const(text),
+        }
+    }
+
+    /// creates the `NaN`, `Infinity` or `-Infinity` literal for a float that is not finite.
+    /// Returns `None` for a finite float.
+    fn from_non_finite(value: f64) -> Option<Operand> {
+        if value.is_nan() {
+            Some(Operand::Literal(Literal::NaN("NaN".to_string())))
+        } else if value.is_infinite() {
+            Some(Operand::Literal(Literal::Infinity(
+                if value < 0.0 { "-Infinity" } else { "Infinity" }.to_string(),
+            )))
+        } else {
+            None
+        }
     }
 
     /// unescapes a CQL string
@@ -322,15 +688,17 @@ impl Operand {
         }
     }
 
-    /// creates an Operand::Const from an unquoted string.
-    /// if the string contains a `'` it will be quoted by the `$$` pattern.  if it contains `$$` and `'`
-    /// it will be quoted by the `'` pattern and all existing `'` will be replaced with `''` (two single quotes).
+    /// creates an Operand from an unquoted string.
+    /// if the string contains a `'` it will be quoted by the `$$` pattern and returned as a
+    /// `Literal::String`.  if it contains `$$` and `'` it will be quoted by the `'` pattern and all
+    /// existing `'` will be replaced with `''` (two single quotes).  Otherwise the string is
+    /// returned as an Operand::Const.
     pub fn escape(txt: &str) -> Operand {
         if txt.contains('\'') {
             if txt.contains("$$") {
-                Operand::Const(format!("'{}'", txt.replace('\'', "''")))
+                Operand::Literal(Literal::String(format!("'{}'", txt.replace('\'', "''"))))
             } else {
-                Operand::Const(format!("$${}$$", txt))
+                Operand::Literal(Literal::String(format!("$${}$$", txt)))
             }
         } else {
             Operand::Const(txt.to_string())
@@ -338,15 +706,42 @@ impl Operand {
     }
 }
 
+impl MoveSpans for Operand {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            Operand::Map(entries) => entries.iter_mut().for_each(|(key, value)| {
+                key.move_spans(offset);
+                value.move_spans(offset);
+            }),
+            Operand::Set(x) | Operand::List(x) | Operand::Tuple(x) => x.move_spans(offset),
+            Operand::Udt(fields) => fields
+                .iter_mut()
+                .for_each(|(_, value)| value.move_spans(offset)),
+            Operand::Func(function) => function.move_spans(offset),
+            Operand::Arithmetic(left, _, right) => {
+                left.move_spans(offset);
+                right.move_spans(offset);
+            }
+            Operand::Const(_)
+            | Operand::Literal(_)
+            | Operand::Column(_)
+            | Operand::Param(_)
+            | Operand::Null => {}
+        }
+    }
+}
+
 impl Display for Operand {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Operand::Column(id) => {
                 write!(f, "{}", id)
             }
-            Operand::Const(text) | Operand::Param(text) | Operand::Func(text) => {
+            Operand::Const(text) | Operand::Param(text) => {
                 write!(f, "{}", text)
             }
+            Operand::Literal(literal) => write!(f, "{}", literal),
+            Operand::Func(function) => write!(f, "{}", function),
             Operand::Map(entries) => {
                 let mut result = String::from('{');
                 result.push_str(
@@ -359,6 +754,18 @@ impl Display for Operand {
                 result.push('}');
                 write!(f, "{}", result)
             }
+            Operand::Udt(fields) => {
+                let mut result = String::from('{');
+                result.push_str(
+                    fields
+                        .iter()
+                        .map(|(x, y)| format!("{}:{}", x, y))
+                        .join(", ")
+                        .as_str(),
+                );
+                result.push('}');
+                write!(f, "{}", result)
+            }
             Operand::Set(values) => {
                 let mut result = String::from('{');
                 result.push_str(values.iter().join(", ").as_str());
@@ -378,7 +785,352 @@ impl Display for Operand {
                 write!(f, "{}", result)
             }
             Operand::Null => write!(f, "NULL"),
+            Operand::Arithmetic(left, operator, right) => write!(
+                f,
+                "{} {} {}",
+                left.operand(operator.precedence()),
+                operator,
+                // operators of the same precedence are evaluated from left to right.
+                right.operand(operator.precedence() + 1)
+            ),
+        }
+    }
+}
+
+/// A literal value.  Each variant holds the text of the literal as it was written so that it
+/// displays unchanged, the `to_*` methods convert the text into the Rust types accepted by the
+/// `From<&T> for Operand` implementations.
+#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
+pub enum Literal {
+    /// a string quoted by `'` or `$$` e.g. `'abc'`
+    String(String),
+    /// an integer e.g. `-42`
+    Integer(String),
+    /// a floating point number e.g. `3.14e2`
+    Float(String),
+    /// `TRUE` or `FALSE`
+    Boolean(String),
+    /// a UUID e.g. `123e4567-e89b-12d3-a456-426614174000`
+    Uuid(String),
+    /// a blob written in hexadecimal e.g. `0xCAFE`
+    Hex(String),
+    /// a duration e.g. `1h30m` or `P1DT2H`
+    Duration(String),
+    /// `NaN` or `-NaN`
+    NaN(String),
+    /// `Infinity` or `-Infinity`
+    Infinity(String),
+}
+
+impl Literal {
+    /// classifies the text of a constant.  Returns `None` if the text is not a literal.
+    pub fn parse(text: &str) -> Option<Literal> {
+        borrowed::Literal::parse(text).map(borrowed::Literal::into_owned)
+    }
+
+    /// the text of the literal as it was written.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Literal::String(text)
+            | Literal::Integer(text)
+            | Literal::Float(text)
+            | Literal::Boolean(text)
+            | Literal::Uuid(text)
+            | Literal::Hex(text)
+            | Literal::Duration(text)
+            | Literal::NaN(text)
+            | Literal::Infinity(text) => text,
+        }
+    }
+
+    /// the value of a string literal without its quotes.
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Literal::String(text) => Some(Operand::unescape(text)),
+            _ => None,
+        }
+    }
+
+    /// the value of a boolean literal.
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            Literal::Boolean(text) => Some(text.eq_ignore_ascii_case("TRUE")),
+            _ => None,
+        }
+    }
+
+    /// the value of an integer literal.  Returns `None` if it does not fit in an `i64`.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Literal::Integer(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// the value of a numeric literal as a float.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Literal::Integer(text) | Literal::Float(text) => text.parse().ok(),
+            Literal::NaN(_) => Some(f64::NAN),
+            Literal::Infinity(text) if text.starts_with('-') => Some(f64::NEG_INFINITY),
+            Literal::Infinity(_) => Some(f64::INFINITY),
+            _ => None,
+        }
+    }
+
+    /// the value of an integer literal.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Literal::Integer(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// the value of an integer or floating point literal.
+    pub fn to_big_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Literal::Integer(text) | Literal::Float(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// the value of a UUID literal, or of a string that holds a UUID.
+    pub fn to_uuid(&self) -> Option<Uuid> {
+        match self {
+            Literal::Uuid(text) => Uuid::parse_str(text).ok(),
+            Literal::String(_) => Uuid::parse_str(&self.to_text()?).ok(),
+            _ => None,
+        }
+    }
+
+    /// the value of a duration literal.
+    pub fn to_duration(&self) -> Option<CqlDuration> {
+        match self {
+            Literal::Duration(text) => CqlDuration::parse(text),
+            _ => None,
+        }
+    }
+
+    /// the bytes of a hexadecimal blob literal.
+    pub fn to_bytes(&self) -> Option<Bytes> {
+        match self {
+            Literal::Hex(text) => hex::decode(text.get(2..)?).ok().map(Bytes::from),
+            _ => None,
+        }
+    }
+
+    /// the value of a string that holds an IP address.
+    pub fn to_ip_addr(&self) -> Option<IpAddr> {
+        self.to_text()?.parse().ok()
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// the units of a duration in the order they are written, with the number of months, days and
+/// nanoseconds in one of the unit.
+const DURATION_UNITS: [(&str, i64, i64, i64); 10] = [
+    ("y", 12, 0, 0),
+    ("mo", 1, 0, 0),
+    ("w", 0, 7, 0),
+    ("d", 0, 1, 0),
+    ("h", 0, 0, 3_600_000_000_000),
+    ("m", 0, 0, 60_000_000_000),
+    ("s", 0, 0, 1_000_000_000),
+    ("ms", 0, 0, 1_000_000),
+    ("us", 0, 0, 1_000),
+    ("ns", 0, 0, 1),
+];
+
+/// A CQL duration.  Months and days are held separately from the nanoseconds as their length
+/// varies.  All three values have the same sign.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Eq, Hash)]
+pub struct CqlDuration {
+    /// the number of months.
+    pub months: i32,
+    /// the number of days.
+    pub days: i32,
+    /// the number of nanoseconds.
+    pub nanos: i64,
+}
+
+impl CqlDuration {
+    /// parses a duration written as quantities and units e.g. `1h30m`, in the ISO 8601 format
+    /// e.g. `P1DT2H` or `P2W`, or in the ISO 8601 alternative format e.g.
+    /// `P0001-02-03T04:05:06`.  A leading `-` negates the duration.  Returns `None` if the text
+    /// is not a duration or the duration does not fit.
+    pub fn parse(text: &str) -> Option<CqlDuration> {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text),
+        };
+        let duration = match unsigned.strip_prefix(|c| c == 'P' || c == 'p') {
+            Some(iso) if iso.contains('-') => CqlDuration::parse_iso_alternative(iso)?,
+            Some(iso) => CqlDuration::parse_iso(&iso.to_ascii_lowercase())?,
+            None => CqlDuration::parse_units(unsigned)?,
+        };
+        if negative {
+            Some(CqlDuration {
+                months: duration.months.checked_neg()?,
+                days: duration.days.checked_neg()?,
+                nanos: duration.nanos.checked_neg()?,
+            })
+        } else {
+            Some(duration)
+        }
+    }
+
+    /// the duration with `quantity` of the unit at `index` in `DURATION_UNITS` added.
+    fn add(self, quantity: &str, index: usize) -> Option<CqlDuration> {
+        if quantity.is_empty() || !quantity.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let quantity: i64 = quantity.parse().ok()?;
+        let (_, months, days, nanos) = DURATION_UNITS[index];
+        Some(CqlDuration {
+            months: i32::try_from(
+                quantity
+                    .checked_mul(months)?
+                    .checked_add(self.months.into())?,
+            )
+            .ok()?,
+            days: i32::try_from(quantity.checked_mul(days)?.checked_add(self.days.into())?).ok()?,
+            nanos: quantity.checked_mul(nanos)?.checked_add(self.nanos)?,
+        })
+    }
+
+    /// parses quantities and units e.g. `1h30m`.  The units are written largest first.
+    fn parse_units(text: &str) -> Option<CqlDuration> {
+        let mut duration = CqlDuration::default();
+        let mut next = 0;
+        let mut rest = text;
+        if rest.is_empty() {
+            return None;
+        }
+        while !rest.is_empty() {
+            let number = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let end = rest[number..]
+                .find(|c: char| !c.is_alphabetic())
+                .map_or(rest.len(), |x| x + number);
+            let unit = rest[number..end].to_lowercase();
+            let unit = if unit == "µs" { "us" } else { unit.as_str() };
+            let index = DURATION_UNITS.iter().position(|(name, ..)| *name == unit)?;
+            if index < next {
+                return None;
+            }
+            duration = duration.add(&rest[..number], index)?;
+            next = index + 1;
+            rest = &rest[end..];
+        }
+        Some(duration)
+    }
+
+    /// parses the ISO 8601 format that follows the `P` e.g. `1dt2h` or `2w`.  The text is in
+    /// lower case.
+    fn parse_iso(text: &str) -> Option<CqlDuration> {
+        if let Some(weeks) = text.strip_suffix('w') {
+            return CqlDuration::default().add(weeks, 2);
+        }
+        // the designators of each part with the index of their unit in `DURATION_UNITS`.
+        let designated =
+            |duration: CqlDuration, mut part: &str, designators: [(char, usize); 3]| {
+                let mut duration = duration;
+                for (designator, index) in designators {
+                    if let Some(pos) = part.find(designator) {
+                        duration = duration.add(&part[..pos], index)?;
+                        part = &part[pos + 1..];
+                    }
+                }
+                if part.is_empty() {
+                    Some(duration)
+                } else {
+                    None
+                }
+            };
+        let (date, time) = match text.find('t') {
+            Some(pos) => (&text[..pos], Some(&text[pos + 1..])),
+            None => (text, None),
+        };
+        if date.is_empty() && time.map_or(true, str::is_empty) {
+            return None;
+        }
+        let duration = designated(CqlDuration::default(), date, [('y', 0), ('m', 1), ('d', 3)])?;
+        match time {
+            Some("") => None,
+            Some(time) => designated(duration, time, [('h', 4), ('m', 5), ('s', 6)]),
+            None => Some(duration),
+        }
+    }
+
+    /// parses the ISO 8601 alternative format that follows the `P` e.g. `0001-02-03T04:05:06`.
+    fn parse_iso_alternative(text: &str) -> Option<CqlDuration> {
+        let (date, time) = text.split_once(|c| c == 'T' || c == 't')?;
+        let date: Vec<&str> = date.split('-').collect();
+        let time: Vec<&str> = time.split(':').collect();
+        if date.len() != 3 || time.len() != 3 || date[0].len() != 4 {
+            return None;
+        }
+        let mut duration = CqlDuration::default();
+        for (quantity, index) in date.iter().chain(time.iter()).zip([0, 1, 3, 4, 5, 6]) {
+            if index > 0 && quantity.len() != 2 {
+                return None;
+            }
+            duration = duration.add(quantity, index)?;
         }
+        Some(duration)
+    }
+}
+
+/// displays the duration as quantities and units with the largest units first e.g. `1y2mo3d4h`.
+/// A zero duration displays as `0s`.
+impl Display for CqlDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.months == 0 && self.days == 0 && self.nanos == 0 {
+            return write!(f, "0s");
+        }
+        if self.months < 0 || self.days < 0 || self.nanos < 0 {
+            write!(f, "-")?;
+        }
+        let mut months = self.months.unsigned_abs() as u64;
+        let mut days = self.days.unsigned_abs() as u64;
+        let mut nanos = self.nanos.unsigned_abs();
+        for (unit, unit_months, unit_days, unit_nanos) in DURATION_UNITS {
+            let (remaining, size) = if unit_months > 0 {
+                (&mut months, unit_months as u64)
+            } else if unit_days > 0 {
+                // days are not written as weeks
+                if unit == "w" {
+                    continue;
+                }
+                (&mut days, unit_days as u64)
+            } else {
+                (&mut nanos, unit_nanos as u64)
+            };
+            let quantity = *remaining / size;
+            if quantity > 0 {
+                write!(f, "{}{}", quantity, unit)?;
+                *remaining %= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&CqlDuration> for Operand {
+    fn from(duration: &CqlDuration) -> Self {
+        Operand::Literal(Literal::Duration(duration.to_string()))
+    }
+}
+
+impl From<CqlDuration> for Operand {
+    fn from(duration: CqlDuration) -> Self {
+        Operand::from(&duration)
     }
 }
 
@@ -393,6 +1145,8 @@ pub struct Privilege {
     pub role: Option<String>,
 }
 
+move_spans!(Privilege { resource; privilege, role });
+
 /// the list of privileges recognized by the system.
 #[derive(PartialEq, Debug, Clone)]
 pub enum PrivilegeType {
@@ -405,6 +1159,10 @@ pub enum PrivilegeType {
     Drop,
     Modify,
     Select,
+    /// read the values of masked columns (Cassandra 5.0).
+    Unmask,
+    /// select on masked columns (Cassandra 5.0).
+    SelectMasked,
 }
 
 impl Display for PrivilegeType {
@@ -419,18 +1177,46 @@ impl Display for PrivilegeType {
             PrivilegeType::Drop => write!(f, "DROP"),
             PrivilegeType::Modify => write!(f, "MODIFY"),
             PrivilegeType::Select => write!(f, "SELECT"),
+            PrivilegeType::Unmask => write!(f, "UNMASK"),
+            PrivilegeType::SelectMasked => write!(f, "SELECT_MASKED"),
         }
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq)]
 pub struct RelationElement {
-    /// the column, function or column list on the left side
-    pub obj: Operand,
+    /// the column, column list or token on the left side
+    pub obj: RelationTarget,
     /// the relational operator
     pub oper: RelationOperator,
-    /// the value, func, argument list, tuple list or tuple
+    /// the value, func, argument list, tuple list or tuple.  The values of `IN (...)` are
+    /// always a tuple, even when there is only one, so `IN (?)` is a tuple that holds a bind
+    /// marker where `IN ?` is the bind marker itself.
     pub value: Operand,
+    /// the location of the relation in the query text.
+    pub span: Option<Span>,
+    /// the location of the column, column list or token on the left side.
+    pub obj_span: Option<Span>,
+    /// the location of the value.
+    pub value_span: Option<Span>,
+}
+
+eq_without_spans!(RelationElement { obj, oper, value; span, obj_span, value_span }, Ord);
+
+move_spans!(RelationElement { obj, value, span, obj_span, value_span; oper });
+
+impl RelationElement {
+    /// the values listed by an `IN` or `NOT IN` relation, e.g. `1` and `2` for `a IN (1, 2)`.
+    /// Returns `None` for other operators and when the values are given by one bind marker,
+    /// e.g. `a IN ?`.
+    pub fn in_values(&self) -> Option<&[Operand]> {
+        match (&self.oper, &self.value) {
+            (RelationOperator::In | RelationOperator::NotIn, Operand::Tuple(values)) => {
+                Some(values)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for RelationElement {
@@ -439,6 +1225,49 @@ impl Display for RelationElement {
     }
 }
 
+/// the left side of a relation.
+#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
+pub enum RelationTarget {
+    /// a column, e.g. `a = 1`.
+    Column(Identifier),
+    /// the columns of a multi-column relation, e.g. `(c1, c2) > (1, 2)`.
+    Columns(Vec<Identifier>),
+    /// the token of the partition key columns, e.g. `token(pk1, pk2) > token(?, ?)`.
+    Token(Vec<Identifier>),
+    /// any other left side, e.g. a call of a function other than `token`.
+    Other(Operand),
+}
+
+impl RelationTarget {
+    /// the columns the relation restricts, empty for `Other`.
+    pub fn columns(&self) -> &[Identifier] {
+        match self {
+            RelationTarget::Column(column) => std::slice::from_ref(column),
+            RelationTarget::Columns(columns) | RelationTarget::Token(columns) => columns,
+            RelationTarget::Other(_) => &[],
+        }
+    }
+}
+
+impl MoveSpans for RelationTarget {
+    fn move_spans(&mut self, offset: isize) {
+        if let RelationTarget::Other(operand) = self {
+            operand.move_spans(offset);
+        }
+    }
+}
+
+impl Display for RelationTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelationTarget::Column(column) => write!(f, "{}", column),
+            RelationTarget::Columns(columns) => write!(f, "({})", columns.iter().join(", ")),
+            RelationTarget::Token(columns) => write!(f, "token({})", columns.iter().join(", ")),
+            RelationTarget::Other(operand) => write!(f, "{}", operand),
+        }
+    }
+}
+
 impl RelationOperator {
     /// evaluates the expression for any PartialOrd implementation
     pub fn eval<T>(&self, left: &T, right: &T) -> bool
@@ -452,10 +1281,14 @@ impl RelationOperator {
             RelationOperator::NotEqual => !left.eq(right),
             RelationOperator::GreaterThanOrEqual => left.ge(right),
             RelationOperator::GreaterThan => left.gt(right),
-            RelationOperator::In => false,
-            RelationOperator::Contains => false,
-            RelationOperator::ContainsKey => false,
-            RelationOperator::IsNot => false,
+            RelationOperator::In
+            | RelationOperator::Contains
+            | RelationOperator::ContainsKey
+            | RelationOperator::IsNot
+            | RelationOperator::NotIn
+            | RelationOperator::NotContains
+            | RelationOperator::NotContainsKey
+            | RelationOperator::Like => false,
         }
     }
 }
@@ -475,6 +1308,14 @@ pub enum RelationOperator {
     /// this is not used in normal cases it is used in the MaterializedView to specify
     /// a collumn that must not be null.
     IsNot,
+    /// `NOT IN`, requires a storage-attached index (Cassandra 5.0).
+    NotIn,
+    /// `NOT CONTAINS`, requires a storage-attached index (Cassandra 5.0).
+    NotContains,
+    /// `NOT CONTAINS KEY`, requires a storage-attached index (Cassandra 5.0).
+    NotContainsKey,
+    /// `LIKE`, requires a SASI index.
+    Like,
 }
 
 impl Display for RelationOperator {
@@ -490,36 +1331,150 @@ impl Display for RelationOperator {
             RelationOperator::Contains => write!(f, "CONTAINS"),
             RelationOperator::ContainsKey => write!(f, "CONTAINS KEY"),
             RelationOperator::IsNot => write!(f, "IS NOT"),
+            RelationOperator::NotIn => write!(f, "NOT IN"),
+            RelationOperator::NotContains => write!(f, "NOT CONTAINS"),
+            RelationOperator::NotContainsKey => write!(f, "NOT CONTAINS KEY"),
+            RelationOperator::Like => write!(f, "LIKE"),
         }
     }
 }
 
+/// the condition of a `WHERE` clause, a boolean expression of relations.  `OR` and
+/// parentheses require storage-attached indexes (Cassandra 5.0).
+#[derive(PartialEq, Debug, Clone, Eq)]
+pub enum WhereExpression {
+    /// a single relation, e.g. `a = 1`.
+    Relation(RelationElement),
+    /// the expressions must all be true.
+    And(Vec<WhereExpression>),
+    /// one of the expressions must be true.
+    Or(Vec<WhereExpression>),
+    /// the expression must be false.  No version of Cassandra accepts `NOT` before a
+    /// condition, so the parser does not produce it and a query holding it is rejected.
+    Not(Box<WhereExpression>),
+}
+
+impl WhereExpression {
+    /// the expression that requires all of the relations, `None` if there are no relations.
+    pub fn from_relations(mut relations: Vec<RelationElement>) -> Option<WhereExpression> {
+        match relations.len() {
+            0 => None,
+            1 => Some(WhereExpression::Relation(relations.remove(0))),
+            _ => Some(WhereExpression::And(
+                relations
+                    .into_iter()
+                    .map(WhereExpression::Relation)
+                    .collect(),
+            )),
+        }
+    }
+
+    /// the relations of the expression if it only requires all of them to be true, i.e. it
+    /// can be written as relations joined by `AND`.  Returns `None` if the expression holds
+    /// an `OR` of several expressions or a `NOT`.
+    pub fn to_conjunction(&self) -> Option<Vec<RelationElement>> {
+        match self {
+            WhereExpression::Relation(relation) => Some(vec![relation.clone()]),
+            WhereExpression::And(items) => items.iter().try_fold(vec![], |mut result, item| {
+                result.extend(item.to_conjunction()?);
+                Some(result)
+            }),
+            WhereExpression::Or(items) if items.len() == 1 => items[0].to_conjunction(),
+            WhereExpression::Or(_) | WhereExpression::Not(_) => None,
+        }
+    }
+
+    /// the relations in the expression, in the order they are written.
+    pub fn relations(&self) -> Vec<&RelationElement> {
+        match self {
+            WhereExpression::Relation(relation) => vec![relation],
+            WhereExpression::And(items) | WhereExpression::Or(items) => {
+                items.iter().flat_map(WhereExpression::relations).collect()
+            }
+            WhereExpression::Not(item) => item.relations(),
+        }
+    }
+}
+
+impl MoveSpans for WhereExpression {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            WhereExpression::Relation(relation) => relation.move_spans(offset),
+            WhereExpression::And(x) | WhereExpression::Or(x) => x.move_spans(offset),
+            WhereExpression::Not(x) => x.move_spans(offset),
+        }
+    }
+}
+
+impl Display for WhereExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WhereExpression::Relation(relation) => write!(f, "{}", relation),
+            WhereExpression::And(items) => write!(
+                f,
+                "{}",
+                items
+                    .iter()
+                    .map(|x| match x {
+                        // AND binds more tightly than OR.
+                        WhereExpression::Or(items) if items.len() > 1 => format!("({})", x),
+                        _ => x.to_string(),
+                    })
+                    .join(" AND ")
+            ),
+            WhereExpression::Or(items) => write!(f, "{}", items.iter().join(" OR ")),
+            WhereExpression::Not(item) => match item.as_ref() {
+                WhereExpression::Relation(_) | WhereExpression::Not(_) => {
+                    write!(f, "NOT {}", item)
+                }
+                _ => write!(f, "NOT ({})", item),
+            },
+        }
+    }
+}
+
+/// a value that may be given by a bind marker, e.g. the value of `LIMIT`, `USING TTL` or
+/// `USING TIMESTAMP`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Bindable<T> {
+    /// a literal value
+    Literal(T),
+    /// A parameter.  The string will either be '?' or ':name'
+    Param(String),
+}
+
+impl<T: Display> Display for Bindable<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bindable::Literal(value) => write!(f, "{}", value),
+            Bindable::Param(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl<T> From<T> for Bindable<T> {
+    fn from(value: T) -> Self {
+        Bindable::Literal(value)
+    }
+}
+
 /// the structure of the TTL / Timestamp option.
 #[derive(PartialEq, Debug, Clone)]
 pub struct TtlTimestamp {
     /// the optional time-to-live value
-    pub ttl: Option<u64>,
+    pub ttl: Option<Bindable<u64>>,
     /// the optional timestamp value
-    pub timestamp: Option<u64>,
+    pub timestamp: Option<Bindable<u64>>,
+    /// the optional timeout of the statement, `USING TIMEOUT` is a ScyllaDB extension.
+    pub timeout: Option<Bindable<CqlDuration>>,
 }
 
 impl Display for TtlTimestamp {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let tl = match self.ttl {
-            Some(t) => format!("TTL {}", t),
-            _ => "".to_string(),
-        };
-
-        let tm = match self.timestamp {
-            Some(t) => format!("TIMESTAMP {}", t),
-            _ => "".to_string(),
-        };
-
-        if self.ttl.is_some() && self.timestamp.is_some() {
-            write!(f, " USING {} AND {}", tl, tm)
-        } else {
-            write!(f, " USING {}", if self.ttl.is_some() { tl } else { tm })
-        }
+        let tl = self.ttl.as_ref().map(|t| format!("TTL {}", t));
+        let tm = self.timestamp.as_ref().map(|t| format!("TIMESTAMP {}", t));
+        let to = self.timeout.as_ref().map(|t| format!("TIMEOUT {}", t));
+        write!(f, " USING {}", [tl, tm, to].iter().flatten().join(" AND "))
     }
 }
 
@@ -529,18 +1484,28 @@ pub enum WithItem {
     /// an option comprising the key (name) and the value for the option.
     Option { key: String, value: OptionValue },
     /// A clustering order clause.
-    ClusterOrder(OrderClause),
+    ClusterOrder(Vec<OrderClause>),
     /// the ID the ID for the table/view.
     ID(String),
     /// use compact storage.
     CompactStorage,
 }
 
+impl MoveSpans for WithItem {
+    fn move_spans(&mut self, offset: isize) {
+        if let WithItem::ClusterOrder(order) = self {
+            order.move_spans(offset);
+        }
+    }
+}
+
 impl Display for WithItem {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             WithItem::Option { key, value } => write!(f, "{} = {}", key, value),
-            WithItem::ClusterOrder(order) => write!(f, "CLUSTERING ORDER BY ({})", order),
+            WithItem::ClusterOrder(order) => {
+                write!(f, "CLUSTERING ORDER BY ({})", order.iter().join(", "))
+            }
             WithItem::ID(txt) => write!(f, "ID = {}", txt),
             WithItem::CompactStorage => write!(f, "COMPACT STORAGE"),
         }
@@ -548,22 +1513,36 @@ impl Display for WithItem {
 }
 
 /// the order clause
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct OrderClause {
     /// the column to order by.
     pub name: Identifier,
     /// if `true` then the order is descending,
     pub desc: bool,
+    /// if set the rows are ordered by the similarity of the vector column to this vector,
+    /// `ANN OF [...]` (Cassandra 5.0).  `desc` is ignored.
+    pub ann_of: Option<Operand>,
+    /// the location of the ordering in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub name_span: Option<Span>,
 }
 
+eq_without_spans!(OrderClause { name, desc, ann_of; span, name_span });
+
+move_spans!(OrderClause { ann_of, span, name_span; name, desc });
+
 impl Display for OrderClause {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.name,
-            if self.desc { "DESC" } else { "ASC" }
-        )
+        match &self.ann_of {
+            Some(vector) => write!(f, "{} ANN OF {}", self.name, vector),
+            None => write!(
+                f,
+                "{} {}",
+                self.name,
+                if self.desc { "DESC" } else { "ASC" }
+            ),
+        }
     }
 }
 
@@ -640,6 +1619,14 @@ pub enum Resource {
     Table(FQName),
 }
 
+impl MoveSpans for Resource {
+    fn move_spans(&mut self, offset: isize) {
+        if let Resource::Function(name) | Resource::Table(name) = self {
+            name.move_spans(offset);
+        }
+    }
+}
+
 impl Display for Resource {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -669,7 +1656,7 @@ impl WhereClause {
         let mut result: BTreeMap<Identifier, Vec<RelationElement>> = BTreeMap::new();
 
         for relation_element in where_clause {
-            if let Operand::Column(key) = &relation_element.obj {
+            if let RelationTarget::Column(key) = &relation_element.obj {
                 if let Some(value) = result.get_mut(key) {
                     value.push(relation_element.clone());
                 } else {
@@ -686,20 +1673,54 @@ impl WhereClause {
         where_clause
             .into_iter()
             .filter_map(|relation_element| match relation_element.obj {
-                Operand::Column(name) => Some(name),
+                RelationTarget::Column(name) => Some(name),
                 _ => None,
             })
             .collect()
     }
 }
 
+/// a function call, e.g. `token(a, b)` or `toTimestamp(now())`.
+#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
+pub struct FunctionCall {
+    /// the name of the function.
+    pub name: FQName,
+    /// the arguments of the call.  The `*` of `count(*)` is held as a constant.
+    pub args: Vec<Operand>,
+}
+
+impl FunctionCall {
+    /// the call of the function with the arguments.
+    pub fn new(name: &str, args: Vec<Operand>) -> FunctionCall {
+        FunctionCall {
+            name: FQName::parse(name),
+            args,
+        }
+    }
+}
+
+move_spans!(FunctionCall { name, args });
+
+impl Display for FunctionCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.args.iter().join(", "))
+    }
+}
+
 /// a fully qualified name.
-#[derive(PartialEq, Debug, Clone, Hash, Eq, Deserialize)]
+#[derive(Debug, Clone, Eq, Deserialize)]
 pub struct FQName {
     pub keyspace: Option<Identifier>,
     pub name: Identifier,
+    /// the location of the name in the query text.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
+eq_without_spans!(FQName { keyspace, name; span }, Ord, Hash);
+
+move_spans!(FQName { span; keyspace, name });
+
 impl FQName {
     /// parses the FQName from a string.  Breaks the string at the first dot (`.`) and makes the left
     /// string the keyspace and the second string the name. If no dot is present the entire string
@@ -717,6 +1738,7 @@ impl FQName {
         FQName {
             keyspace: None,
             name: Identifier::parse(name),
+            span: None,
         }
     }
 
@@ -724,6 +1746,7 @@ impl FQName {
         FQName {
             keyspace: Some(Identifier::parse(keyspace)),
             name: Identifier::parse(name),
+            span: None,
         }
     }
 }
@@ -900,9 +1923,58 @@ impl PartialEq<IdentifierRef<'_>> for Identifier {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::{FQName, Identifier, Operand};
+    use crate::common::{
+        Bindable, CqlDuration, DataType, DataTypeName, FQName, Identifier, Literal, Operand,
+        RelationElement, RelationOperator, RelationTarget, TtlTimestamp, WhereExpression,
+    };
+    use bigdecimal::BigDecimal;
+    use bytes::Bytes;
+    use num_bigint::BigInt;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
+    use std::net::IpAddr;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_data_type_parse_prefix() {
+        let parse = |text| DataType::parse_prefix(text).map(|(x, rest)| (x.to_string(), rest));
+        assert_eq!(
+            Some(("SET<FROZEN<MAP<INT, ks.\"My Type\">>>".to_string(), " ) x")),
+            parse(" set < frozen<map<int, ks.\"My Type\">> > ) x")
+        );
+        assert_eq!(
+            Some(("VECTOR<FLOAT, 3>".to_string(), "")),
+            parse("vector<float, 3>")
+        );
+        assert_eq!(None, parse("map<int>"));
+        assert_eq!(None, parse("list<int"));
+        assert_eq!(
+            Some(DataType::Native(DataTypeName::List)),
+            DataType::new("list", vec![])
+        );
+    }
+
+    #[test]
+    fn test_ttl_timestamp_display() {
+        let using = TtlTimestamp {
+            ttl: Some(Bindable::Param("?".to_string())),
+            timestamp: Some(Bindable::Literal(5)),
+            timeout: None,
+        };
+        assert_eq!(" USING TTL ? AND TIMESTAMP 5", using.to_string());
+        let using = TtlTimestamp {
+            ttl: None,
+            timestamp: Some(Bindable::Param(":ts".to_string())),
+            timeout: None,
+        };
+        assert_eq!(" USING TIMESTAMP :ts", using.to_string());
+        let using = TtlTimestamp {
+            ttl: Some(Bindable::Literal(3)),
+            timestamp: None,
+            timeout: CqlDuration::parse("1m30s").map(Bindable::Literal),
+        };
+        assert_eq!(" USING TTL 3 AND TIMEOUT 1m30s", using.to_string());
+    }
 
     #[test]
     pub fn test_operand_unescape() {
@@ -930,6 +2002,207 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_literal_parse() {
+        let tests = [
+            ("'abc'", Literal::String("'abc'".to_string())),
+            ("$$it's$$", Literal::String("$$it's$$".to_string())),
+            ("-42", Literal::Integer("-42".to_string())),
+            ("3.14e2", Literal::Float("3.14e2".to_string())),
+            ("1.5", Literal::Float("1.5".to_string())),
+            ("true", Literal::Boolean("true".to_string())),
+            (
+                "123e4567-e89b-12d3-a456-426614174000",
+                Literal::Uuid("123e4567-e89b-12d3-a456-426614174000".to_string()),
+            ),
+            ("0xCAFE", Literal::Hex("0xCAFE".to_string())),
+            ("1h30m", Literal::Duration("1h30m".to_string())),
+            ("P1DT2H", Literal::Duration("P1DT2H".to_string())),
+            ("-NaN", Literal::NaN("-NaN".to_string())),
+            ("Infinity", Literal::Infinity("Infinity".to_string())),
+        ];
+        for (text, expected) in tests {
+            assert_eq!(Some(expected), Literal::parse(text), "{}", text);
+        }
+        for text in [
+            "", "abc", "*", "0x", "1x", "1.2.3", "--1", "é", "0é", "0xé", "3é",
+        ] {
+            assert_eq!(None, Literal::parse(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_literal_conversions() {
+        let literal = |operand| match operand {
+            Operand::Literal(literal) => literal,
+            operand => panic!("not a literal: {}", operand),
+        };
+        assert_eq!(Some(-42), Literal::parse("-42").unwrap().to_i64());
+        assert_eq!(None, Literal::parse("'42'").unwrap().to_i64());
+        let big: BigInt = BigInt::from(i64::MAX) * 10;
+        assert_eq!(Some(big.clone()), literal(Operand::from(&big)).to_bigint());
+        assert_eq!(
+            Some(BigDecimal::from(314)),
+            Literal::parse("3.14e2").unwrap().to_big_decimal()
+        );
+        assert_eq!(Some(0.5), literal(Operand::from(&0.5_f64)).to_f64());
+        // floats that are not finite are written as CQL literals.
+        for (operand, text) in [
+            (Operand::from(&f64::INFINITY), "Infinity"),
+            (Operand::from(&f64::NEG_INFINITY), "-Infinity"),
+            (Operand::from(&f64::NAN), "NaN"),
+            (Operand::from(&f32::NEG_INFINITY), "-Infinity"),
+            (Operand::from(&f32::NAN), "NaN"),
+        ] {
+            assert_eq!(text, operand.to_string());
+            assert_eq!(Literal::parse(text), Some(literal(operand)));
+        }
+        assert_eq!(
+            Some(f64::INFINITY),
+            literal(Operand::from(&f64::INFINITY)).to_f64()
+        );
+        assert!(literal(Operand::from(&f32::NAN)).to_f64().unwrap().is_nan());
+        assert_eq!(Some(0.25), literal(Operand::from(&0.25_f32)).to_f64());
+        assert_eq!(
+            Some(f64::NEG_INFINITY),
+            Literal::parse("-Infinity").unwrap().to_f64()
+        );
+        assert_eq!(Some(false), literal(Operand::from(&false)).to_bool());
+        assert_eq!(
+            Some("it's".to_string()),
+            Literal::parse("'it''s'").unwrap().to_text()
+        );
+        let uuid = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").unwrap();
+        assert_eq!(Some(uuid), literal(Operand::from(&uuid)).to_uuid());
+        assert_eq!(
+            Some(uuid),
+            Literal::parse("123e4567-e89b-12d3-a456-426614174000")
+                .unwrap()
+                .to_uuid()
+        );
+        let bytes = Bytes::from_static(&[0xca, 0xfe]);
+        assert_eq!(
+            Some(bytes.clone()),
+            literal(Operand::from(&bytes)).to_bytes()
+        );
+        assert_eq!(Some(bytes), Literal::parse("0xCAFE").unwrap().to_bytes());
+        let addr: IpAddr = "10.0.0.1".parse().unwrap();
+        assert_eq!(Some(addr), literal(Operand::from(&addr)).to_ip_addr());
+    }
+
+    #[test]
+    fn test_where_expression() {
+        let relation = |column: &str, value: i32| {
+            WhereExpression::Relation(RelationElement {
+                obj: RelationTarget::Column(Identifier::parse(column)),
+                oper: RelationOperator::Equal,
+                value: Operand::from(&value),
+                span: None,
+                obj_span: None,
+                value_span: None,
+            })
+        };
+        let relations = |expression: &WhereExpression| {
+            expression.to_conjunction().map(|x| {
+                x.iter()
+                    .map(RelationElement::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" AND ")
+            })
+        };
+
+        let expression = WhereExpression::And(vec![
+            relation("a", 1),
+            WhereExpression::And(vec![relation("b", 2), relation("c", 3)]),
+            WhereExpression::Or(vec![relation("d", 4)]),
+        ]);
+        assert_eq!(
+            Some("a = 1 AND b = 2 AND c = 3 AND d = 4".to_string()),
+            relations(&expression)
+        );
+        assert_eq!(
+            None,
+            WhereExpression::from_relations(vec![]).and_then(|x| x.to_conjunction())
+        );
+
+        let expression = WhereExpression::And(vec![
+            WhereExpression::Or(vec![relation("a", 1), relation("b", 2)]),
+            WhereExpression::Not(Box::new(WhereExpression::And(vec![
+                relation("c", 3),
+                relation("d", 4),
+            ]))),
+        ]);
+        assert_eq!(None, relations(&expression));
+        assert_eq!(4, expression.relations().len());
+        assert_eq!(
+            "(a = 1 OR b = 2) AND NOT (c = 3 AND d = 4)",
+            expression.to_string()
+        );
+        assert_eq!(
+            "a = 1 OR b = 2 AND c = 3",
+            WhereExpression::Or(vec![
+                relation("a", 1),
+                WhereExpression::And(vec![relation("b", 2), relation("c", 3)]),
+            ])
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_duration() {
+        let duration = |months, days, nanos| CqlDuration {
+            months,
+            days,
+            nanos,
+        };
+        let tests = [
+            ("1h30m", duration(0, 0, 5_400_000_000_000), "1h30m"),
+            ("1y2MO3w4d", duration(14, 25, 0), "1y2mo25d"),
+            ("-1s500ms", duration(0, 0, -1_500_000_000), "-1s500ms"),
+            ("10ms3µs7ns", duration(0, 0, 10_003_007), "10ms3us7ns"),
+            ("P1DT2H", duration(0, 1, 7_200_000_000_000), "1d2h"),
+            ("P1Y14M", duration(26, 0, 0), "2y2mo"),
+            ("PT90S", duration(0, 0, 90_000_000_000), "1m30s"),
+            ("P2W", duration(0, 14, 0), "14d"),
+            (
+                "-P0001-02-03T04:05:06",
+                duration(-14, -3, -14_706_000_000_000),
+                "-1y2mo3d4h5m6s",
+            ),
+            ("0d", duration(0, 0, 0), "0s"),
+        ];
+        for (text, expected, canonical) in tests {
+            assert_eq!(Some(expected), CqlDuration::parse(text), "{}", text);
+            assert_eq!(canonical, expected.to_string());
+        }
+        for text in [
+            "",
+            "h",
+            "1",
+            "1x",
+            "30m1h",
+            "1h1h",
+            "P",
+            "PT",
+            "P1DT",
+            "P1H",
+            "P0001-2-03T04:05:06",
+            "99999999999y",
+        ] {
+            assert_eq!(None, CqlDuration::parse(text), "{}", text);
+        }
+        let literal = match Operand::from(duration(1, 2, 3)) {
+            Operand::Literal(literal) => literal,
+            operand => panic!("not a literal: {}", operand),
+        };
+        assert_eq!(Literal::Duration("1mo2d3ns".to_string()), literal);
+        assert_eq!(Some(duration(1, 2, 3)), literal.to_duration());
+        assert_eq!(
+            Some(DataType::Native(DataTypeName::Duration)),
+            DataType::new("duration", vec![])
+        );
+    }
+
     #[test]
     pub fn test_operand_escape() {
         let tests = [
@@ -941,11 +2214,13 @@ mod tests {
                 "'Women''s Tour of New Zealand makes big $$'",
                 "Women's Tour of New Zealand makes big $$",
             ),
-            ("55", "55"),
         ];
         for (expected, arg) in tests {
-            assert_eq!(Operand::Const(expected.to_string()), Operand::escape(arg));
+            let literal = Literal::String(expected.to_string());
+            assert_eq!(Some(arg.to_string()), literal.to_text());
+            assert_eq!(Operand::Literal(literal), Operand::escape(arg));
         }
+        assert_eq!(Operand::Const("55".to_string()), Operand::escape("55"));
     }
 
     #[test]
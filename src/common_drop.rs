@@ -1,4 +1,4 @@
-use crate::common::FQName;
+use crate::common::{move_spans, FQName};
 
 /// the data for many `Drop` commands
 #[derive(PartialEq, Debug, Clone)]
@@ -9,6 +9,8 @@ pub struct CommonDrop {
     pub if_exists: bool,
 }
 
+move_spans!(CommonDrop { name; if_exists });
+
 impl CommonDrop {
     pub fn get_text(&self, type_: &str) -> String {
         format!(
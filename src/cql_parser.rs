@@ -0,0 +1,256 @@
+use crate::borrowed;
+use crate::cassandra_ast::CassandraAST;
+use crate::comment::Comment;
+use crate::dialect::Dialect;
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::statement_summary::{self, StatementSummary};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tree_sitter::{Parser, Tree};
+
+thread_local! {
+    /// the parser used by `CassandraAST::new` and `CassandraAST::try_new` on this thread.
+    static DEFAULT_PARSER: RefCell<Option<CqlParser>> = RefCell::new(None);
+}
+
+/// A reusable CQL parser.
+///
+/// Creating a tree-sitter parser and loading the CQL grammar is done once when the `CqlParser`
+/// is created rather than for every query.  A parser can only parse one query at a time, use
+/// one parser per thread or the thread local default provided by `CqlParser::with_default`.
+pub struct CqlParser {
+    /// the tree-sitter parser configured with the CQL grammar.
+    parser: Parser,
+    /// the flag that cancels the parse in progress.  The tree-sitter parser holds a pointer to
+    /// the flag so it is declared after the parser and is dropped after it.
+    cancellation_flag: Arc<AtomicUsize>,
+    /// the version whose syntax is accepted.
+    dialect: Dialect,
+}
+
+impl CqlParser {
+    /// create a parser.
+    /// Returns an error if the tree-sitter grammar is not compatible with the tree-sitter library.
+    pub fn new() -> Result<CqlParser, ParseError> {
+        let mut parser = Parser::new();
+        if parser.set_language(&tree_sitter_cql::language()).is_err() {
+            return Err(ParseError::without_location(ParseErrorKind::Language));
+        }
+
+        // this code enables debug logging
+        /*
+        fn log( _x : LogType, message : &str) {
+            println!("{}", message );
+        }
+        parser.set_logger( Some( Box::new( log)) );
+        */
+
+        let cancellation_flag = Arc::new(AtomicUsize::new(0));
+        // SAFETY: the flag is kept alive by `cancellation_flag` until after the parser is dropped.
+        unsafe { parser.set_cancellation_flag(Some(&cancellation_flag)) };
+        Ok(CqlParser {
+            parser,
+            cancellation_flag,
+            dialect: Dialect::default(),
+        })
+    }
+
+    /// run `f` with the parser for the current thread.  The parser is created on first use.
+    /// If the thread's parser is already in use (e.g. `f` calls `with_default`) a new parser
+    /// is used instead.
+    /// Returns an error if the parser can not be created.
+    pub fn with_default<R>(f: impl FnOnce(&mut CqlParser) -> R) -> Result<R, ParseError> {
+        DEFAULT_PARSER.with(|default| match default.try_borrow_mut() {
+            Ok(mut default) => {
+                let parser = match default.as_mut() {
+                    Some(parser) => parser,
+                    None => default.insert(CqlParser::new()?),
+                };
+                Ok(f(parser))
+            }
+            Err(_) => Ok(f(&mut CqlParser::new()?)),
+        })
+    }
+
+    /// the maximum time in microseconds a parse may take.  Zero means there is no limit.
+    pub fn timeout_micros(&self) -> u64 {
+        self.parser.timeout_micros()
+    }
+
+    /// set the maximum time in microseconds a parse may take.  Zero means there is no limit.
+    /// A parse that takes longer fails with `ParseErrorKind::Aborted`.
+    pub fn set_timeout_micros(&mut self, timeout_micros: u64) {
+        self.parser.set_timeout_micros(timeout_micros)
+    }
+
+    /// the version whose syntax is accepted.  The default is `Dialect::Cassandra3_11`.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// set the version whose syntax is accepted.  Syntax the dialect does not support is
+    /// reported as `ParseErrorKind::Unsupported`.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect
+    }
+
+    /// the flag that cancels parsing.
+    /// Storing a non-zero value cancels the parse in progress, or the next parse if none is in
+    /// progress, which then fails with `ParseErrorKind::Aborted`.  The flag is cleared when
+    /// the parse stops so the parser can be used again.
+    pub fn cancellation_flag(&self) -> Arc<AtomicUsize> {
+        self.cancellation_flag.clone()
+    }
+
+    /// parse the query string.  This is equivalent to `CassandraAST::try_new`.
+    pub fn parse(&mut self, cassandra_statement: &str) -> Result<CassandraAST, ParseError> {
+        let tree = self.parse_tree(cassandra_statement, None)?;
+        CassandraAST::try_from_tree(cassandra_statement, tree, self.dialect)
+    }
+
+    /// parse the query string into an AST that borrows from the query string.  This is
+    /// equivalent to `borrowed::CassandraAST::try_new`.
+    pub fn parse_borrowed<'a>(
+        &mut self,
+        cassandra_statement: &'a str,
+    ) -> Result<borrowed::CassandraAST<'a>, ParseError> {
+        let tree = self.parse_tree(cassandra_statement, None)?;
+        borrowed::CassandraAST::try_from_tree(cassandra_statement, tree, self.dialect)
+    }
+
+    /// summarize the statements in the query string without building the statements.  This is
+    /// equivalent to `statement_summary::classify`.
+    pub fn classify(
+        &mut self,
+        cassandra_statement: &str,
+    ) -> Result<Vec<StatementSummary>, ParseError> {
+        let tree = self.parse_tree(cassandra_statement, None)?;
+        statement_summary::classify_tree(&tree, cassandra_statement)
+    }
+
+    /// replace the text of the AST between the `start` and `old_end` byte offsets with
+    /// `new_text` and reparse it.  The previous tree is reused by tree-sitter so only the text
+    /// around the edit is parsed again.  The statements the edit did not change are kept, those
+    /// after the edit are moved to their new location, the rest are rebuilt.
+    /// Returns an error, and leaves the AST unchanged, if the range does not fit the text or if
+    /// the new text can not be parsed (see `CqlParser::parse`).
+    pub fn edit(
+        &mut self,
+        ast: &mut CassandraAST,
+        start: usize,
+        old_end: usize,
+        new_text: &str,
+    ) -> Result<(), ParseError> {
+        ast.apply_edit(self, start, old_end, new_text)
+    }
+
+    /// parse the query string into a tree-sitter tree.  Comments are parsed as spaces.
+    /// If the text is an edited version of the text of `old_tree` then `old_tree` must have been
+    /// edited to match.
+    pub(crate) fn parse_tree(
+        &mut self,
+        cassandra_statement: &str,
+        old_tree: Option<&Tree>,
+    ) -> Result<Tree, ParseError> {
+        // the grammar does not accept comments.
+        let blanked = Comment::blank(cassandra_statement, &Comment::find(cassandra_statement));
+        match self.parser.parse(blanked.as_ref(), old_tree) {
+            Some(tree) => Ok(tree),
+            None => {
+                // do not resume the aborted parse on the next call.
+                self.parser.reset();
+                self.cancellation_flag.store(0, Ordering::Relaxed);
+                Err(ParseError::without_location(ParseErrorKind::Aborted))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::cql_parser::CqlParser;
+    use crate::parse_error::ParseErrorKind;
+    use std::sync::atomic::Ordering;
+
+    fn long_query() -> String {
+        "SELECT * FROM foo WHERE bar = 5;".repeat(10_000)
+    }
+
+    #[test]
+    fn test_reuse() {
+        let mut parser = CqlParser::new().unwrap();
+        for query in [
+            "SELECT * FROM foo",
+            "INSERT INTO foo (a) VALUES (1)",
+            "bad query",
+        ] {
+            let ast = parser.parse(query).unwrap();
+            let expected = CassandraAST::new(query);
+            assert_eq!(expected.statements, ast.statements);
+            assert_eq!(expected.errors, ast.errors);
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.cancellation_flag().store(1, Ordering::Relaxed);
+        let error = parser.parse(&long_query()).err().unwrap();
+        assert_eq!(ParseErrorKind::Aborted, error.kind);
+        assert_eq!(0, parser.cancellation_flag().load(Ordering::Relaxed));
+        // the parser starts afresh after a cancelled parse.
+        let ast = parser.parse("SELECT * FROM foo").unwrap();
+        assert!(!ast.has_error());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_timeout_micros(1);
+        assert_eq!(1, parser.timeout_micros());
+        let error = parser.parse(&long_query()).err().unwrap();
+        assert_eq!(ParseErrorKind::Aborted, error.kind);
+        parser.set_timeout_micros(0);
+        assert_eq!(
+            10_000,
+            parser.parse(&long_query()).unwrap().statements.len()
+        );
+    }
+
+    #[test]
+    fn test_nested_default() {
+        let ast = CqlParser::with_default(|outer| {
+            let inner = CqlParser::with_default(|inner| inner.parse("SELECT * FROM bar"))
+                .unwrap()
+                .unwrap();
+            assert!(!inner.has_error());
+            outer.parse("SELECT * FROM foo").unwrap()
+        })
+        .unwrap();
+        assert!(!ast.has_error());
+    }
+
+    #[test]
+    fn test_new_aborted() {
+        let query = long_query();
+        CqlParser::with_default(|parser| parser.cancellation_flag().store(1, Ordering::Relaxed))
+            .unwrap();
+        let mut ast = CassandraAST::new(&query);
+        assert_eq!(1, ast.statements.len());
+        assert!(ast.statements[0].has_error);
+        assert_eq!(
+            CassandraStatement::Unknown(query.clone()),
+            ast.statements[0].statement
+        );
+        assert_eq!(1, ast.errors.len());
+        assert_eq!(ParseErrorKind::Aborted, ast.errors[0].kind);
+
+        // the AST is parsed in full by the next edit.
+        ast.edit(0, query.len(), "SELECT * FROM foo").unwrap();
+        assert!(!ast.has_error());
+        assert_eq!(1, ast.statements.len());
+    }
+}
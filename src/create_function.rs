@@ -1,4 +1,4 @@
-use crate::common::DataType;
+use crate::common::{move_spans, DataType};
 use crate::common::{ColumnDefinition, FQName};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
@@ -25,6 +25,11 @@ pub struct CreateFunction {
     pub code_block: String,
 }
 
+move_spans!(CreateFunction {
+    name, params, return_type;
+    or_replace, not_exists, return_null, language, code_block
+});
+
 impl Display for CreateFunction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,9 +1,12 @@
-use crate::common::{FQName, Identifier};
+use crate::common::{move_spans, FQName, Identifier};
+use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
 /// data to for the create index statement.
 #[derive(PartialEq, Debug, Clone)]
 pub struct CreateIndex {
+    /// if true this is a `CREATE CUSTOM INDEX` statement.
+    pub custom: bool,
     /// only if not exists.
     pub if_not_exists: bool,
     /// optional name of the index.
@@ -12,8 +15,15 @@ pub struct CreateIndex {
     pub table: FQName,
     /// the index column type.
     pub column: IndexColumnType,
+    /// the optional index class from the `USING` clause, e.g. `StorageAttachedIndex` or `sai`.
+    pub using: Option<String>,
+    /// the options from the `WITH OPTIONS` clause as unquoted keys and values, e.g.
+    /// `similarity_function` and `cosine`.
+    pub options: Vec<(String, String)>,
 }
 
+move_spans!(CreateIndex { table; custom, if_not_exists, name, column, using, options });
+
 impl Display for CreateIndex {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let name = if let Some(name) = &self.name {
@@ -29,8 +39,31 @@ impl Display for CreateIndex {
 
         write!(
             f,
-            "CREATE INDEX {}{}ON {}( {} )",
-            exists, name, self.table, self.column
+            "CREATE {}INDEX {}{}ON {}( {} ){}{}",
+            if self.custom { "CUSTOM " } else { "" },
+            exists,
+            name,
+            self.table,
+            self.column,
+            self.using.as_ref().map_or("".to_string(), |x| format!(
+                " USING '{}'",
+                x.replace('\'', "''")
+            )),
+            if self.options.is_empty() {
+                "".to_string()
+            } else {
+                format!(
+                    " WITH OPTIONS = {{{}}}",
+                    self.options
+                        .iter()
+                        .map(|(x, y)| format!(
+                            "'{}':'{}'",
+                            x.replace('\'', "''"),
+                            y.replace('\'', "''")
+                        ))
+                        .join(", ")
+                )
+            }
         )
     }
 }
@@ -46,6 +79,8 @@ pub enum IndexColumnType {
     Entries(Identifier),
     /// use the full column entry.
     Full(Identifier),
+    /// use the values from the column
+    Values(Identifier),
 }
 
 impl Display for IndexColumnType {
@@ -55,6 +90,7 @@ impl Display for IndexColumnType {
             IndexColumnType::Keys(name) => write!(f, "KEYS( {} )", name),
             IndexColumnType::Entries(name) => write!(f, "ENTRIES( {} )", name),
             IndexColumnType::Full(name) => write!(f, "FULL( {} )", name),
+            IndexColumnType::Values(name) => write!(f, "VALUES( {} )", name),
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::common::{FQName, Identifier, PrimaryKey};
+use crate::common::{move_spans, FQName, Identifier, PrimaryKey};
 use crate::common::{RelationElement, WithItem};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
@@ -23,6 +23,11 @@ pub struct CreateMaterializedView {
     pub with_clause: Vec<WithItem>,
 }
 
+move_spans!(CreateMaterializedView {
+    name, table, where_clause, with_clause;
+    if_not_exists, columns, key
+});
+
 impl Display for CreateMaterializedView {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
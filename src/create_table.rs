@@ -1,4 +1,5 @@
-use crate::common::{ColumnDefinition, FQName, PrimaryKey, WithItem};
+use crate::comment::Comment;
+use crate::common::{move_spans, ColumnDefinition, FQName, PrimaryKey, WithItem};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -17,11 +18,36 @@ pub struct CreateTable {
     pub with_clause: Vec<WithItem>,
 }
 
+move_spans!(CreateTable { name, columns, with_clause; if_not_exists, key });
+
 impl Display for CreateTable {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut v: Vec<String> = self.columns.iter().map(|x| x.to_string()).collect();
+        let mut v: Vec<(String, Option<&Comment>)> = self
+            .columns
+            .iter()
+            .map(|x| (x.to_string(), x.comment.as_ref()))
+            .collect();
         if let Some(key) = &self.key {
-            v.push(key.to_string());
+            v.push((key.to_string(), None));
+        }
+        let mut columns = String::new();
+        for (idx, (column, comment)) in v.iter().enumerate() {
+            let last = idx + 1 == v.len();
+            columns.push_str(column);
+            if !last {
+                columns.push(',');
+            }
+            if let Some(comment) = comment {
+                columns.push_str(&format!(" {}", comment));
+                if comment.is_line_comment() {
+                    // the line comment would hide the rest of the statement.
+                    columns.push('\n');
+                    continue;
+                }
+            }
+            if !last {
+                columns.push(' ');
+            }
         }
         write!(
             f,
@@ -32,7 +58,7 @@ impl Display for CreateTable {
                 "".to_string()
             },
             self.name,
-            v.join(", "),
+            columns,
             if !self.with_clause.is_empty() {
                 format!(
                     " WITH {}",
@@ -1,4 +1,4 @@
-use crate::common::FQName;
+use crate::common::{move_spans, FQName};
 use std::fmt::{Display, Formatter};
 
 /// data for the `CreateTrigger` statement.
@@ -12,6 +12,8 @@ pub struct CreateTrigger {
     pub class: String,
 }
 
+move_spans!(CreateTrigger { name; not_exists, class });
+
 impl Display for CreateTrigger {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
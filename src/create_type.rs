@@ -1,4 +1,4 @@
-use crate::common::{ColumnDefinition, FQName};
+use crate::common::{move_spans, ColumnDefinition, FQName};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -13,6 +13,8 @@ pub struct CreateType {
     pub columns: Vec<ColumnDefinition>,
 }
 
+move_spans!(CreateType { name, columns; not_exists });
+
 impl Display for CreateType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
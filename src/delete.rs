@@ -1,27 +1,42 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, Identifier, RelationElement};
+use crate::common::{
+    eq_without_spans, move_spans, Bindable, FQName, Identifier, RelationElement, Span,
+};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
 /// the data for a delete statement.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Delete {
-    /// if set the statement starts with `BEGIN BATCH`
+    /// if set the statement starts with `BEGIN BATCH`.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
     pub begin_batch: Option<BeginBatch>,
     /// an optional list of columns to delete
     pub columns: Vec<IndexedColumn>,
     /// the table to delete from
     pub table_name: FQName,
     /// an optional timestamp to use for the deletion.
-    pub timestamp: Option<u64>,
+    pub timestamp: Option<Bindable<u64>>,
     /// the were clause for the delete.
     pub where_clause: Vec<RelationElement>,
     /// if present a list of key,values for the `IF` clause
     pub if_clause: Vec<RelationElement>,
     /// if true and if_clause is NONE then `IF EXISTS` is added
     pub if_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
 }
 
+eq_without_spans!(Delete {
+    begin_batch, columns, table_name, timestamp, where_clause, if_clause, if_exists;
+    span
+});
+
+move_spans!(Delete {
+    columns, table_name, where_clause, if_clause, span;
+    begin_batch, timestamp, if_exists
+});
+
 impl Display for Delete {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -56,14 +71,22 @@ impl Display for Delete {
 
 /// Defines an indexed column.  Indexed columns comprise a column name and an optional index into
 /// the column.  This is expressed as `column[idx]`
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct IndexedColumn {
     /// the column name
     pub column: Identifier,
     /// the optional index in to the column
     pub idx: Option<String>,
+    /// the location of the column in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub column_span: Option<Span>,
 }
 
+eq_without_spans!(IndexedColumn { column, idx; span, column_span });
+
+move_spans!(IndexedColumn { span, column_span; column, idx });
+
 impl Display for IndexedColumn {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.idx {
@@ -0,0 +1,855 @@
+use crate::cassandra_ast::BorrowedParser;
+use crate::parse_error::{ParseError, ParseErrorKind};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use tree_sitter::Node;
+
+/// a word of a statement: the bytes it is at and its text in upper case, see `Feature::words`.
+type Word = (Range<usize>, String);
+
+/// The database version whose CQL is being parsed.
+///
+/// The grammar accepts the CQL of Cassandra 3.11.  Syntax it accepts that the dialect does not
+/// support, and syntax of other versions that it does not accept, is reported as
+/// `ParseErrorKind::Unsupported` rather than as a plain syntax error.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Dialect {
+    Cassandra3_11,
+    Cassandra4_0,
+    Cassandra4_1,
+    Cassandra5_0,
+    ScyllaDb,
+}
+
+/// Syntax that is only supported by some dialects.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Feature {
+    /// `WITH COMPACT STORAGE` when creating a table.
+    CompactStorage,
+    /// functions written in Java.
+    JavaFunctions,
+    /// functions written in JavaScript.
+    JavaScriptFunctions,
+    /// `IF EXISTS` and `IF NOT EXISTS` in `ALTER TABLE` and `ALTER TYPE`.
+    AlterIfExists,
+    /// the `vector<type, dimension>` data type.
+    VectorType,
+    /// `ORDER BY column ANN OF [...]` in `SELECT`.
+    AnnOrdering,
+    /// `OR` in `WHERE` clauses.
+    WhereOr,
+    /// the `NOT IN` relation operator.
+    NotIn,
+    /// the `NOT CONTAINS` and `NOT CONTAINS KEY` relation operators.
+    NotContains,
+    /// `BYPASS CACHE` in `SELECT`.
+    BypassCache,
+    /// `USING TIMEOUT` in statements.
+    UsingTimeout,
+    /// masked columns and the `UNMASK` and `SELECT_MASKED` permissions.
+    DataMasking,
+    /// the `MAXWRITETIME(column)` selector.
+    MaxWritetime,
+    /// storage-attached indexes, `CREATE INDEX ... USING 'sai'`.
+    StorageAttachedIndex,
+}
+
+impl Dialect {
+    /// true if the dialect supports the feature.
+    pub fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::CompactStorage => {
+                matches!(self, Dialect::Cassandra3_11 | Dialect::ScyllaDb)
+            }
+            Feature::JavaFunctions => *self != Dialect::ScyllaDb,
+            // deprecated in Cassandra 4.1 and removed in 5.0.
+            Feature::JavaScriptFunctions => matches!(
+                self,
+                Dialect::Cassandra3_11 | Dialect::Cassandra4_0 | Dialect::Cassandra4_1
+            ),
+            Feature::AlterIfExists => {
+                matches!(self, Dialect::Cassandra4_1 | Dialect::Cassandra5_0)
+            }
+            Feature::VectorType
+            | Feature::AnnOrdering
+            | Feature::DataMasking
+            | Feature::WhereOr
+            | Feature::NotIn
+            | Feature::NotContains
+            | Feature::MaxWritetime
+            | Feature::StorageAttachedIndex => *self == Dialect::Cassandra5_0,
+            Feature::BypassCache | Feature::UsingTimeout => *self == Dialect::ScyllaDb,
+        }
+    }
+
+    /// find the syntax in the tree that the dialect does not support.  The syntax errors in
+    /// `errors` that are caused by syntax of a specific version are changed to `Unsupported`.
+    /// Returns the errors for the syntax the grammar accepts but the dialect does not support.
+    pub(crate) fn check(
+        &self,
+        root: &Node,
+        source: &str,
+        errors: &mut [ParseError],
+    ) -> Vec<ParseError> {
+        let mut result = vec![];
+        let mut statement: Option<Node> = None;
+        let mut cursor = root.walk();
+        let mut process = cursor.goto_first_child();
+        while process {
+            let node = cursor.node();
+            if node.kind().eq(";") {
+                statement = None;
+            } else if node.is_error() {
+                // text the grammar does not accept after a statement belongs to that statement.
+                self.check_error(&node, statement.as_ref(), source, errors);
+            } else {
+                self.check_node(&node, &node, source, errors, &mut result);
+                statement = Some(node);
+            }
+            process = cursor.goto_next_sibling();
+        }
+        result
+    }
+
+    /// check the node of the statement and its children.
+    fn check_node(
+        &self,
+        node: &Node,
+        statement: &Node,
+        source: &str,
+        errors: &mut [ParseError],
+        result: &mut Vec<ParseError>,
+    ) {
+        if node.is_error() {
+            self.check_error(node, Some(statement), source, errors);
+            return;
+        }
+        if let Some(feature) = Feature::of_node(node, source) {
+            if !self.supports(feature) {
+                result.push(ParseError::unsupported(node, source, feature, *self));
+            }
+        }
+        let mut cursor = node.walk();
+        let mut process = cursor.goto_first_child();
+        while process {
+            self.check_node(&cursor.node(), statement, source, errors, result);
+            process = cursor.goto_next_sibling();
+        }
+    }
+
+    /// change the syntax error for the `ERROR` node to `Unsupported` if it is caused by syntax
+    /// of a specific version.  The error then starts at the keywords of the syntax and covers
+    /// them.
+    fn check_error(
+        &self,
+        node: &Node,
+        statement: Option<&Node>,
+        source: &str,
+        errors: &mut [ParseError],
+    ) {
+        let feature = Feature::of_error(node, statement, source).or_else(|| {
+            statement.and_then(|statement| Feature::of_where_error(statement, node, source))
+        });
+        if let Some((feature, keywords)) = feature {
+            for error in errors.iter_mut().filter(|x| {
+                x.kind == ParseErrorKind::Error
+                    && x.start_byte == node.start_byte()
+                    && x.end_byte == node.end_byte()
+            }) {
+                error.kind = ParseErrorKind::Unsupported(feature, *self);
+                let end_byte = error.end_byte.max(keywords.end);
+                error.move_to(keywords.start, end_byte, source);
+            }
+        }
+    }
+}
+
+/// Cassandra 3.11, the version whose CQL the grammar accepts.
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect::Cassandra3_11
+    }
+}
+
+impl Display for Dialect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dialect::Cassandra3_11 => write!(f, "Cassandra 3.11"),
+            Dialect::Cassandra4_0 => write!(f, "Cassandra 4.0"),
+            Dialect::Cassandra4_1 => write!(f, "Cassandra 4.1"),
+            Dialect::Cassandra5_0 => write!(f, "Cassandra 5.0"),
+            Dialect::ScyllaDb => write!(f, "ScyllaDB"),
+        }
+    }
+}
+
+impl Feature {
+    /// the feature used by a node the grammar accepts.
+    fn of_node(node: &Node, source: &str) -> Option<Feature> {
+        match node.kind() {
+            "compact_storage" => Some(Feature::CompactStorage),
+            "create_function" => {
+                let mut cursor = node.walk();
+                let mut process = cursor.goto_first_child();
+                while process && !cursor.node().kind().eq("LANGUAGE") {
+                    process = cursor.goto_next_sibling();
+                }
+                if !process || !cursor.goto_next_sibling() {
+                    return None;
+                }
+                let language = source
+                    .get(cursor.node().start_byte()..cursor.node().end_byte())
+                    .unwrap_or_default();
+                if language.eq_ignore_ascii_case("java") {
+                    Some(Feature::JavaFunctions)
+                } else if language.eq_ignore_ascii_case("javascript") {
+                    Some(Feature::JavaScriptFunctions)
+                } else {
+                    None
+                }
+            }
+            // `MAXWRITETIME` is only a selector when it is not qualified by a keyspace.
+            "function_name" => {
+                let name = source
+                    .get(node.start_byte()..node.end_byte())
+                    .unwrap_or_default();
+                let selector = std::iter::successors(node.parent(), Node::parent)
+                    .map(|x| x.kind())
+                    .take_while(|x| !x.eq(&"select_statement"))
+                    .any(|x| x.eq("select_elements"));
+                let qualified = node.next_sibling().map_or(false, |x| x.kind().ne("("));
+                if name.eq_ignore_ascii_case("MAXWRITETIME") && selector && !qualified {
+                    Some(Feature::MaxWritetime)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// the feature that caused the syntax error of the `ERROR` node and the bytes of its
+    /// keywords.  `statement` is the statement the error is in or follows.
+    fn of_error(
+        node: &Node,
+        statement: Option<&Node>,
+        source: &str,
+    ) -> Option<(Feature, Range<usize>)> {
+        let mut words = statement.map_or(vec![], |x| Feature::words(x, source));
+        if statement.map_or(true, |x| x.end_byte() <= node.start_byte()) {
+            words.extend(Feature::words(node, source));
+        }
+        let kind = statement.map_or("", |x| x.kind());
+        let word = |idx: usize| words.get(idx).map_or("", |x| x.1.as_str());
+        let is_name =
+            |word: &str| !word.is_empty() && word.chars().all(|x| x.is_alphanumeric() || x == '_');
+        if let Some(clause) = Feature::masking_clause(&words, node) {
+            return Some((Feature::DataMasking, clause));
+        }
+        for idx in 0..words.len() {
+            // the feature and the first and last words of its keywords.
+            let (feature, first, last) = match (word(idx), word(idx + 1)) {
+                ("BYPASS", "CACHE") => (Feature::BypassCache, idx, idx + 1),
+                ("USING", "TIMEOUT") => (Feature::UsingTimeout, idx, idx + 1),
+                // the timeout may follow another value of the `USING` clause.
+                ("AND", "TIMEOUT") if words[..idx].iter().any(|x| x.1.eq("USING")) => {
+                    (Feature::UsingTimeout, idx + 1, idx + 1)
+                }
+                ("ANN", "OF") => (Feature::AnnOrdering, idx, idx + 1),
+                ("IF", "EXISTS") if matches!(kind, "alter_table" | "alter_type") => {
+                    (Feature::AlterIfExists, idx, idx + 1)
+                }
+                ("IF", "NOT")
+                    if matches!(kind, "alter_table" | "alter_type")
+                        && word(idx + 2) == "EXISTS" =>
+                {
+                    (Feature::AlterIfExists, idx, idx + 2)
+                }
+                // the grammar reads `vector<float, 3>` as a collection and fails at the
+                // dimension.
+                ("VECTOR", "<") if is_name(word(idx + 2)) && word(idx + 3) == "," => {
+                    let last = if word(idx + 5) == ">" {
+                        idx + 5
+                    } else {
+                        idx + 3
+                    };
+                    (Feature::VectorType, idx, last)
+                }
+                // the grammar does not accept the `USING` clause of a create index statement.
+                ("USING", class) if kind.eq("create_index") => {
+                    let class = class.trim_matches('\'');
+                    if class.eq("SAI")
+                        || class.eq("STORAGEATTACHEDINDEX")
+                        || class.ends_with(".STORAGEATTACHEDINDEX")
+                    {
+                        (Feature::StorageAttachedIndex, idx, idx + 1)
+                    } else {
+                        continue;
+                    }
+                }
+                _ => continue,
+            };
+            let keywords = words[first].0.start..words[last].0.end;
+            if keywords.start < node.end_byte() && node.start_byte() < keywords.end {
+                return Some((feature, keywords));
+            }
+        }
+        None
+    }
+
+    /// the Cassandra 5.0 `WHERE` clause syntax that caused a syntax error in a select
+    /// statement, and the bytes of its keyword: an `OR`, or a `NOT` that is followed by `IN`
+    /// or `CONTAINS`, within the error.
+    /// The keywords are found as tokens so text in string literals is not read.  An `OR` is
+    /// only the feature if the condition of the where clause it is in can be read and is
+    /// followed by the end of the error or another clause, otherwise it is a syntax error in
+    /// every dialect, e.g. when it is not followed by a condition or the condition starts with
+    /// `NOT`.
+    fn of_where_error(
+        statement: &Node,
+        node: &Node,
+        source: &str,
+    ) -> Option<(Feature, Range<usize>)> {
+        if !statement.kind().eq("select_statement") {
+            return None;
+        }
+        let next_word = |token: &Node| {
+            source
+                .get(token.end_byte()..)
+                .unwrap_or_default()
+                .trim_start()
+                .chars()
+                .take_while(|x| x.is_ascii_alphabetic())
+                .collect::<String>()
+                .to_uppercase()
+        };
+        let mut feature = None;
+        for token in Feature::tokens(node) {
+            match token.kind() {
+                "OR" => {
+                    // the error may be within the statement or follow it.
+                    let condition = Feature::tokens(statement)
+                        .into_iter()
+                        .chain(Feature::tokens(node))
+                        .filter(|x| x.kind().eq("WHERE") && x.end_byte() <= token.start_byte())
+                        .map(|x| x.end_byte())
+                        .max()?;
+                    let text = source.get(condition..).unwrap_or_default();
+                    let (_, rest) = BorrowedParser::parse_unparsed_where(text, source.len())?;
+                    // the condition must include the `OR` and be followed by a clause.
+                    let clause = rest.split_whitespace().next().map(str::to_uppercase);
+                    let followed = source.len() - rest.len() >= node.end_byte()
+                        || matches!(
+                            clause.as_deref(),
+                            Some("GROUP" | "ORDER" | "PER" | "LIMIT" | "ALLOW")
+                        );
+                    if source.len() - rest.len() < token.end_byte() || !followed {
+                        return None;
+                    }
+                    feature = feature.or(Some((Feature::WhereOr, token.byte_range())));
+                }
+                "NOT" if next_word(&token).eq("IN") => {
+                    return Some((Feature::NotIn, token.byte_range()))
+                }
+                "NOT" if next_word(&token).eq("CONTAINS") => {
+                    return Some((Feature::NotContains, token.byte_range()))
+                }
+                _ => {}
+            }
+        }
+        feature
+    }
+
+    /// the leaf nodes of the node in the order they are in the text.
+    fn tokens<'t>(node: &Node<'t>) -> Vec<Node<'t>> {
+        let mut result = vec![];
+        let mut cursor = node.walk();
+        loop {
+            if !cursor.goto_first_child() {
+                result.push(cursor.node());
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() || cursor.node() == *node {
+                        return result;
+                    }
+                }
+            }
+        }
+    }
+
+    /// the words of the node in the order they are in the text, in upper case, with the bytes
+    /// they are at.  The tokens of the node are its words, except that tokens the grammar read
+    /// within a word are joined, e.g. `BY` and `PASS` of `BYPASS` or `SELECT` and `_MASKED` of
+    /// `SELECT_MASKED`, and an error token, or the text an error skipped between its tokens, is
+    /// split into the words it holds.
+    fn words(node: &Node, source: &str) -> Vec<Word> {
+        let split = |start: usize, end: usize| {
+            let mut offset = start;
+            let mut parts = vec![];
+            for part in source
+                .get(start..end)
+                .unwrap_or_default()
+                .split(|x: char| x.is_ascii_whitespace())
+            {
+                parts.push((offset, part));
+                offset += part.len() + 1;
+            }
+            parts
+        };
+        let mut parts = vec![];
+        let mut end = node.start_byte();
+        for token in Feature::tokens(node) {
+            parts.extend(split(end, token.start_byte()));
+            if token.is_error() {
+                parts.extend(split(token.start_byte(), token.end_byte()));
+            } else {
+                parts.push((
+                    token.start_byte(),
+                    source
+                        .get(token.start_byte()..token.end_byte())
+                        .unwrap_or_default(),
+                ));
+            }
+            end = end.max(token.end_byte());
+        }
+        parts.extend(split(end, node.end_byte()));
+        let is_word = |text: &str| text.chars().all(|x| x.is_alphanumeric() || x == '_');
+        let mut result: Vec<Word> = vec![];
+        for (start, part) in parts.into_iter().filter(|(_, x)| !x.is_empty()) {
+            match result.last_mut() {
+                Some((range, word)) if range.end == start && is_word(word) && is_word(part) => {
+                    range.end = start + part.len();
+                    word.push_str(&part.to_uppercase());
+                }
+                _ => result.push((start..start + part.len(), part.to_uppercase())),
+            }
+        }
+        result
+    }
+
+    /// the bytes of the data masking clause the error is within.  The clauses are `MASKED WITH function` in a column definition, `ALTER column MASKED WITH
+    /// function` or `ALTER column DROP MASKED` in `ALTER TABLE`, and the `UNMASK` or
+    /// `SELECT_MASKED` permission of `GRANT` or `REVOKE`.
+    fn masking_clause(words: &[Word], error: &Node) -> Option<Range<usize>> {
+        let word = |idx: usize| words.get(idx).map_or("", |x| x.1.as_str());
+        // the first and last words of the clauses.
+        let mut clauses = vec![];
+        match (word(0), word(1)) {
+            ("CREATE", "TABLE") | ("ALTER", "TABLE") => {
+                for idx in 0..words.len() {
+                    let altered = idx >= 2 && word(idx - 2) == "ALTER";
+                    match (word(idx), word(idx + 1)) {
+                        ("MASKED", "WITH") if altered => clauses.push((idx - 2, idx + 1)),
+                        ("MASKED", "WITH") => clauses.push((idx, idx + 1)),
+                        ("DROP", "MASKED") if altered => clauses.push((idx - 2, idx + 1)),
+                        _ => {}
+                    }
+                }
+            }
+            ("GRANT", "UNMASK")
+            | ("GRANT", "SELECT_MASKED")
+            | ("REVOKE", "UNMASK")
+            | ("REVOKE", "SELECT_MASKED") => clauses.push((1, 1)),
+            _ => {}
+        }
+        clauses
+            .into_iter()
+            .map(|(first, last)| words[first].0.start..words[last].0.end)
+            .find(|x| x.start < error.end_byte() && error.start_byte() < x.end)
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Feature::CompactStorage => write!(f, "COMPACT STORAGE"),
+            Feature::JavaFunctions => write!(f, "Java functions"),
+            Feature::JavaScriptFunctions => write!(f, "JavaScript functions"),
+            Feature::AlterIfExists => write!(f, "IF EXISTS in ALTER statements"),
+            Feature::VectorType => write!(f, "the vector type"),
+            Feature::AnnOrdering => write!(f, "ANN OF"),
+            Feature::WhereOr => write!(f, "OR in WHERE clauses"),
+            Feature::NotIn => write!(f, "NOT IN"),
+            Feature::NotContains => write!(f, "NOT CONTAINS"),
+            Feature::MaxWritetime => write!(f, "MAXWRITETIME"),
+            Feature::StorageAttachedIndex => write!(f, "storage-attached indexes"),
+            Feature::BypassCache => write!(f, "BYPASS CACHE"),
+            Feature::UsingTimeout => write!(f, "USING TIMEOUT"),
+            Feature::DataMasking => write!(f, "data masking"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::common::{Bindable, CqlDuration, TtlTimestamp};
+    use crate::cql_parser::CqlParser;
+    use crate::dialect::{Dialect, Feature};
+    use crate::parse_error::{ParseErrorKind, Position};
+
+    fn kinds(dialect: Dialect, query: &str) -> Vec<ParseErrorKind> {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(dialect);
+        let ast = parser.parse(query).unwrap();
+        ast.errors.into_iter().map(|x| x.kind).collect()
+    }
+
+    #[test]
+    fn test_supported_syntax() {
+        let query = "CREATE TABLE t (a int PRIMARY KEY) WITH COMPACT STORAGE";
+        assert!(kinds(Dialect::Cassandra3_11, query).is_empty());
+        assert!(kinds(Dialect::ScyllaDb, query).is_empty());
+        assert_eq!(
+            vec![ParseErrorKind::Unsupported(
+                Feature::CompactStorage,
+                Dialect::Cassandra4_0
+            )],
+            kinds(Dialect::Cassandra4_0, query)
+        );
+
+        let query = "CREATE FUNCTION f (a int) RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE javascript AS $$ a $$";
+        assert!(kinds(Dialect::Cassandra4_0, query).is_empty());
+        assert!(kinds(Dialect::Cassandra4_1, query).is_empty());
+        assert_eq!(
+            vec![ParseErrorKind::Unsupported(
+                Feature::JavaScriptFunctions,
+                Dialect::Cassandra5_0
+            )],
+            kinds(Dialect::Cassandra5_0, query)
+        );
+
+        let query = "SELECT MAXWRITETIME(a), CAST(maxwritetime(b) AS text) FROM t";
+        assert!(kinds(Dialect::Cassandra5_0, query).is_empty());
+        assert_eq!(
+            vec![
+                ParseErrorKind::Unsupported(Feature::MaxWritetime, Dialect::Cassandra4_1),
+                ParseErrorKind::Unsupported(Feature::MaxWritetime, Dialect::Cassandra4_1)
+            ],
+            kinds(Dialect::Cassandra4_1, query)
+        );
+        // a function of a keyspace, or outside the selectors, is not the selector.
+        assert!(kinds(
+            Dialect::Cassandra4_1,
+            "SELECT ks.maxwritetime(a) FROM t WHERE b = maxwritetime(c)"
+        )
+        .is_empty());
+
+        for query in [
+            "ALTER TABLE IF EXISTS t ADD c int",
+            "ALTER TABLE IF EXISTS ks.t ALTER b MASKED WITH mask_null()",
+            "ALTER TYPE IF EXISTS ks.u ADD c int",
+        ] {
+            assert!(kinds(Dialect::Cassandra5_0, query).is_empty(), "{}", query);
+            let mut parser = CqlParser::new().unwrap();
+            parser.set_dialect(Dialect::Cassandra5_0);
+            let ast = parser.parse(query).unwrap();
+            assert!(!ast.statements[0].has_error, "{}", query);
+            assert_eq!(
+                query.replace(" int", " INT"),
+                ast.statements[0].statement.to_string()
+            );
+        }
+        assert!(kinds(Dialect::Cassandra4_1, "ALTER TABLE IF EXISTS t ADD c int").is_empty());
+        assert!(kinds(
+            Dialect::Cassandra5_0,
+            "ALTER TABLE t ADD c text MASKED WITH mask_default(), d int"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_statement_marked() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::Cassandra5_0);
+        let ast = parser
+            .parse("USE ks; CREATE TABLE t (a int PRIMARY KEY) WITH COMPACT STORAGE")
+            .unwrap();
+        assert!(!ast.statements[0].has_error);
+        assert!(ast.statements[1].has_error);
+        assert_eq!(
+            "COMPACT STORAGE is not supported in Cassandra 5.0 at line 1, column 49 in create_table",
+            ast.errors[0].to_string()
+        );
+    }
+
+    #[test]
+    fn test_unaccepted_syntax() {
+        for (query, feature) in [
+            ("SELECT * FROM t BYPASS CACHE", Feature::BypassCache),
+            ("SELECT * FROM t USING TIMEOUT 5s", Feature::UsingTimeout),
+            ("ALTER TABLE IF EXISTS t ADD c int", Feature::AlterIfExists),
+            (
+                "ALTER TABLE t ADD IF NOT EXISTS c int",
+                Feature::AlterIfExists,
+            ),
+            (
+                "CREATE TABLE t (a int PRIMARY KEY, v vector<float, 3>)",
+                Feature::VectorType,
+            ),
+            (
+                "SELECT * FROM t ORDER BY v ANN OF [0.1, 0.2] LIMIT 3",
+                Feature::AnnOrdering,
+            ),
+            ("SELECT * FROM t WHERE a = 1 OR b = 2", Feature::WhereOr),
+            (
+                "SELECT * FROM t WHERE a = 1 AND (b = 2 OR c = 3) LIMIT 4",
+                Feature::WhereOr,
+            ),
+            ("SELECT * FROM t WHERE (a = 1 OR b = 2)", Feature::WhereOr),
+            ("SELECT * FROM t WHERE a NOT IN (1, 2)", Feature::NotIn),
+            (
+                "SELECT * FROM t WHERE s NOT CONTAINS 1",
+                Feature::NotContains,
+            ),
+            (
+                "SELECT * FROM t WHERE m NOT CONTAINS KEY 'k' AND a = 1",
+                Feature::NotContains,
+            ),
+            (
+                "CREATE TABLE t (a int PRIMARY KEY, b text MASKED WITH mask_default())",
+                Feature::DataMasking,
+            ),
+            (
+                "CREATE CUSTOM INDEX ON t (a) USING 'StorageAttachedIndex'",
+                Feature::StorageAttachedIndex,
+            ),
+            (
+                "CREATE INDEX i ON t (a) USING 'sai' WITH OPTIONS = {'case_sensitive': 'false'}",
+                Feature::StorageAttachedIndex,
+            ),
+            (
+                "CREATE CUSTOM INDEX ON t (a) USING 'org.apache.cassandra.index.sai.StorageAttachedIndex'",
+                Feature::StorageAttachedIndex,
+            ),
+            (
+                "CREATE CUSTOM INDEX ON t (VALUES(m)) USING 'StorageAttachedIndex'",
+                Feature::StorageAttachedIndex,
+            ),
+            ("GRANT UNMASK ON TABLE t TO r", Feature::DataMasking),
+            ("GRANT SELECT_MASKED ON TABLE t TO r", Feature::DataMasking),
+            ("REVOKE UNMASK ON TABLE t FROM r", Feature::DataMasking),
+            ("ALTER TABLE t ALTER b DROP MASKED", Feature::DataMasking),
+            (
+                "ALTER TABLE t ADD c text MASKED WITH mask_default()",
+                Feature::DataMasking,
+            ),
+        ] {
+            assert_eq!(
+                vec![ParseErrorKind::Unsupported(feature, Dialect::Cassandra4_0)],
+                kinds(Dialect::Cassandra4_0, query),
+                "{}",
+                query
+            );
+        }
+        assert_eq!(
+            vec![ParseErrorKind::Error],
+            kinds(Dialect::Cassandra4_0, "SELECT * FROM t BYPASS")
+        );
+        // the masking keywords are only data masking within a masking clause.
+        assert_eq!(
+            vec![ParseErrorKind::Error],
+            kinds(Dialect::Cassandra4_0, "SELECT * FROM t WHERE a = masked b")
+        );
+        assert!(kinds(Dialect::Cassandra4_0, "ALTER TABLE t DROP masked").is_empty());
+        // every dialect supports these so they are not reported once they are read, and an
+        // error in them is a syntax error.
+        for query in [
+            "SELECT a, count(*) FROM t GROUP BY a",
+            "SELECT * FROM t PER PARTITION LIMIT 1",
+        ] {
+            assert!(kinds(Dialect::Cassandra3_11, query).is_empty(), "{}", query);
+            assert!(kinds(Dialect::Cassandra4_0, query).is_empty(), "{}", query);
+        }
+        for query in [
+            "SELECT a FROM t GROUP BY a junk",
+            "SELECT a FROM t GROUP BY 3 PER PARTITION LIMIT 3",
+            "SELECT a FROM t PER PARTITION zzz LIMIT 3",
+        ] {
+            let kinds = kinds(Dialect::Cassandra3_11, query);
+            assert!(!kinds.is_empty(), "{}", query);
+            assert!(
+                kinds.iter().all(|x| *x == ParseErrorKind::Error),
+                "{}",
+                query
+            );
+        }
+        // other index classes are supported by every dialect.
+        assert!(kinds(
+            Dialect::Cassandra3_11,
+            "CREATE CUSTOM INDEX ON t (a) USING 'org.apache.cassandra.index.sasi.SASIIndex'"
+        )
+        .is_empty());
+        assert!(kinds(
+            Dialect::Cassandra5_0,
+            "CREATE CUSTOM INDEX ON t (a) USING 'StorageAttachedIndex'"
+        )
+        .is_empty());
+        // the where clause keywords are only found as tokens.
+        assert!(kinds(
+            Dialect::Cassandra3_11,
+            "SELECT * FROM t WHERE a = 'OR' AND b = 'NOT IN' LIMIT ?"
+        )
+        .is_empty());
+        for query in [
+            "SELECT * FROM t WHERE a = 1 OR b = 2",
+            "SELECT * FROM t WHERE a NOT IN (1, 2) AND s NOT CONTAINS 1",
+        ] {
+            assert!(kinds(Dialect::Cassandra5_0, query).is_empty(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_scylla_extensions() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::ScyllaDb);
+        for query in [
+            "SELECT * FROM t BYPASS CACHE",
+            "SELECT * FROM t USING TIMEOUT 5s",
+            "SELECT a FROM t WHERE a = 1 LIMIT 2 ALLOW FILTERING BYPASS CACHE USING TIMEOUT ?",
+            "INSERT INTO t (a) VALUES (1) USING TIMEOUT 500ms",
+            "INSERT INTO t (a) VALUES (1) USING TTL 5 AND TIMEOUT 1m30s",
+            "UPDATE t USING TIMEOUT 5s SET a = 1 WHERE b = 2",
+            "UPDATE t USING TIMESTAMP 3 AND TIMEOUT :timeout SET a = 1 WHERE b = 2",
+        ] {
+            let ast = parser.parse(query).unwrap();
+            assert!(ast.errors.is_empty(), "{}", query);
+            assert_eq!(1, ast.statements.len(), "{}", query);
+            assert!(!ast.statements[0].has_error, "{}", query);
+            assert_eq!(query, ast.statements[0].statement.to_string());
+            // the statement is read the same way in other dialects, which report the clause.
+            assert_eq!(
+                vec![ParseErrorKind::Unsupported(
+                    if query.contains("BYPASS") {
+                        Feature::BypassCache
+                    } else {
+                        Feature::UsingTimeout
+                    },
+                    Dialect::Cassandra5_0
+                )],
+                kinds(Dialect::Cassandra5_0, query),
+                "{}",
+                query
+            );
+        }
+        let ast = parser
+            .parse("SELECT * FROM t BYPASS CACHE USING TIMEOUT 5s")
+            .unwrap();
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                assert!(select.bypass_cache);
+                assert_eq!(
+                    Some(Bindable::Literal(CqlDuration::parse("5s").unwrap())),
+                    select.timeout
+                );
+            }
+            statement => panic!("not a select: {}", statement),
+        }
+        let ast = parser
+            .parse("UPDATE t USING TTL 3 AND TIMEOUT ? SET a = 1 WHERE b = 2")
+            .unwrap();
+        match &ast.statements[0].statement {
+            CassandraStatement::Update(update) => assert_eq!(
+                Some(TtlTimestamp {
+                    ttl: Some(Bindable::Literal(3)),
+                    timestamp: None,
+                    timeout: Some(Bindable::Param("?".to_string())),
+                }),
+                update.using_ttl
+            ),
+            statement => panic!("not an update: {}", statement),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_location() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::Cassandra4_0);
+        for (query, line, column, text) in [
+            ("SELECT * FROM t BYPASS CACHE", 1, 17, "BYPASS CACHE"),
+            (
+                "UPDATE t\n  USING TIMEOUT 5s SET a = 1 WHERE b = 2",
+                2,
+                3,
+                "USING TIMEOUT 5s",
+            ),
+            (
+                "INSERT INTO t (a) VALUES (1) USING TTL 5 AND TIMEOUT 5s",
+                1,
+                46,
+                "TIMEOUT 5s",
+            ),
+            ("ALTER TABLE IF EXISTS t ADD c int", 1, 13, "IF EXISTS t"),
+            (
+                "CREATE TABLE t (\n  a int PRIMARY KEY,\n  v vector<float, 3>\n)",
+                3,
+                5,
+                "vector<float, 3>",
+            ),
+            (
+                "SELECT * FROM t ORDER BY v ANN OF [0.1, 0.2] LIMIT 3",
+                1,
+                28,
+                "ANN OF [0.1, 0.2]",
+            ),
+            ("SELECT * FROM t WHERE a NOT IN (1, 2)", 1, 25, "NOT"),
+            (
+                "SELECT * FROM t WHERE a = 1 AND (b = 2 OR c = 3) LIMIT 4",
+                1,
+                40,
+                "OR c = 3)",
+            ),
+            (
+                "ALTER TABLE t ALTER b DROP MASKED",
+                1,
+                15,
+                "ALTER b DROP MASKED",
+            ),
+            (
+                "CREATE CUSTOM INDEX ON t (a) USING 'sai'",
+                1,
+                30,
+                "USING 'sai'",
+            ),
+        ] {
+            let ast = parser.parse(query).unwrap();
+            assert_eq!(1, ast.errors.len(), "{}", query);
+            let error = &ast.errors[0];
+            assert!(
+                matches!(error.kind, ParseErrorKind::Unsupported(_, _)),
+                "{}",
+                query
+            );
+            assert_eq!(Position { line, column }, error.start, "{}", query);
+            assert_eq!(text, error.text, "{}", query);
+            assert_eq!(text, &query[error.start_byte..error.end_byte], "{}", query);
+        }
+        // a word that only ends with `vector` is not the vector type.
+        assert_eq!(
+            vec![ParseErrorKind::Error],
+            kinds(
+                Dialect::Cassandra4_0,
+                "CREATE TABLE t (a int PRIMARY KEY, v myvector<float, 3>)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_messages() {
+        let mut parser = CqlParser::new().unwrap();
+        parser.set_dialect(Dialect::ScyllaDb);
+        // the timeout of a delete statement is not read.
+        let ast = parser
+            .parse("DELETE FROM t USING TIMEOUT 5s WHERE a = 1")
+            .unwrap();
+        assert_eq!(
+            "USING TIMEOUT is not supported by the grammar at line 1, column 15 in delete_statement",
+            ast.errors[0].to_string()
+        );
+        parser.set_dialect(Dialect::Cassandra4_1);
+        let ast = parser.parse("SELECT * FROM t BYPASS CACHE").unwrap();
+        assert_eq!(
+            "BYPASS CACHE is not supported in Cassandra 4.1 at line 1, column 17 in select_statement",
+            ast.errors[0].to_string()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::FQName;
+use crate::common::{move_spans, FQName};
 use std::fmt::{Display, Formatter};
 
 /// The data for a `drop trigger` command
@@ -12,6 +12,8 @@ pub struct DropTrigger {
     pub if_exists: bool,
 }
 
+move_spans!(DropTrigger { name, table; if_exists });
+
 impl Display for DropTrigger {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,13 +1,16 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, Identifier, Operand, TtlTimestamp};
+use crate::common::{
+    eq_without_spans, move_spans, FQName, Identifier, MoveSpans, Operand, Span, TtlTimestamp,
+};
 use itertools::Itertools;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
 /// the data for insert statements.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Insert {
-    /// if set the statement starts with `BEGIN BATCH`
+    /// if set the statement starts with `BEGIN BATCH`.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
     pub begin_batch: Option<BeginBatch>,
     /// the table name
     pub table_name: FQName,
@@ -19,8 +22,20 @@ pub struct Insert {
     pub using_ttl: Option<TtlTimestamp>,
     /// if true then `IF NOT EXISTS` is added to the statement
     pub if_not_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
 }
 
+eq_without_spans!(Insert {
+    begin_batch, table_name, columns, values, using_ttl, if_not_exists;
+    span
+});
+
+move_spans!(Insert {
+    table_name, values, span;
+    begin_batch, columns, using_ttl, if_not_exists
+});
+
 impl Insert {
     /// return a sorted map of column names to Operands.
     pub fn get_value_map(&self) -> BTreeMap<Identifier, &Operand> {
@@ -72,6 +87,14 @@ pub enum InsertValues {
     Json(String),
 }
 
+impl MoveSpans for InsertValues {
+    fn move_spans(&mut self, offset: isize) {
+        if let InsertValues::Values(values) = self {
+            values.move_spans(offset);
+        }
+    }
+}
+
 impl Display for InsertValues {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
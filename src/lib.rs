@@ -6,10 +6,13 @@ pub mod alter_materialized_view;
 pub mod alter_table;
 pub mod alter_type;
 pub mod begin_batch;
+pub mod borrowed;
 pub mod cassandra_ast;
 pub mod cassandra_statement;
+pub mod comment;
 pub mod common;
 pub mod common_drop;
+pub mod cql_parser;
 pub mod create_function;
 pub mod create_index;
 pub mod create_keyspace;
@@ -19,9 +22,13 @@ pub mod create_trigger;
 pub mod create_type;
 pub mod create_user;
 pub mod delete;
+pub mod dialect;
 pub mod drop_trigger;
 pub mod insert;
 pub mod list_role;
+pub mod parse_error;
 pub mod role_common;
 pub mod select;
+pub mod statement_reader;
+pub mod statement_summary;
 pub mod update;
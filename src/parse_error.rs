@@ -0,0 +1,337 @@
+use crate::common::Span;
+use crate::dialect::{Dialect, Feature};
+use std::fmt::{Display, Formatter};
+use tree_sitter::Node;
+
+/// The kind of problem found while parsing a query.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParseErrorKind {
+    /// the text could not be matched against the grammar (a tree-sitter `ERROR` node).
+    Error,
+    /// a token required by the grammar was not found in the text (a tree-sitter `MISSING` node).
+    /// The value is the kind of the missing token.
+    Missing(&'static str),
+    /// the parser found a node it did not expect at this point in the statement.
+    /// The value is the kind of the node.
+    Unexpected(&'static str),
+    /// the text of the node could not be converted to the expected value (e.g. a number that is
+    /// out of range).
+    InvalidValue,
+    /// the tree-sitter grammar is not compatible with the tree-sitter library.
+    Language,
+    /// tree-sitter stopped before a tree was produced.
+    Aborted,
+    /// the byte range of an edit is outside the text or does not fall on character boundaries.
+    InvalidEdit,
+    /// the query could not be read, or was not valid UTF-8.  The text of the error holds the
+    /// message of the I/O error.
+    Io(std::io::ErrorKind),
+    /// the text uses a feature the dialect does not support, or a feature of the dialect that
+    /// the grammar does not accept.
+    Unsupported(Feature, Dialect),
+    /// a statement read by `StatementReader` is longer than the limit set on the reader.  The
+    /// value is the limit in bytes.
+    TooLong(usize),
+}
+
+/// A location in the query text.
+/// Both the line and column are 1 based.  The column is counted in characters, not bytes.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// create the position of a tree-sitter point within the source.
+    fn from_point(point: tree_sitter::Point, byte: usize, source: &str) -> Position {
+        let line_start = byte - point.column;
+        Position {
+            line: point.row + 1,
+            column: source
+                .get(line_start..byte)
+                .map_or(point.column, |line| line.chars().count())
+                + 1,
+        }
+    }
+
+    /// move the position past `bytes`, which start at this position.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                // UTF-8 continuation bytes are part of the preceding character.
+                self.column += 1;
+            }
+        }
+    }
+
+    /// the position within a larger text of this position within a part of that text that
+    /// starts at `start`.
+    fn moved_to(self, start: Position) -> Position {
+        Position {
+            line: self.line + start.line - 1,
+            column: if self.line == 1 {
+                self.column + start.column - 1
+            } else {
+                self.column
+            },
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A problem found while parsing a query.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParseError {
+    /// the kind of problem.
+    pub kind: ParseErrorKind,
+    /// the first byte of the offending text within the query.
+    pub start_byte: usize,
+    /// the byte after the last byte of the offending text within the query.
+    pub end_byte: usize,
+    /// the position of the first character of the offending text.
+    pub start: Position,
+    /// the position after the last character of the offending text.
+    pub end: Position,
+    /// the offending text.  Empty for `Missing` errors.
+    pub text: String,
+    /// the tree-sitter kind of the statement the error was found in (e.g. `select_statement`).
+    /// `None` if the error could not be associated with a statement.
+    pub statement: Option<&'static str>,
+}
+
+impl ParseError {
+    /// true if the problem is within the span, e.g. of a statement.  A `Missing` error may be
+    /// at the end of the span.
+    pub(crate) fn is_within(&self, span: Span) -> bool {
+        span.start_byte <= self.start_byte
+            && (self.start_byte < span.end_byte
+                || (self.start_byte == span.end_byte && self.end_byte == span.end_byte))
+    }
+
+    /// create the error for an `ERROR` or `MISSING` node.
+    pub(crate) fn from_node(node: &Node, source: &str) -> ParseError {
+        if ParseError::is_missing(node) {
+            ParseError {
+                text: "".to_string(),
+                ..ParseError::at_node(ParseErrorKind::Missing(node.kind()), node, source)
+            }
+        } else {
+            ParseError::at_node(ParseErrorKind::Error, node, source)
+        }
+    }
+
+    /// create the error for a token that is missing after the node.
+    pub(crate) fn missing_after(node: &Node, source: &str, token: &'static str) -> ParseError {
+        let error = ParseError::at_node(ParseErrorKind::Missing(token), node, source);
+        ParseError {
+            start_byte: error.end_byte,
+            start: error.end,
+            text: "".to_string(),
+            ..error
+        }
+    }
+
+    /// move the error to the text between the `start_byte` and `end_byte` offsets, e.g. to
+    /// the keywords of the syntax that caused it.
+    pub(crate) fn move_to(&mut self, start_byte: usize, end_byte: usize, source: &str) {
+        let mut start = Position { line: 1, column: 1 };
+        start.advance(source.as_bytes().get(..start_byte).unwrap_or_default());
+        let mut end = start;
+        end.advance(
+            source
+                .as_bytes()
+                .get(start_byte..end_byte)
+                .unwrap_or_default(),
+        );
+        self.start_byte = start_byte;
+        self.end_byte = end_byte;
+        self.start = start;
+        self.end = end;
+        self.text = source
+            .get(start_byte..end_byte)
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    /// create the error for a node the parser did not expect.
+    pub(crate) fn unexpected(node: &Node, source: &str) -> ParseError {
+        ParseError::at_node(ParseErrorKind::Unexpected(node.kind()), node, source)
+    }
+
+    /// create the error for a node whose text could not be converted.
+    pub(crate) fn invalid_value(node: &Node, source: &str) -> ParseError {
+        ParseError::at_node(ParseErrorKind::InvalidValue, node, source)
+    }
+
+    /// create the error for a node that uses a feature the dialect does not support.
+    pub(crate) fn unsupported(
+        node: &Node,
+        source: &str,
+        feature: Feature,
+        dialect: Dialect,
+    ) -> ParseError {
+        ParseError::at_node(ParseErrorKind::Unsupported(feature, dialect), node, source)
+    }
+
+    /// create an error that is not associated with any text.
+    pub(crate) fn without_location(kind: ParseErrorKind) -> ParseError {
+        let start = Position { line: 1, column: 1 };
+        ParseError {
+            kind,
+            start_byte: 0,
+            end_byte: 0,
+            start,
+            end: start,
+            text: "".to_string(),
+            statement: None,
+        }
+    }
+
+    /// create the error for a failure to read the query.
+    pub(crate) fn io(error: &std::io::Error) -> ParseError {
+        ParseError {
+            text: error.to_string(),
+            ..ParseError::without_location(ParseErrorKind::Io(error.kind()))
+        }
+    }
+
+    /// the error for the same text within a larger text, when the error was found in a part of
+    /// that text that starts at `start_byte` and `start`.
+    pub(crate) fn moved_to(self, start_byte: usize, start: Position) -> ParseError {
+        ParseError {
+            start_byte: self.start_byte + start_byte,
+            end_byte: self.end_byte + start_byte,
+            start: self.start.moved_to(start),
+            end: self.end.moved_to(start),
+            ..self
+        }
+    }
+
+    fn at_node(kind: ParseErrorKind, node: &Node, source: &str) -> ParseError {
+        ParseError {
+            kind,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            start: Position::from_point(node.start_position(), node.start_byte(), source),
+            end: Position::from_point(node.end_position(), node.end_byte(), source),
+            text: source
+                .get(node.start_byte()..node.end_byte())
+                .unwrap_or_default()
+                .to_string(),
+            statement: ParseError::enclosing_statement(node),
+        }
+    }
+
+    /// true if the node is, or only contains, a token that tree-sitter inserted.
+    /// Missing tokens within a named leaf (e.g. a `constant`) are hidden from the tree so the
+    /// leaf is reported in their place.
+    fn is_missing(node: &Node) -> bool {
+        node.is_missing() || (node.child_count() == 0 && node.has_error() && !node.is_error())
+    }
+
+    /// collect every `ERROR` and `MISSING` node in the tree.
+    /// The children of an `ERROR` node are not reported separately as they are covered
+    /// by the error itself.
+    pub(crate) fn collect(root: &Node, source: &str) -> Vec<ParseError> {
+        let mut result = vec![];
+        let mut cursor = root.walk();
+        let mut process = true;
+        while process {
+            let node = cursor.node();
+            let is_error = node.is_error() || ParseError::is_missing(&node);
+            if is_error {
+                result.push(ParseError::from_node(&node, source));
+            }
+            if (is_error || !cursor.goto_first_child()) && !cursor.goto_next_sibling() {
+                // climb until a sibling is found or we are back at the root.
+                loop {
+                    if !cursor.goto_parent() {
+                        process = false;
+                        break;
+                    }
+                    if cursor.goto_next_sibling() {
+                        break;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// find the kind of the statement the node belongs to.
+    /// Text that can not be parsed after a statement is reported by tree-sitter as an `ERROR`
+    /// sibling of that statement, so in that case the preceding statement is used unless the two
+    /// are separated by a `;`.
+    fn enclosing_statement(node: &Node) -> Option<&'static str> {
+        let mut statement = *node;
+        while let Some(parent) = statement.parent() {
+            if parent.parent().is_none() {
+                break;
+            }
+            statement = parent;
+        }
+        if !statement.is_error() {
+            return if statement.parent().is_some() {
+                Some(statement.kind())
+            } else {
+                None
+            };
+        }
+        match statement.prev_sibling() {
+            Some(prev) if !prev.is_error() && !prev.kind().eq(";") => Some(prev.kind()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseErrorKind::Error => {
+                write!(f, "syntax error at {} near '{}'", self.start, self.text)
+            }
+            ParseErrorKind::Missing(token) => write!(f, "missing {} at {}", token, self.start),
+            ParseErrorKind::Unexpected(kind) => write!(f, "unexpected {} at {}", kind, self.start),
+            ParseErrorKind::InvalidValue => {
+                write!(f, "invalid value '{}' at {}", self.text, self.start)
+            }
+            ParseErrorKind::Language => write!(
+                f,
+                "the CQL grammar is not compatible with the tree-sitter library"
+            ),
+            ParseErrorKind::Aborted => write!(f, "parsing stopped before a tree was produced"),
+            ParseErrorKind::InvalidEdit => write!(f, "the edit range does not fit the query"),
+            ParseErrorKind::Io(_) => write!(f, "the query could not be read: {}", self.text),
+            ParseErrorKind::Unsupported(feature, dialect) if dialect.supports(*feature) => write!(
+                f,
+                "{} is not supported by the grammar at {}",
+                feature, self.start
+            ),
+            ParseErrorKind::Unsupported(feature, dialect) => write!(
+                f,
+                "{} is not supported in {} at {}",
+                feature, dialect, self.start
+            ),
+            ParseErrorKind::TooLong(limit) => write!(
+                f,
+                "the statement at {} is longer than {} bytes",
+                self.start, limit
+            ),
+        }?;
+        if let Some(statement) = self.statement {
+            write!(f, " in {}", statement)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,9 +1,12 @@
-use crate::common::{FQName, Identifier, OrderClause, RelationElement};
+use crate::common::{
+    eq_without_spans, move_spans, Bindable, CqlDuration, DataType, FQName, FunctionCall,
+    Identifier, MoveSpans, Operand, OrderClause, RelationElement, Span, WhereExpression,
+};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
 /// data for select statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Select {
     /// if true DISTINCT results
     pub distinct: bool,
@@ -13,16 +16,37 @@ pub struct Select {
     pub table_name: FQName,
     /// the list of elements to select.
     pub columns: Vec<SelectElement>,
-    /// the where clause
-    pub where_clause: Vec<RelationElement>,
-    /// the optional ordering
-    pub order: Option<OrderClause>,
+    /// the condition of the where clause, `None` if there is no `WHERE` clause.
+    pub where_clause: Option<WhereExpression>,
+    /// the columns to group the results by.
+    pub group_by: Vec<Identifier>,
+    /// the ordering, empty if there is no `ORDER BY` clause
+    pub order: Vec<OrderClause>,
+    /// the number of items to return from each partition
+    pub per_partition_limit: Option<Bindable<i32>>,
     /// the number of items to return
-    pub limit: Option<i32>,
+    pub limit: Option<Bindable<i32>>,
     /// if true ALLOW FILTERING is displayed
     pub filtering: bool,
+    /// if true BYPASS CACHE is displayed, a ScyllaDB extension.
+    pub bypass_cache: bool,
+    /// the timeout of the query, `USING TIMEOUT` is a ScyllaDB extension.
+    pub timeout: Option<Bindable<CqlDuration>>,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
 }
 
+eq_without_spans!(Select {
+    distinct, json, table_name, columns, where_clause, group_by, order, per_partition_limit, limit,
+    filtering, bypass_cache, timeout;
+    span
+});
+
+move_spans!(Select {
+    table_name, columns, where_clause, order, span;
+    distinct, json, group_by, per_partition_limit, limit, filtering, bypass_cache, timeout
+});
+
 impl Select {
     /// return the column names selected
     /// does not return functions.
@@ -53,32 +77,58 @@ impl Select {
             })
             .collect()
     }
+
+    /// the relations of the where clause as a list that are all required to be true.  Returns
+    /// an empty list if there is no where clause and `None` if the condition can not be written
+    /// as relations joined by `AND`, see [WhereExpression::to_conjunction].
+    pub fn where_relations(&self) -> Option<Vec<RelationElement>> {
+        self.where_clause
+            .as_ref()
+            .map_or(Some(vec![]), WhereExpression::to_conjunction)
+    }
 }
 
 impl Display for Select {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "SELECT {}{}{} FROM {}{}{}{}{}",
+            "SELECT {}{}{} FROM {}{}{}{}{}{}{}{}{}",
             if self.distinct { "DISTINCT " } else { "" },
             if self.json { "JSON " } else { "" },
             self.columns.iter().join(", "),
             self.table_name,
-            if !self.where_clause.is_empty() {
-                format!(" WHERE {}", self.where_clause.iter().join(" AND "))
+            self.where_clause
+                .as_ref()
+                .map_or("".to_string(), |x| format!(" WHERE {}", x)),
+            if !self.group_by.is_empty() {
+                format!(" GROUP BY {}", self.group_by.iter().join(", "))
             } else {
                 "".to_string()
             },
-            self.order
+            if !self.order.is_empty() {
+                format!(" ORDER BY {}", self.order.iter().join(", "))
+            } else {
+                "".to_string()
+            },
+            self.per_partition_limit
                 .as_ref()
-                .map_or("".to_string(), |x| format!(" ORDER BY {}", x)),
+                .map_or("".to_string(), |x| format!(" PER PARTITION LIMIT {}", x)),
             self.limit
+                .as_ref()
                 .map_or("".to_string(), |x| format!(" LIMIT {}", x)),
             if self.filtering {
                 " ALLOW FILTERING"
             } else {
                 ""
-            }
+            },
+            if self.bypass_cache {
+                " BYPASS CACHE"
+            } else {
+                ""
+            },
+            self.timeout
+                .as_ref()
+                .map_or("".to_string(), |x| format!(" USING TIMEOUT {}", x)),
         )
     }
 }
@@ -90,31 +140,56 @@ pub enum SelectElement {
     Star,
     /// a named column.  May have an alias specified.
     Column(Named),
-    /// a named column.  May have an alias specified.
-    Function(Named),
+    /// a function call.  May have an alias specified.
+    Function(NamedFunction),
+    /// any other selector, e.g. `CAST(a AS TEXT)` or `a + 1`.  May have an alias specified.
+    Selector(NamedSelector),
+}
+
+impl MoveSpans for SelectElement {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            SelectElement::Star => {}
+            SelectElement::Column(named) => named.move_spans(offset),
+            SelectElement::Function(named) => named.move_spans(offset),
+            SelectElement::Selector(named) => named.move_spans(offset),
+        }
+    }
 }
 
 impl Display for SelectElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             SelectElement::Star => write!(f, "*"),
-            SelectElement::Column(named) | SelectElement::Function(named) => write!(f, "{}", named),
+            SelectElement::Column(named) => write!(f, "{}", named),
+            SelectElement::Function(named) => write!(f, "{}", named),
+            SelectElement::Selector(named) => write!(f, "{}", named),
         }
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Named {
     pub name: Identifier,
     pub alias: Option<Identifier>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+    /// the location of the column name.
+    pub name_span: Option<Span>,
 }
 
+eq_without_spans!(Named { name, alias; span, name_span });
+
+move_spans!(Named { span, name_span; name, alias });
+
 /// the name an optional alias for a named item.
 impl Named {
     pub fn new(name: &str, alias: &str) -> Named {
         Named {
             name: Identifier::parse(name),
             alias: Some(Identifier::parse(alias)),
+            span: None,
+            name_span: None,
         }
     }
 
@@ -122,6 +197,8 @@ impl Named {
         Named {
             name: Identifier::parse(name),
             alias: None,
+            span: None,
+            name_span: None,
         }
     }
 
@@ -142,9 +219,226 @@ impl Display for Named {
     }
 }
 
+/// a function call and an optional alias for it.
+#[derive(Debug, Clone)]
+pub struct NamedFunction {
+    pub function: FunctionCall,
+    pub alias: Option<Identifier>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(NamedFunction { function, alias; span });
+
+move_spans!(NamedFunction { function, span; alias });
+
+impl NamedFunction {
+    pub fn new(function: FunctionCall, alias: Option<&str>) -> NamedFunction {
+        NamedFunction {
+            function,
+            alias: alias.map(Identifier::parse),
+            span: None,
+        }
+    }
+}
+
+impl Display for NamedFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            None => write!(f, "{}", self.function),
+            Some(a) => write!(f, "{} AS {}", self.function, a),
+        }
+    }
+}
+
+/// the arithmetic operators of a selector or operand.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Ord, PartialOrd)]
+pub enum ArithmeticOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+impl ArithmeticOperator {
+    /// the precedence of the operator, multiplication binds more tightly than addition.
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            ArithmeticOperator::Add | ArithmeticOperator::Subtract => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticOperator::Add => write!(f, "+"),
+            ArithmeticOperator::Subtract => write!(f, "-"),
+            ArithmeticOperator::Multiply => write!(f, "*"),
+            ArithmeticOperator::Divide => write!(f, "/"),
+            ArithmeticOperator::Modulo => write!(f, "%"),
+        }
+    }
+}
+
+/// a selector expression.  Parentheses are not held, they are written where the precedence of
+/// the operators requires them.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Selector {
+    /// a column.
+    Column(Identifier),
+    /// a literal, bind marker or collection term, e.g. `'abc'`, `?` or `[1, 2]`.
+    Term(Operand),
+    /// a function call on selectors, e.g. `f(a, b + 1)`.  The `*` of `count(*)` is held as a
+    /// constant term.
+    Function(FQName, Vec<Selector>),
+    /// `CAST(selector AS type)`
+    Cast(Box<Selector>, DataType),
+    /// `WRITETIME(selector)`
+    WriteTime(Box<Selector>),
+    /// `MAXWRITETIME(selector)`
+    MaxWriteTime(Box<Selector>),
+    /// `TTL(selector)`
+    Ttl(Box<Selector>),
+    /// an element of a map or list, `selector[key]`
+    Element(Box<Selector>, Box<Selector>),
+    /// a slice of a collection, `selector[start..end]`.  Either bound may be omitted.
+    Slice(Box<Selector>, Option<Box<Selector>>, Option<Box<Selector>>),
+    /// a field of a user defined type, `selector.field`
+    Field(Box<Selector>, Identifier),
+    /// an arithmetic operation, e.g. `a + b`
+    Arithmetic(Box<Selector>, ArithmeticOperator, Box<Selector>),
+    /// the negation of a selector, `-selector`
+    Negate(Box<Selector>),
+}
+
+impl Selector {
+    /// the selector as written when it is an operand of an operator.  Arithmetic operations
+    /// with a lower precedence than `precedence` are enclosed in parentheses, a `precedence`
+    /// of 4 encloses all operations.
+    fn operand(&self, precedence: u8) -> String {
+        match self {
+            Selector::Arithmetic(_, operator, _) if operator.precedence() < precedence => {
+                format!("({})", self)
+            }
+            // a negation is enclosed when a field, element or slice is taken from it, or when
+            // it is negated as `--` starts a comment.
+            Selector::Negate(_) if precedence > 3 => format!("({})", self),
+            Selector::Term(term) if precedence > 3 && term.to_string().starts_with('-') => {
+                format!("({})", self)
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl MoveSpans for Selector {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            Selector::Column(_) => {}
+            Selector::Term(operand) => operand.move_spans(offset),
+            Selector::Function(name, args) => {
+                name.move_spans(offset);
+                args.move_spans(offset);
+            }
+            Selector::Cast(selector, data_type) => {
+                selector.move_spans(offset);
+                data_type.move_spans(offset);
+            }
+            Selector::WriteTime(selector)
+            | Selector::MaxWriteTime(selector)
+            | Selector::Ttl(selector)
+            | Selector::Field(selector, _)
+            | Selector::Negate(selector) => selector.move_spans(offset),
+            Selector::Element(selector, element) => {
+                selector.move_spans(offset);
+                element.move_spans(offset);
+            }
+            Selector::Slice(selector, from, to) => {
+                selector.move_spans(offset);
+                from.move_spans(offset);
+                to.move_spans(offset);
+            }
+            Selector::Arithmetic(left, _, right) => {
+                left.move_spans(offset);
+                right.move_spans(offset);
+            }
+        }
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Selector::Column(name) => write!(f, "{}", name),
+            Selector::Term(term) => write!(f, "{}", term),
+            Selector::Function(name, args) => write!(f, "{}({})", name, args.iter().join(", ")),
+            Selector::Cast(selector, data_type) => write!(f, "CAST({} AS {})", selector, data_type),
+            Selector::WriteTime(selector) => write!(f, "WRITETIME({})", selector),
+            Selector::MaxWriteTime(selector) => write!(f, "MAXWRITETIME({})", selector),
+            Selector::Ttl(selector) => write!(f, "TTL({})", selector),
+            Selector::Element(selector, key) => write!(f, "{}[{}]", selector.operand(4), key),
+            Selector::Slice(selector, start, end) => write!(
+                f,
+                "{}[{}..{}]",
+                selector.operand(4),
+                start.as_ref().map_or("".to_string(), |x| x.to_string()),
+                end.as_ref().map_or("".to_string(), |x| x.to_string())
+            ),
+            Selector::Field(selector, field) => write!(f, "{}.{}", selector.operand(4), field),
+            Selector::Arithmetic(left, operator, right) => write!(
+                f,
+                "{} {} {}",
+                left.operand(operator.precedence()),
+                operator,
+                // operators of the same precedence are evaluated from left to right.
+                right.operand(operator.precedence() + 1)
+            ),
+            Selector::Negate(selector) => write!(f, "-{}", selector.operand(4)),
+        }
+    }
+}
+
+/// a selector and an optional alias for it.
+#[derive(Debug, Clone)]
+pub struct NamedSelector {
+    pub selector: Selector,
+    pub alias: Option<Identifier>,
+    /// the location of the element in the query text.
+    pub span: Option<Span>,
+}
+
+eq_without_spans!(NamedSelector { selector, alias; span });
+
+move_spans!(NamedSelector { selector, span; alias });
+
+impl NamedSelector {
+    pub fn new(selector: Selector, alias: Option<&str>) -> NamedSelector {
+        NamedSelector {
+            selector,
+            alias: alias.map(Identifier::parse),
+            span: None,
+        }
+    }
+}
+
+impl Display for NamedSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            None => write!(f, "{}", self.selector),
+            Some(a) => write!(f, "{} AS {}", self.selector, a),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::select::{Named, SelectElement};
+    use crate::common::{DataType, DataTypeName, FQName, FunctionCall, Identifier, Operand};
+    use crate::select::{
+        ArithmeticOperator, Named, NamedFunction, NamedSelector, SelectElement, Selector,
+    };
 
     #[test]
     fn test_select_element_display() {
@@ -153,17 +447,90 @@ mod tests {
             "col",
             SelectElement::Column(Named::simple("col")).to_string()
         );
+        let count = FunctionCall::new("count", vec![Operand::Const("*".to_string())]);
         assert_eq!(
-            "func",
-            SelectElement::Function(Named::simple("func")).to_string()
+            "count(*)",
+            SelectElement::Function(NamedFunction::new(count.clone(), None)).to_string()
         );
         assert_eq!(
             "col AS alias",
             SelectElement::Column(Named::new("col", "alias")).to_string()
         );
         assert_eq!(
-            "func AS alias",
-            SelectElement::Function(Named::new("func", "alias")).to_string()
+            "count(*) AS alias",
+            SelectElement::Function(NamedFunction::new(count, Some("alias"))).to_string()
+        );
+        assert_eq!(
+            "ks.f(a, now())",
+            FunctionCall::new(
+                "ks.f",
+                vec![
+                    Operand::Column(Identifier::parse("a")),
+                    Operand::Func(FunctionCall::new("now", vec![]))
+                ]
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_selector_display() {
+        let column = |name: &str| Box::new(Selector::Column(Identifier::parse(name)));
+        let arithmetic =
+            |left, operator, right| Box::new(Selector::Arithmetic(left, operator, right));
+        let sum = arithmetic(column("a"), ArithmeticOperator::Add, column("b"));
+        assert_eq!(
+            "(a + b) * c",
+            arithmetic(sum.clone(), ArithmeticOperator::Multiply, column("c")).to_string()
+        );
+        assert_eq!(
+            "c - (a + b)",
+            arithmetic(column("c"), ArithmeticOperator::Subtract, sum.clone()).to_string()
+        );
+        assert_eq!(
+            "a + b - c",
+            arithmetic(sum.clone(), ArithmeticOperator::Subtract, column("c")).to_string()
+        );
+        assert_eq!(
+            "a * -b",
+            arithmetic(
+                column("a"),
+                ArithmeticOperator::Multiply,
+                Box::new(Selector::Negate(column("b")))
+            )
+            .to_string()
+        );
+        assert_eq!(
+            "-(-a)",
+            Selector::Negate(Box::new(Selector::Negate(column("a")))).to_string()
+        );
+        assert_eq!(
+            "(a + b).f[1..]",
+            Selector::Slice(
+                Box::new(Selector::Field(sum, Identifier::parse("f"))),
+                Some(Box::new(Selector::Term(Operand::from(&1)))),
+                None
+            )
+            .to_string()
+        );
+        let cast = Selector::Cast(
+            Box::new(Selector::WriteTime(column("a"))),
+            DataType::Native(DataTypeName::Text),
+        );
+        assert_eq!(
+            "CAST(WRITETIME(a) AS TEXT) AS w",
+            SelectElement::Selector(NamedSelector::new(cast, Some("w"))).to_string()
+        );
+        assert_eq!(
+            "ks.f(m['k'], TTL(b))",
+            Selector::Function(
+                FQName::new("ks", "f"),
+                vec![
+                    Selector::Element(column("m"), Box::new(Selector::Term(Operand::from("k")))),
+                    Selector::Ttl(column("b"))
+                ]
+            )
+            .to_string()
         );
     }
 }
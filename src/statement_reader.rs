@@ -0,0 +1,610 @@
+use crate::cassandra_ast::{CassandraAST, ParsedStatement};
+use crate::comment::Comment;
+use crate::common::MoveSpans;
+use crate::cql_parser::CqlParser;
+use crate::dialect::Dialect;
+use crate::parse_error::{ParseError, ParseErrorKind, Position};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+/// the default largest number of bytes `StatementReader` reads to find the end of a statement.
+pub const DEFAULT_MAX_STATEMENT_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Reads the statements of a CQL script one at a time.
+///
+/// The text is split after each `;` that is not within a string constant, quoted identifier,
+/// `$$` block or comment, and each part is parsed on its own with the thread's default
+/// `CqlParser` and the reader's dialect.  Only the text of the statement being parsed is held in memory.
+///
+/// The statements have the location (`ParsedStatement::span`), comments and element spans they
+/// would have if the whole text was parsed by `CassandraAST::new`, and the syntax errors found
+/// within them are returned with them.
+///
+/// A statement longer than `max_statement_length` bytes is reported as an error rather than held
+/// in memory, as is a batch that does not end with `APPLY BATCH`.  Iteration stops after the
+/// first error.
+pub struct StatementReader<R: BufRead> {
+    reader: R,
+    /// the text that has been read but not parsed.
+    pending: Vec<u8>,
+    /// the byte offset of the start of `pending` within the text.
+    offset: usize,
+    /// the position of the start of `pending` within the text.
+    position: Position,
+    /// the version whose syntax is accepted.
+    dialect: Dialect,
+    /// the largest number of bytes that are read to find the end of a statement.
+    max_statement_length: usize,
+    /// finds the end of the next statement in `pending`.
+    splitter: Splitter,
+    /// the statements that have been parsed but not returned.  The last statement is kept until
+    /// the text that follows it is read as the comments at the end of the text are given to it.
+    statements: VecDeque<ReadStatement>,
+    /// the error that stopped reading, returned after the statements before it.
+    error: Option<ParseError>,
+    /// true once the end of the text has been reached.
+    eof: bool,
+    /// true once there is nothing more to read.
+    done: bool,
+}
+
+impl<R: BufRead> StatementReader<R> {
+    /// create a reader of the statements in the text read from `reader`.
+    pub fn new(reader: R) -> StatementReader<R> {
+        StatementReader {
+            reader,
+            pending: vec![],
+            offset: 0,
+            position: Position { line: 1, column: 1 },
+            dialect: Dialect::default(),
+            max_statement_length: DEFAULT_MAX_STATEMENT_LENGTH,
+            splitter: Splitter::new(),
+            statements: VecDeque::new(),
+            error: None,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// the version whose syntax is accepted.  The default is `Dialect::Cassandra3_11`.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// set the version whose syntax is accepted, see `CqlParser::set_dialect`.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect
+    }
+
+    /// the largest number of bytes that are read to find the end of a statement.  The default
+    /// is `DEFAULT_MAX_STATEMENT_LENGTH`.
+    pub fn max_statement_length(&self) -> usize {
+        self.max_statement_length
+    }
+
+    /// set the largest number of bytes that are read to find the end of a statement.  A longer
+    /// statement stops the reader with a `ParseErrorKind::TooLong` error.
+    pub fn set_max_statement_length(&mut self, max_statement_length: usize) {
+        self.max_statement_length = max_statement_length
+    }
+
+    /// parse the text of the next statement.
+    fn read_statement(&mut self) -> Result<(), ParseError> {
+        let end = loop {
+            if let Some(end) = self.splitter.split(&self.pending, self.eof) {
+                break end;
+            }
+            if self.eof {
+                self.done = true;
+                return Ok(());
+            }
+            if self.pending.len() > self.max_statement_length {
+                return Err(ParseError {
+                    start_byte: self.offset,
+                    end_byte: self.offset,
+                    start: self.position,
+                    end: self.position,
+                    ..ParseError::without_location(ParseErrorKind::TooLong(
+                        self.max_statement_length,
+                    ))
+                });
+            }
+            self.fill()?;
+        };
+        let bytes: Vec<u8> = self.pending.drain(..end).collect();
+        let content_start = self.splitter.content_start;
+        self.splitter = Splitter::new();
+        let text = String::from_utf8(bytes)
+            .map_err(|e| ParseError::io(&std::io::Error::new(ErrorKind::InvalidData, e)))?;
+
+        match content_start {
+            Some(start) => self.parse(&text, start)?,
+            // only comments are left at the end of the text.
+            None => {
+                let comments = Comment::find(&text);
+                if let Some(last) = self.statements.back_mut() {
+                    last.statement
+                        .trailing_comments
+                        .extend(comments.into_iter().map(|x| moved(x, self.offset)));
+                }
+            }
+        }
+        self.offset += text.len();
+        self.position.advance(text.as_bytes());
+        Ok(())
+    }
+
+    /// parse the text of a statement.  The text before `start` only holds comments and white
+    /// space.
+    fn parse(&mut self, text: &str, start: usize) -> Result<(), ParseError> {
+        let mut position = self.position;
+        position.advance(text[..start].as_bytes());
+        let offset = self.offset + start;
+        let ast = CqlParser::with_default(|parser| {
+            let tree = parser.parse_tree(&text[start..], None)?;
+            CassandraAST::try_from_tree(&text[start..], tree, self.dialect)
+        })?
+        .map_err(|e| e.moved_to(offset, position))?;
+
+        let mut leading = Comment::find(&text[..start])
+            .into_iter()
+            .map(|x| moved(x, self.offset))
+            .collect::<Vec<Comment>>();
+        let mut errors = ast
+            .errors
+            .into_iter()
+            .map(|x| x.moved_to(offset, position))
+            .collect::<Vec<ParseError>>();
+        let count = ast.statements.len();
+        for (i, mut statement) in ast.statements.into_iter().enumerate() {
+            statement.start_byte += offset;
+            statement.end_byte += offset;
+            statement.statement.move_spans(offset as isize);
+            leading.extend(
+                statement
+                    .leading_comments
+                    .into_iter()
+                    .map(|x| moved(x, offset)),
+            );
+            statement.leading_comments = std::mem::take(&mut leading);
+            statement.trailing_comments = statement
+                .trailing_comments
+                .into_iter()
+                .map(|x| moved(x, offset))
+                .collect();
+            // the errors that are not within a statement are given to the last one.
+            let (within, rest) = errors
+                .into_iter()
+                .partition(|x: &ParseError| i + 1 == count || x.is_within(statement.span()));
+            errors = rest;
+            self.statements.push_back(ReadStatement {
+                statement,
+                errors: within,
+            });
+        }
+        Ok(())
+    }
+
+    /// add the next block of the text to `pending`.
+    fn fill(&mut self) -> Result<(), ParseError> {
+        let length = loop {
+            match self.reader.fill_buf() {
+                Ok(buffer) => {
+                    self.pending.extend_from_slice(buffer);
+                    break buffer.len();
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(ParseError::io(&e)),
+            }
+        };
+        self.eof = length == 0;
+        self.reader.consume(length);
+        Ok(())
+    }
+}
+
+impl<R: Read> StatementReader<BufReader<R>> {
+    /// create a reader of the statements in the text read from `reader`.
+    pub fn from_read(reader: R) -> StatementReader<BufReader<R>> {
+        StatementReader::new(BufReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Iterator for StatementReader<R> {
+    type Item = Result<ReadStatement, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done && self.statements.len() < 2 {
+            if let Err(e) = self.read_statement() {
+                self.done = true;
+                self.error = Some(e);
+            }
+        }
+        match self.statements.pop_front() {
+            Some(statement) => Some(Ok(statement)),
+            None => self.error.take().map(Err),
+        }
+    }
+}
+
+/// A statement read by `StatementReader`.
+#[derive(PartialEq, Debug)]
+pub struct ReadStatement {
+    /// the parsed statement.
+    pub statement: ParsedStatement,
+    /// the syntax errors found within the statement, as `CassandraAST::errors` would list them
+    /// for the whole text.
+    pub errors: Vec<ParseError>,
+}
+
+/// the comment moved `offset` bytes further into the text.
+fn moved(mut comment: Comment, offset: usize) -> Comment {
+    comment.move_spans(offset as isize);
+    comment
+}
+
+/// The kind of text the splitter is in.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Lexeme {
+    Code,
+    /// a string constant or quoted identifier, the value is the quote character.
+    Quoted(u8),
+    Dollar,
+    LineComment,
+    BlockComment,
+}
+
+/// Finds the end of a statement in the text.
+///
+/// A statement ends after its `;` and the comments that follow it on the same line.  If
+/// another statement starts on the same line the white space before it is part of that
+/// statement.  The statements of a batch are kept together, a batch ends after the `;` that
+/// follows `APPLY BATCH`.
+#[derive(Debug)]
+struct Splitter {
+    lexeme: Lexeme,
+    /// the offset of the next byte to examine.
+    pos: usize,
+    /// the offset of the first byte of the statement that is not white space or a comment.
+    content_start: Option<usize>,
+    /// the offset after the `;` that ends the statement and any comments that follow it.
+    end: Option<usize>,
+}
+
+impl Splitter {
+    fn new() -> Splitter {
+        Splitter {
+            lexeme: Lexeme::Code,
+            pos: 0,
+            content_start: None,
+            end: None,
+        }
+    }
+
+    /// true if the statement text starts with `BEGIN` and does not end with `APPLY BATCH`, in
+    /// which case a `;` that follows it separates the statements of a batch.
+    fn in_batch(statement: &[u8]) -> bool {
+        let begin = b"BEGIN";
+        if statement.len() <= begin.len()
+            || !statement[..begin.len()].eq_ignore_ascii_case(begin)
+            || !statement[begin.len()].is_ascii_whitespace()
+        {
+            return false;
+        }
+        let mut rest = statement;
+        for word in [&b"BATCH"[..], &b"APPLY"[..]] {
+            let end = rest
+                .iter()
+                .rposition(|x| !x.is_ascii_whitespace())
+                .map_or(0, |x| x + 1);
+            if end < word.len() || !rest[end - word.len()..end].eq_ignore_ascii_case(word) {
+                return true;
+            }
+            rest = &rest[..end - word.len()];
+            if rest
+                .last()
+                .map_or(false, |x| x.is_ascii_alphanumeric() || *x == b'_')
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// the offset of the end of the first statement in `text`.  Returns `None` if more text is
+    /// needed to find the end.  At the end of the text (`eof`) the remaining text is a
+    /// statement.
+    fn split(&mut self, text: &[u8], eof: bool) -> Option<usize> {
+        while self.pos < text.len() {
+            let byte = text[self.pos];
+            let next = text.get(self.pos + 1).copied();
+            if next.is_none() && !eof {
+                // the byte may start a two byte marker.
+                return None;
+            }
+            let (lexeme, length) = match (self.lexeme, byte, next) {
+                (Lexeme::Code, b'-', Some(b'-')) | (Lexeme::Code, b'/', Some(b'/')) => {
+                    (Lexeme::LineComment, 2)
+                }
+                (Lexeme::Code, b'/', Some(b'*')) => (Lexeme::BlockComment, 2),
+                (Lexeme::Code, b'\n', _) if self.end.is_some() => return Some(self.pos + 1),
+                (Lexeme::Code, _, _) if byte.is_ascii_whitespace() => (Lexeme::Code, 1),
+                (Lexeme::Code, _, _) if self.end.is_some() => return self.end,
+                (Lexeme::Code, _, _) => {
+                    self.content_start.get_or_insert(self.pos);
+                    match (byte, next) {
+                        (b'\'', _) | (b'"', _) => (Lexeme::Quoted(byte), 1),
+                        (b'$', Some(b'$')) => (Lexeme::Dollar, 2),
+                        (b';', _)
+                            if !Splitter::in_batch(
+                                &text[self.content_start.unwrap_or(self.pos)..self.pos],
+                            ) =>
+                        {
+                            self.end = Some(self.pos + 1);
+                            (Lexeme::Code, 1)
+                        }
+                        _ => (Lexeme::Code, 1),
+                    }
+                }
+                (Lexeme::Quoted(quote), _, _) if byte == quote => (Lexeme::Code, 1),
+                (Lexeme::Dollar, b'$', Some(b'$')) => (Lexeme::Code, 2),
+                // the line break is examined as code as it may end the statement.
+                (Lexeme::LineComment, b'\n', _) => (Lexeme::Code, 0),
+                (Lexeme::BlockComment, b'*', Some(b'/')) => {
+                    if self.end.is_some() {
+                        self.end = Some(self.pos + 2);
+                    }
+                    (Lexeme::Code, 2)
+                }
+                (lexeme, _, _) => (lexeme, 1),
+            };
+            self.lexeme = lexeme;
+            self.pos += length;
+        }
+        if eof && !text.is_empty() {
+            Some(text.len())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::comment::Comment;
+    use crate::dialect::{Dialect, Feature};
+    use crate::parse_error::{ParseErrorKind, Position};
+    use crate::select::SelectElement;
+    use crate::statement_reader::{ReadStatement, StatementReader};
+    use std::io::{BufReader, Read};
+    use std::ops::Range;
+
+    const SCRIPT: &str = "-- the schema
+CREATE TABLE ks.t (a int PRIMARY KEY, -- the key
+  b text) WITH comment = 'a;b'; -- t
+
+/* data */ INSERT INTO ks.t (a, b) VALUES (1, 'x;y'); INSERT INTO ks.t (a) VALUES (2); /* two */
+BEGIN BATCH INSERT INTO ks.t (a) VALUES (3);
+  DELETE FROM ks.t WHERE a = 1; -- in the batch
+Apply  Batch;
+CREATE FUNCTION ks.f (a int) RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE java AS $$ return a; $$;
+SELECT a FROM ks.t WHERE a = 1; // é;
+USE ks
+-- done";
+
+    fn read_all(text: &str, capacity: usize) -> Vec<ReadStatement> {
+        StatementReader::new(BufReader::with_capacity(capacity, text.as_bytes()))
+            .map(|x| x.unwrap())
+            .collect()
+    }
+
+    fn read(text: &str, capacity: usize) -> Vec<crate::cassandra_ast::ParsedStatement> {
+        read_all(text, capacity)
+            .into_iter()
+            .map(|x| x.statement)
+            .collect()
+    }
+
+    /// the spans of the comments, which are not compared by `==`.
+    fn comment_spans(comments: &[Comment]) -> Vec<Option<Range<usize>>> {
+        comments.iter().map(|x| x.span.map(|x| x.range())).collect()
+    }
+
+    #[test]
+    fn test_matches_ast() {
+        let ast = CassandraAST::new(SCRIPT);
+        assert!(!ast.has_error());
+        for capacity in [1, 2, 3, 7, 8192] {
+            let statements = read(SCRIPT, capacity);
+            assert_eq!(ast.statements, statements, "{}", capacity);
+            // `==` ignores the spans, so they are compared on their own.  The debug output of a
+            // statement holds the spans of all of its elements.
+            for (expected, statement) in ast.statements.iter().zip(&statements) {
+                assert_eq!(expected.span().range(), statement.span().range());
+                assert_eq!(
+                    comment_spans(&expected.leading_comments),
+                    comment_spans(&statement.leading_comments)
+                );
+                assert_eq!(
+                    comment_spans(&expected.trailing_comments),
+                    comment_spans(&statement.trailing_comments)
+                );
+                assert_eq!(
+                    format!("{:?}", expected.statement),
+                    format!("{:?}", statement.statement),
+                    "{}",
+                    capacity
+                );
+            }
+        }
+        assert!(read("", 10).is_empty());
+        assert!(read("-- nothing\n", 10).is_empty());
+    }
+
+    #[test]
+    fn test_errors_match_ast() {
+        let script = "SELECT a FROM t;\n/* x */ SELECT FROM WHERE;\nUSE ks;\nINSERT INTO t (a) VALUES (1) zq";
+        let ast = CassandraAST::new(script);
+        for capacity in [1, 4, 8192] {
+            let statements = read_all(script, capacity);
+            assert_eq!(
+                ast.errors,
+                statements
+                    .iter()
+                    .flat_map(|x| x.errors.clone())
+                    .collect::<Vec<_>>(),
+                "{}",
+                capacity
+            );
+            for statement in &statements {
+                assert_eq!(statement.statement.has_error, !statement.errors.is_empty());
+            }
+        }
+        let statements = read_all(script, 4);
+        assert_eq!(2, statements[1].errors[0].start.line);
+        assert!(statements[2].errors.is_empty());
+    }
+
+    #[test]
+    fn test_max_statement_length() {
+        // a batch that is not applied would otherwise hold the rest of the text.
+        let script = "USE ks;\nBEGIN BATCH INSERT INTO t (a) VALUES (1); USE ks; USE ks; USE ks;";
+        let mut reader = StatementReader::new(BufReader::with_capacity(4, script.as_bytes()));
+        reader.set_max_statement_length(20);
+        assert_eq!(20, reader.max_statement_length());
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(ParseErrorKind::TooLong(20), error.kind);
+        assert_eq!(8, error.start_byte);
+        assert_eq!(Position { line: 2, column: 1 }, error.start);
+        assert_eq!(
+            "the statement at line 2, column 1 is longer than 20 bytes",
+            error.to_string()
+        );
+        assert!(reader.next().is_none());
+
+        let mut reader = StatementReader::new(script.as_bytes());
+        reader.set_max_statement_length(script.len());
+        assert!(reader.all(|x| x.is_ok()));
+    }
+
+    #[test]
+    fn test_offsets() {
+        let statements = read(SCRIPT, 5);
+        assert_eq!(
+            "INSERT INTO ks.t (a) VALUES (2)",
+            &SCRIPT[statements[2].span().range()]
+        );
+        assert_eq!("BATCH", statements[3].statement.short_name());
+        assert!(SCRIPT[statements[3].span().range()].ends_with("Apply  Batch"));
+        assert_eq!(
+            "SELECT a FROM ks.t WHERE a = 1",
+            &SCRIPT[statements[5].span().range()]
+        );
+        // the element spans are offsets within the whole text.
+        match &statements[5].statement {
+            CassandraStatement::Select(select) => {
+                assert_eq!("ks.t", &SCRIPT[select.table_name.span.unwrap().range()]);
+                match &select.columns[0] {
+                    SelectElement::Column(named) => {
+                        assert_eq!("a", &SCRIPT[named.span.unwrap().range()])
+                    }
+                    _ => unreachable!(),
+                }
+                let relations = select.where_relations().unwrap();
+                assert_eq!("a = 1", &SCRIPT[relations[0].span.unwrap().range()]);
+                assert_eq!("1", &SCRIPT[relations[0].value_span.unwrap().range()]);
+            }
+            _ => unreachable!(),
+        }
+        match &statements[0].statement {
+            CassandraStatement::CreateTable(table) => {
+                let column = &table.columns[1];
+                assert_eq!("b text", &SCRIPT[column.span.unwrap().range()]);
+                assert_eq!("b", &SCRIPT[column.name_span.unwrap().range()]);
+            }
+            _ => unreachable!(),
+        }
+        match &statements[3].statement {
+            CassandraStatement::Batch { statements, .. } => match &statements[1] {
+                CassandraStatement::Delete(delete) => {
+                    let relation = &delete.where_clause[0];
+                    assert_eq!("a = 1", &SCRIPT[relation.span.unwrap().range()]);
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            "/* two */",
+            &SCRIPT[statements[2].trailing_comments[0].span.unwrap().range()]
+        );
+        assert_eq!(
+            "-- done",
+            &SCRIPT[statements[6].trailing_comments[0].span.unwrap().range()]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let script = "SELECT a FROM t;\nSELECT FROM WHERE;\nUSE ks;";
+        let statements = read(script, 4);
+        assert_eq!(3, statements.len());
+        assert!(statements[1].has_error);
+        assert!(!statements[2].has_error);
+        assert_eq!("USE ks", &script[statements[2].span().range()]);
+    }
+
+    #[test]
+    fn test_dialect() {
+        let script = "USE ks;\nCREATE TABLE t (a int PRIMARY KEY) WITH COMPACT STORAGE;";
+        let mut reader = StatementReader::new(script.as_bytes());
+        reader.set_dialect(Dialect::Cassandra4_0);
+        let statements = reader.map(|x| x.unwrap()).collect::<Vec<_>>();
+        assert!(!statements[0].statement.has_error);
+        assert!(statements[1].statement.has_error);
+        assert!(matches!(
+            statements[1].errors[0].kind,
+            ParseErrorKind::Unsupported(Feature::CompactStorage, Dialect::Cassandra4_0)
+        ));
+    }
+
+    struct Failing<'a>(&'a [u8]);
+
+    impl Read for Failing<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, "failed"));
+            }
+            let length = self.0.read(buf)?;
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn test_read_error() {
+        let mut reader = StatementReader::from_read(Failing(b"USE ks; USE other"));
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(ParseErrorKind::Io(std::io::ErrorKind::Other), error.kind);
+        assert_eq!("the query could not be read: failed", error.to_string());
+        assert!(reader.next().is_none());
+
+        let mut reader = StatementReader::from_read(&b"USE ks;\nUSE \xff"[..]);
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().err().unwrap();
+        assert_eq!(
+            ParseErrorKind::Io(std::io::ErrorKind::InvalidData),
+            error.kind
+        );
+    }
+
+    #[test]
+    fn test_advance_position() {
+        let mut position = Position { line: 1, column: 1 };
+        position.advance("ab\ncé".as_bytes());
+        assert_eq!(Position { line: 2, column: 3 }, position);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::cassandra_ast::{CassandraParser, NodeFuncs};
+use crate::cassandra_statement::CassandraStatement;
+use crate::common::{FQName, Identifier, Span};
+use crate::cql_parser::CqlParser;
+use crate::parse_error::ParseError;
+
+/// The broad category of a statement.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum StatementCategory {
+    /// `SELECT`
+    Read,
+    /// `INSERT`, `UPDATE`, `DELETE`, `TRUNCATE`, batches and `APPLY BATCH`
+    Write,
+    /// statements that create, alter or drop keyspaces, tables, types, indexes, views, functions,
+    /// aggregates and triggers.
+    Ddl,
+    /// statements that manage roles, users and permissions.
+    Dcl,
+    /// `USE` and unrecognized statements.
+    Other,
+}
+
+/// A summary of a statement that is produced without building the `CassandraStatement`.
+/// Only the names the statement acts on are extracted from the tree.
+#[derive(PartialEq, Debug, Clone)]
+pub struct StatementSummary {
+    /// the kind of statement, this is the value `CassandraStatement::short_name` returns.
+    pub kind: &'static str,
+    /// the category of the statement.
+    pub category: StatementCategory,
+    /// the keyspace named by the statement, either the keyspace the statement acts on or the
+    /// keyspace of the object it acts on.  `None` if the statement uses the default keyspace.
+    pub keyspace: Option<Identifier>,
+    /// the table name, the value `CassandraStatement::get_table_name` returns.
+    pub table: Option<FQName>,
+    /// the number of bind markers (`?` or `:name`) in the statement.
+    pub bind_markers: usize,
+    /// true if the statement holds a syntax error.  Unlike `ParsedStatement::has_error` this
+    /// includes the errors the parser reads clauses from, such as a bind marker in a `LIMIT`.
+    pub has_error: bool,
+    /// the location of the statement in the query text.
+    pub span: Span,
+}
+
+impl StatementSummary {
+    /// the kind and category of a statement node kind.
+    pub(crate) fn kind_of(node_kind: &str) -> (&'static str, StatementCategory) {
+        match node_kind {
+            "alter_keyspace" => ("ALTER KEYSPACE", StatementCategory::Ddl),
+            "alter_materialized_view" => ("ALTER MATERIALIZED VIEW", StatementCategory::Ddl),
+            "alter_role" => ("ALTER ROLE", StatementCategory::Dcl),
+            "alter_table" => ("ALTER TABLE", StatementCategory::Ddl),
+            "alter_type" => ("ALTER TYPE", StatementCategory::Ddl),
+            "alter_user" => ("ALTER USER", StatementCategory::Dcl),
+            "apply_batch" => ("APPLY BATCH", StatementCategory::Write),
+            "create_aggregate" => ("CREATE AGGREGATE", StatementCategory::Ddl),
+            "create_function" => ("CREATE FUNCTION", StatementCategory::Ddl),
+            "create_index" => ("CREATE INDEX", StatementCategory::Ddl),
+            "create_keyspace" => ("CREATE KEYSPACE", StatementCategory::Ddl),
+            "create_materialized_view" => ("CREATE MATERIALIZED VIEW", StatementCategory::Ddl),
+            "create_role" => ("CREATE ROLE", StatementCategory::Dcl),
+            "create_table" => ("CREATE TABLE", StatementCategory::Ddl),
+            "create_trigger" => ("CREATE TRIGGER", StatementCategory::Ddl),
+            "create_type" => ("CREATE TYPE", StatementCategory::Ddl),
+            "create_user" => ("CREATE USER", StatementCategory::Dcl),
+            "delete_statement" => ("DELETE", StatementCategory::Write),
+            "drop_aggregate" => ("DROP AGGREGATE", StatementCategory::Ddl),
+            "drop_function" => ("DROP FUNCTION", StatementCategory::Ddl),
+            "drop_index" => ("DROP INDEX", StatementCategory::Ddl),
+            "drop_keyspace" => ("DROP KEYSPACE", StatementCategory::Ddl),
+            "drop_materialized_view" => ("DROP MATERIALIZED VIEW", StatementCategory::Ddl),
+            "drop_role" => ("DROP ROLE", StatementCategory::Dcl),
+            "drop_table" => ("DROP TABLE", StatementCategory::Ddl),
+            "drop_trigger" => ("DROP TRIGGER", StatementCategory::Ddl),
+            "drop_type" => ("DROP TYPE", StatementCategory::Ddl),
+            "drop_user" => ("DROP USER", StatementCategory::Dcl),
+            "grant" => ("GRANT", StatementCategory::Dcl),
+            "insert_statement" => ("INSERT", StatementCategory::Write),
+            "list_permissions" => ("LIST PERMISSIONS", StatementCategory::Dcl),
+            "list_roles" => ("LIST ROLES", StatementCategory::Dcl),
+            "revoke" => ("REVOKE", StatementCategory::Dcl),
+            "select_statement" => ("SELECT", StatementCategory::Read),
+            "truncate" => ("TRUNCATE", StatementCategory::Write),
+            "update" => ("UPDATE", StatementCategory::Write),
+            "use" => ("USE", StatementCategory::Other),
+            _ => ("UNRECOGNIZED CQL", StatementCategory::Other),
+        }
+    }
+
+    /// the kind and category of a statement that starts with a syntax error, from the keywords
+    /// the error starts with.
+    fn kind_of_error(text: &str) -> (&'static str, StatementCategory) {
+        let words: Vec<String> = text
+            .split_whitespace()
+            .take(3)
+            .map(|x| x.to_lowercase())
+            .collect();
+        let word = |i: usize| words.get(i).map(String::as_str).unwrap_or_default();
+        let node_kind = match word(0) {
+            "insert" | "select" | "delete" => format!("{}_statement", word(0)),
+            verb @ ("alter" | "create" | "drop") => match (word(1), word(2)) {
+                ("materialized", "view") => format!("{}_materialized_view", verb),
+                ("custom", "index") => format!("{}_index", verb),
+                (object, _) => format!("{}_{}", verb, object),
+            },
+            verb => verb.to_string(),
+        };
+        StatementSummary::kind_of(&node_kind)
+    }
+}
+
+/// summarize the statements in the query string using the thread's default `CqlParser`.
+/// Returns an error if tree-sitter could not produce a tree or if a statement without syntax
+/// errors does not have the shape the parser expects.
+pub fn classify(query: &str) -> Result<Vec<StatementSummary>, ParseError> {
+    CqlParser::with_default(|parser| parser.classify(query))?
+}
+
+/// summarize the statements of a tree produced by parsing the query string.
+/// Only the top level nodes of the statements are read, so a summary has an error when its
+/// statement holds a syntax error, even one that the parser reads from the text of the error.
+pub(crate) fn classify_tree(
+    tree: &tree_sitter::Tree,
+    source: &str,
+) -> Result<Vec<StatementSummary>, ParseError> {
+    CassandraStatement::statement_groups(tree, source)
+        .iter()
+        .map(|nodes| {
+            if CassandraStatement::is_batch(nodes) {
+                return Ok(CassandraParser::parse_batch_summary(nodes, source));
+            }
+            // a statement may be followed by the error that holds the rest of its clauses.
+            let mut summary = CassandraParser::parse_statement_summary(&nodes[0], source)?;
+            if nodes[0].is_error() {
+                let (kind, category) =
+                    StatementSummary::kind_of_error(NodeFuncs::as_str(&nodes[0], source)?);
+                summary.kind = kind;
+                summary.category = category;
+            }
+            for node in &nodes[1..] {
+                summary.bind_markers += NodeFuncs::count_bind_markers(node, source);
+                summary.has_error |= node.has_error();
+                summary.span.end_byte = node.end_byte();
+            }
+            Ok(summary)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_ast::CassandraAST;
+    use crate::common::{FQName, Identifier};
+    use crate::statement_summary::{classify, StatementCategory};
+
+    #[test]
+    fn test_matches_statement() {
+        let default = Identifier::parse("default_ks");
+        for query in [
+            "ALTER KEYSPACE ks WITH REPLICATION = {'class':'SimpleStrategy'}",
+            "ALTER MATERIALIZED VIEW ks.mv WITH comment = 'x'",
+            "ALTER ROLE r WITH PASSWORD = 'x'",
+            "ALTER TABLE ks.t ADD c int",
+            "ALTER TYPE ty ADD f int",
+            "APPLY BATCH",
+            "BEGIN BATCH INSERT INTO t (a) VALUES (?); DELETE FROM ks.t WHERE a = ?; APPLY BATCH",
+            "CREATE INDEX idx ON ks.t (c)",
+            "CREATE KEYSPACE ks WITH REPLICATION = {'class':'SimpleStrategy'}",
+            "CREATE MATERIALIZED VIEW ks.mv AS SELECT a FROM other.t WHERE a IS NOT NULL PRIMARY KEY (a)",
+            "CREATE TABLE ks.t (a int PRIMARY KEY)",
+            "CREATE TYPE ks.ty (f int)",
+            "CREATE USER u WITH PASSWORD 'x'",
+            "DELETE FROM ks.t WHERE a = ?",
+            "DROP INDEX ks.i",
+            "DROP KEYSPACE ks",
+            "DROP TRIGGER ks.tr ON other.t",
+            "DROP USER u",
+            "GRANT SELECT ON KEYSPACE ks TO r",
+            "INSERT INTO t (a) VALUES (:x)",
+            "LIST ROLES",
+            "SELECT a FROM \"Ks\".t WHERE a = ?",
+            "TRUNCATE ks.t",
+            "UPDATE ks.t SET a = 5 WHERE b = ?",
+            "USE ks",
+            "not a statement",
+        ] {
+            let ast = CassandraAST::new(query);
+            let summaries = classify(query).unwrap();
+            assert_eq!(1, summaries.len());
+            let statement = &ast.statements[0].statement;
+            let summary = &summaries[0];
+            assert_eq!(statement.short_name(), summary.kind, "{}", query);
+            assert_eq!(statement.get_table_name(), summary.table.as_ref(), "{}", query);
+            assert_eq!(
+                statement.get_keyspace(&default),
+                summary.keyspace.as_ref().unwrap_or(&default),
+                "{}",
+                query
+            );
+            assert_eq!(ast.has_error(), summary.has_error, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let query = "SELECT a FROM ks.t WHERE a = ? AND b = :c AND c < ?; INSERT INTO t (a, b) VALUES (?, ?); CREATE TABLE t (a int PRIMARY KEY); USE ks; GRANT SELECT ON KEYSPACE ks TO r";
+        let summaries = classify(query).unwrap();
+        assert_eq!(
+            vec![
+                StatementCategory::Read,
+                StatementCategory::Write,
+                StatementCategory::Ddl,
+                StatementCategory::Other,
+                StatementCategory::Dcl,
+            ],
+            summaries.iter().map(|x| x.category).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![3, 2, 0, 0, 0],
+            summaries.iter().map(|x| x.bind_markers).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(FQName::new("ks", "t")), summaries[0].table);
+        assert_eq!(Some(Identifier::parse("ks")), summaries[0].keyspace);
+        assert_eq!(Some(FQName::simple("t")), summaries[1].table);
+        assert_eq!(None, summaries[1].keyspace);
+        assert_eq!(Some(Identifier::parse("ks")), summaries[3].keyspace);
+        assert_eq!(
+            "INSERT INTO t (a, b) VALUES (?, ?)",
+            &query[summaries[1].span.range()]
+        );
+        assert!(summaries.iter().all(|x| !x.has_error));
+    }
+
+    #[test]
+    fn test_bind_markers_in_errors() {
+        // the bind markers the grammar does not accept are within errors.
+        for (query, bind_markers) in [
+            ("SELECT a FROM ks.t WHERE a = ? LIMIT ?", 2),
+            ("SELECT a FROM ks.t WHERE a = ? LIMIT :n", 2),
+            ("SELECT a FROM ks.t WHERE a IN ? AND b = :x", 2),
+            ("SELECT a FROM ks.t WHERE (a = ? OR b = ?)", 2),
+            (
+                "INSERT INTO t (a) VALUES (?) USING TTL ? AND TIMESTAMP :ts",
+                3,
+            ),
+            ("UPDATE t USING TTL ? SET a = :a WHERE b = ?", 3),
+            ("INSERT INTO t (a) VALUES ({'a':1})", 0),
+        ] {
+            let summaries = classify(query).unwrap();
+            assert_eq!(1, summaries.len(), "{}", query);
+            assert_eq!(
+                CassandraAST::new(query).statements[0]
+                    .statement
+                    .short_name(),
+                summaries[0].kind,
+                "{}",
+                query
+            );
+            assert_eq!(bind_markers, summaries[0].bind_markers, "{}", query);
+            // the summary covers the error that holds the rest of the statement.
+            assert_eq!(0..query.len(), summaries[0].span.range(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_kind_of_errors() {
+        // these statements start with a syntax error, the kind is read from their keywords.
+        for (query, kind, category) in [
+            (
+                "UPDATE t SET a = fn(b) WHERE c = 1",
+                "UPDATE",
+                StatementCategory::Write,
+            ),
+            (
+                "CREATE CUSTOM INDEX i ON t (c) USING 'StorageAttachedIndex'",
+                "CREATE INDEX",
+                StatementCategory::Ddl,
+            ),
+            ("zq", "UNRECOGNIZED CQL", StatementCategory::Other),
+        ] {
+            let summaries = classify(query).unwrap();
+            assert_eq!(1, summaries.len(), "{}", query);
+            assert_eq!(kind, summaries[0].kind, "{}", query);
+            assert_eq!(category, summaries[0].category, "{}", query);
+            assert!(summaries[0].has_error, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_summary_with_errors() {
+        let summaries = classify("SELECT a FROM ks.t WHERE").unwrap();
+        assert_eq!(2, summaries.len());
+        assert_eq!("SELECT", summaries[0].kind);
+        assert_eq!(Some(FQName::new("ks", "t")), summaries[0].table);
+        assert!(summaries[1].has_error);
+        assert_eq!("UNRECOGNIZED CQL", summaries[1].kind);
+        assert_eq!(StatementCategory::Other, summaries[1].category);
+    }
+}
@@ -1,13 +1,16 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, Operand, RelationElement, TtlTimestamp};
+use crate::common::{
+    eq_without_spans, move_spans, FQName, MoveSpans, Operand, RelationElement, Span, TtlTimestamp,
+};
 use crate::delete::IndexedColumn;
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
 /// data for `Update` statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Update {
-    /// if present then statement starts with BEGIN BATCH
+    /// if present then statement starts with BEGIN BATCH.  The statements of a batch that is
+    /// applied are held by `CassandraStatement::Batch` instead.
     pub begin_batch: Option<BeginBatch>,
     /// the table name to update
     pub table_name: FQName,
@@ -21,8 +24,20 @@ pub struct Update {
     pub if_clause: Vec<RelationElement>,
     /// if true and `if_clause` is NONE then  `IF EXISTS` is added to the statement
     pub if_exists: bool,
+    /// the location of the statement in the query text.
+    pub span: Option<Span>,
 }
 
+eq_without_spans!(Update {
+    begin_batch, table_name, using_ttl, assignments, where_clause, if_clause, if_exists;
+    span
+});
+
+move_spans!(Update {
+    table_name, assignments, where_clause, if_clause, span;
+    begin_batch, using_ttl, if_exists
+});
+
 impl Display for Update {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -49,7 +64,7 @@ impl Display for Update {
 }
 
 /// defines an assignment element comprising the column, the value, and an optional +/- value operator.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct AssignmentElement {
     /// the column to set the value for.
     pub name: IndexedColumn,
@@ -57,8 +72,22 @@ pub struct AssignmentElement {
     pub value: Operand,
     /// an optional +/- value
     pub operator: Option<AssignmentOperator>,
+    /// the location of the assignment in the query text.
+    pub span: Option<Span>,
+    /// the location of the value.
+    pub value_span: Option<Span>,
 }
 
+eq_without_spans!(AssignmentElement { name, value, operator; span, value_span });
+
+move_spans!(AssignmentElement {
+    name,
+    value,
+    operator,
+    span,
+    value_span
+});
+
 impl Display for AssignmentElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.operator {
@@ -75,6 +104,16 @@ pub enum AssignmentOperator {
     Minus(Operand),
 }
 
+impl MoveSpans for AssignmentOperator {
+    fn move_spans(&mut self, offset: isize) {
+        match self {
+            AssignmentOperator::Plus(operand) | AssignmentOperator::Minus(operand) => {
+                operand.move_spans(offset)
+            }
+        }
+    }
+}
+
 impl Display for AssignmentOperator {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {